@@ -0,0 +1,314 @@
+use std::fmt;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::graph::NodeId;
+
+mod file;
+
+pub use file::FileApprovalStore;
+
+const APPROVAL_DOMAIN: &[u8] = b"ADR-APPROVAL\0";
+
+/// Public key of a human allowed to approve gated nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApproverKey {
+    pub approver_id: String,
+    pub key: VerifyingKey,
+    /// Roles the approver acts in, e.g. `operator`, `safety_officer`.
+    pub roles: Vec<String>,
+}
+
+impl ApproverKey {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+
+    /// Whether the approver holds one of `roles`; an empty list admits
+    /// everyone.
+    pub fn has_any_role(&self, roles: &[String]) -> bool {
+        roles.is_empty() || roles.iter().any(|r| self.has_role(r))
+    }
+}
+
+/// How many distinct approvers a gate needs. Each must hold one of
+/// `roles`; an empty list admits any registered approver.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quorum {
+    pub count: usize,
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+impl Default for Quorum {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            roles: Vec::new(),
+        }
+    }
+}
+
+/// What an approval covers: one node of one graph version, in plans bound
+/// to one `cap_scope_hash`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalScope {
+    pub node: NodeId,
+    pub graph_version: String,
+    /// `ExecutionPlan::cap_scope_hash` of the plan the approval is for;
+    /// `None` only matches unbound plans.
+    pub cap_scope_hash: Option<String>,
+}
+
+/// Signed sign-off for a human-gated node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Approval {
+    pub approver_id: String,
+    pub scope: ApprovalScope,
+    /// Seconds since the Unix epoch.
+    pub issued_at: u64,
+    /// Seconds since the Unix epoch; the approval is void from then on.
+    pub expires_at: u64,
+    /// Ed25519 signature over `Approval::signing_bytes` (hex).
+    pub signature: String,
+}
+
+impl Approval {
+    pub fn sign(
+        scope: ApprovalScope,
+        approver_id: impl Into<String>,
+        issued_at: u64,
+        expires_at: u64,
+        key: &SigningKey,
+    ) -> Self {
+        let mut approval = Self {
+            approver_id: approver_id.into(),
+            scope,
+            issued_at,
+            expires_at,
+            signature: String::new(),
+        };
+        approval.signature = hex::encode(key.sign(&approval.signing_bytes()).to_bytes());
+        approval
+    }
+
+    /// Domain tag, approver id, graph version, node id (16 raw bytes),
+    /// cap scope hash as 0x00 for `None` or 0x01 + string, then issue and
+    /// expiry time as u64 BE. Strings are u64 BE length + UTF-8 bytes.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        fn put_str(buf: &mut Vec<u8>, s: &str) {
+            buf.extend_from_slice(&(s.len() as u64).to_be_bytes());
+            buf.extend_from_slice(s.as_bytes());
+        }
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(APPROVAL_DOMAIN);
+        put_str(&mut buf, &self.approver_id);
+        put_str(&mut buf, &self.scope.graph_version);
+        buf.extend_from_slice(self.scope.node.as_bytes());
+        match &self.scope.cap_scope_hash {
+            Some(hash) => {
+                buf.push(0x01);
+                put_str(&mut buf, hash);
+            }
+            None => buf.push(0x00),
+        }
+        buf.extend_from_slice(&self.issued_at.to_be_bytes());
+        buf.extend_from_slice(&self.expires_at.to_be_bytes());
+        buf
+    }
+
+    /// Checks signer and validity window at `now` (Unix time).
+    pub(crate) fn verify(
+        &self,
+        approvers: &[ApproverKey],
+        now: Duration,
+    ) -> Result<(), ApprovalRefusal> {
+        let approver = self.approver(approvers)?;
+
+        let bytes: [u8; 64] = hex::decode(&self.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or(ApprovalRefusal::BadSignature)?;
+        approver
+            .key
+            .verify(&self.signing_bytes(), &Signature::from_bytes(&bytes))
+            .map_err(|_| ApprovalRefusal::BadSignature)?;
+
+        self.check_window(now)
+    }
+
+    pub(crate) fn approver<'a>(
+        &self,
+        approvers: &'a [ApproverKey],
+    ) -> Result<&'a ApproverKey, ApprovalRefusal> {
+        approvers
+            .iter()
+            .find(|a| a.approver_id == self.approver_id)
+            .ok_or_else(|| ApprovalRefusal::UnknownApprover(self.approver_id.clone()))
+    }
+
+    pub(crate) fn check_window(&self, now: Duration) -> Result<(), ApprovalRefusal> {
+        let now = now.as_secs();
+        if now < self.issued_at {
+            return Err(ApprovalRefusal::NotYetValid);
+        }
+        if now >= self.expires_at {
+            return Err(ApprovalRefusal::Expired);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalRefusal {
+    UnknownApprover(String),
+    /// Signature malformed, by another key, or over other fields.
+    BadSignature,
+    NotYetValid,
+    Expired,
+    /// Signed for another node or graph version than the one waiting.
+    ScopeMismatch,
+    /// The approver holds none of the roles the gate accepts.
+    RoleMismatch {
+        required: Vec<String>,
+    },
+    /// This approver already approved the node; a quorum needs distinct
+    /// humans.
+    DuplicateApprover(String),
+    /// The gate's deadline passed before the approval arrived.
+    TimedOut,
+}
+
+/// Stable audit text: a refusal code, then the variant's data as
+/// `key=value`, like `EffectError`.
+impl fmt::Display for ApprovalRefusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApprovalRefusal::UnknownApprover(id) => write!(f, "unknown_approver approver={}", id),
+            ApprovalRefusal::BadSignature => f.write_str("bad_signature"),
+            ApprovalRefusal::NotYetValid => f.write_str("not_yet_valid"),
+            ApprovalRefusal::Expired => f.write_str("expired"),
+            ApprovalRefusal::ScopeMismatch => f.write_str("scope_mismatch"),
+            ApprovalRefusal::RoleMismatch { required } => {
+                write!(f, "role_mismatch required={}", required.join(","))
+            }
+            ApprovalRefusal::DuplicateApprover(id) => {
+                write!(f, "duplicate_approver approver={}", id)
+            }
+            ApprovalRefusal::TimedOut => f.write_str("timed_out"),
+        }
+    }
+}
+
+/// What happens to a gate nobody approved before its deadline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeoutAction {
+    /// The gate is closed and a plan waiting at it is dropped.
+    Reject,
+    /// The gate stays open for approvers holding `role`, with a new
+    /// deadline.
+    Escalate { role: String },
+    /// The runtime enters Stopping; a plan waiting at the gate is
+    /// compensated as on any Soft Stop.
+    SoftStop,
+}
+
+/// How long a gate may stay open, and what happens then.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalTimeout {
+    pub after: Duration,
+    pub action: TimeoutAction,
+}
+
+/// A human gate waiting for approval. Stored so that it outlives the
+/// process: after a restart the gate keeps its deadline and role.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingGate {
+    pub node: NodeId,
+    pub graph_version: String,
+    /// `cap_scope_hash` of the plan that opened the gate.
+    #[serde(default)]
+    pub cap_scope_hash: Option<String>,
+    /// Role an approver must hold once escalated; until then the
+    /// quorum's roles apply.
+    pub role: Option<String>,
+    #[serde(default)]
+    pub quorum: Quorum,
+    /// Seconds since the Unix epoch.
+    pub opened_at: u64,
+    /// Seconds since the Unix epoch; `None` waits indefinitely.
+    pub deadline: Option<u64>,
+}
+
+impl PendingGate {
+    /// Roles an approver may hold to count towards this gate.
+    pub fn accepted_roles(&self) -> &[String] {
+        match &self.role {
+            Some(role) => std::slice::from_ref(role),
+            None => &self.quorum.roles,
+        }
+    }
+}
+
+/// Durable state of open human gates, one per node.
+pub trait ApprovalStore: Send {
+    /// Open gates in the order they were opened.
+    fn pending(&self) -> Vec<PendingGate>;
+
+    /// Opens the gate for `gate.node`, or replaces it.
+    fn put(&mut self, gate: PendingGate) -> io::Result<()>;
+
+    /// Closes the gate for `node`; no-op when none is open.
+    fn remove(&mut self, node: &NodeId) -> io::Result<()>;
+
+    fn get(&self, node: &NodeId) -> Option<PendingGate> {
+        self.pending().into_iter().find(|g| &g.node == node)
+    }
+}
+
+/// In-memory store. Clones share the same gates, so a test can keep a
+/// handle while the runtime owns the boxed store.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryApprovalStore {
+    gates: Arc<Mutex<Vec<PendingGate>>>,
+}
+
+impl MemoryApprovalStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ApprovalStore for MemoryApprovalStore {
+    fn pending(&self) -> Vec<PendingGate> {
+        self.gates.lock().expect("approval store poisoned").clone()
+    }
+
+    fn put(&mut self, gate: PendingGate) -> io::Result<()> {
+        upsert(
+            &mut self.gates.lock().expect("approval store poisoned"),
+            gate,
+        );
+        Ok(())
+    }
+
+    fn remove(&mut self, node: &NodeId) -> io::Result<()> {
+        self.gates
+            .lock()
+            .expect("approval store poisoned")
+            .retain(|g| &g.node != node);
+        Ok(())
+    }
+}
+
+fn upsert(gates: &mut Vec<PendingGate>, gate: PendingGate) {
+    match gates.iter_mut().find(|g| g.node == gate.node) {
+        Some(slot) => *slot = gate,
+        None => gates.push(gate),
+    }
+}
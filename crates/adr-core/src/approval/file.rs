@@ -0,0 +1,79 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::{upsert, ApprovalStore, PendingGate};
+use crate::graph::NodeId;
+
+/// Approval store kept in a single JSON file.
+///
+/// Every change rewrites the file through a temporary sibling that is
+/// synced and then renamed over it, so a crash leaves either the old or
+/// the new set of gates, never a torn file.
+#[derive(Debug)]
+pub struct FileApprovalStore {
+    path: PathBuf,
+    gates: Vec<PendingGate>,
+}
+
+impl FileApprovalStore {
+    /// Opens `path`, starting empty if it does not exist yet.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let gates = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("approval store is unreadable: {}", e),
+                )
+            })?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self { path, gates })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn persist(&self, gates: &[PendingGate]) -> io::Result<()> {
+        let bytes = serde_json::to_vec_pretty(gates)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let tmp = self.path.with_extension("tmp");
+        let mut file = File::create(&tmp)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+impl ApprovalStore for FileApprovalStore {
+    fn pending(&self) -> Vec<PendingGate> {
+        self.gates.clone()
+    }
+
+    /// The in-memory state only changes once the file is written.
+    fn put(&mut self, gate: PendingGate) -> io::Result<()> {
+        let mut gates = self.gates.clone();
+        upsert(&mut gates, gate);
+        self.persist(&gates)?;
+        self.gates = gates;
+        Ok(())
+    }
+
+    fn remove(&mut self, node: &NodeId) -> io::Result<()> {
+        if !self.gates.iter().any(|g| &g.node == node) {
+            return Ok(());
+        }
+        let gates: Vec<PendingGate> = self
+            .gates
+            .iter()
+            .filter(|g| &g.node != node)
+            .cloned()
+            .collect();
+        self.persist(&gates)?;
+        self.gates = gates;
+        Ok(())
+    }
+}
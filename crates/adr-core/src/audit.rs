@@ -1,6 +1,36 @@
 use serde::{Deserialize, Serialize};
 use crate::graph::NodeId;
 use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+mod jsonl;
+mod merkle;
+mod signing;
+
+pub use jsonl::{verify_chain, JsonlAuditStore, TornTail};
+pub use merkle::{
+    merkle_root, InclusionProof, MerkleAnchorer, MerkleConfig, SealedBatch,
+    DEFAULT_MAX_BATCH_SIZE,
+};
+pub use signing::{
+    root_signing_bytes, verify_root_signatures, RequiredSigner, RootSignature, RootSigner,
+    SignatureRejection, SignatureRequirement, SignatureVerification, SigningKey, TrustedSigner,
+    VerifyingKey,
+};
+
+/// Original hash scheme: unseparated field concatenation, `Debug` kind names.
+/// Kept only so logs written before `hash_version` existed still verify.
+pub const HASH_VERSION_LEGACY: u32 = 1;
+
+/// Canonical, length-prefixed encoding (see `ActionLogEntry::canonical_bytes`).
+pub const HASH_VERSION_CANONICAL: u32 = 2;
+
+/// `HASH_VERSION_CANONICAL` plus the `trust` record.
+pub const HASH_VERSION_TRUST: u32 = 3;
+
+/// Scheme used for newly written entries.
+pub const CURRENT_HASH_VERSION: u32 = HASH_VERSION_TRUST;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ActionKind {
@@ -8,6 +38,39 @@ pub enum ActionKind {
     Execute,
     Freeze,
     Halt,
+    /// Merkle batch sealed; `detail` carries the root.
+    Anchor,
+    /// Operator resume attempt; `detail` carries operator, state, reason.
+    Resume,
+    /// Saga compensation of an executed node after Soft Stop;
+    /// `detail` carries the action.
+    Compensate,
+    /// Plan paused at, or continued after, a checkpoint node.
+    Checkpoint,
+    /// Signed approval for a human-gated node, accepted or refused;
+    /// `trust` carries the approver.
+    Approval,
+    /// Runtime entered Stopping (Soft Stop); `detail` carries the cause.
+    SoftStop,
+}
+
+impl ActionKind {
+    /// Stable numeric code used in the canonical hash encoding.
+    /// Codes are append-only: never renumber, never reuse.
+    pub fn code(&self) -> u8 {
+        match self {
+            ActionKind::Resolve => 1,
+            ActionKind::Execute => 2,
+            ActionKind::Freeze => 3,
+            ActionKind::Halt => 4,
+            ActionKind::Anchor => 5,
+            ActionKind::Resume => 6,
+            ActionKind::Compensate => 7,
+            ActionKind::Checkpoint => 8,
+            ActionKind::Approval => 9,
+            ActionKind::SoftStop => 10,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -19,18 +82,126 @@ pub struct Evidence {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ActionLogEntry {
+    /// Hash scheme `entry_hash` was computed with. Records written before
+    /// the field existed deserialize as `HASH_VERSION_LEGACY`.
+    #[serde(default = "legacy_hash_version")]
+    pub hash_version: u32,
+
     pub node_id: NodeId,
     pub kind: ActionKind,
     pub timestamp_utc: String,
     pub success: bool,
     pub evidence: Evidence,
+    /// Why the action failed or was blocked. `None` for successful actions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Who approved the action, for human-gated nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust: Option<TrustRecord>,
 
     pub prev_hash: Option<String>,
     pub entry_hash: String,
 }
 
+/// Human sign-off behind an action (`trust` in the SPEC Action Log).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TrustRecord {
+    /// Approver ids, in the order their approvals were accepted.
+    pub approved_by: Vec<String>,
+}
+
+fn legacy_hash_version() -> u32 {
+    HASH_VERSION_LEGACY
+}
+
 impl ActionLogEntry {
+    /// Hash of this entry under its own `hash_version`.
+    /// Returns an empty string for an unknown version, or for a `trust`
+    /// record under a version that does not cover it; neither matches a
+    /// stored hash.
     pub fn compute_entry_hash(&self) -> String {
+        match self.hash_version {
+            HASH_VERSION_LEGACY | HASH_VERSION_CANONICAL if self.trust.is_some() => String::new(),
+            HASH_VERSION_LEGACY => self.legacy_entry_hash(),
+            HASH_VERSION_CANONICAL | HASH_VERSION_TRUST => {
+                hex::encode(Sha256::digest(self.canonical_bytes()))
+            }
+            _ => String::new(),
+        }
+    }
+
+    pub fn with_computed_hash(mut self) -> Self {
+        self.entry_hash = self.compute_entry_hash();
+        self
+    }
+
+    /// Canonical encoding for `HASH_VERSION_CANONICAL` and
+    /// `HASH_VERSION_TRUST`.
+    ///
+    /// ```text
+    /// "ADR-AUDIT" 0x00
+    /// u32-be    hash_version
+    /// [16]      node_id (raw UUID bytes)
+    /// u8        kind code (ActionKind::code)
+    /// str       timestamp_utc
+    /// u8        success (0 / 1)
+    /// str       evidence.graph_version
+    /// str       evidence.policy_version
+    /// str       evidence.contract_hash
+    /// opt<str>  detail
+    /// opt<list> trust.approved_by  (HASH_VERSION_TRUST only)
+    /// opt<str>  prev_hash  (absent = GENESIS)
+    ///
+    /// str       = u64-be byte length, then UTF-8 bytes
+    /// opt<str>  = 0x00 if absent, 0x01 followed by str if present
+    /// list      = u64-be item count, then each item as str
+    /// ```
+    ///
+    /// Every variable-length field is length-prefixed, so no two distinct
+    /// entries share an encoding.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        fn put_str(out: &mut Vec<u8>, s: &str) {
+            out.extend_from_slice(&(s.len() as u64).to_be_bytes());
+            out.extend_from_slice(s.as_bytes());
+        }
+        fn put_opt(out: &mut Vec<u8>, s: Option<&str>) {
+            match s {
+                Some(s) => {
+                    out.push(1);
+                    put_str(out, s);
+                }
+                None => out.push(0),
+            }
+        }
+
+        let mut out = Vec::with_capacity(256);
+        out.extend_from_slice(b"ADR-AUDIT\0");
+        out.extend_from_slice(&self.hash_version.to_be_bytes());
+        out.extend_from_slice(self.node_id.as_bytes());
+        out.push(self.kind.code());
+        put_str(&mut out, &self.timestamp_utc);
+        out.push(u8::from(self.success));
+        put_str(&mut out, &self.evidence.graph_version);
+        put_str(&mut out, &self.evidence.policy_version);
+        put_str(&mut out, &self.evidence.contract_hash);
+        put_opt(&mut out, self.detail.as_deref());
+        if self.hash_version >= HASH_VERSION_TRUST {
+            match &self.trust {
+                Some(trust) => {
+                    out.push(1);
+                    out.extend_from_slice(&(trust.approved_by.len() as u64).to_be_bytes());
+                    for approver in &trust.approved_by {
+                        put_str(&mut out, approver);
+                    }
+                }
+                None => out.push(0),
+            }
+        }
+        put_opt(&mut out, self.prev_hash.as_deref());
+        out
+    }
+
+    fn legacy_entry_hash(&self) -> String {
         let mut hasher = Sha256::new();
 
         hasher.update(self.node_id.to_string().as_bytes());
@@ -42,6 +213,12 @@ impl ActionLogEntry {
         hasher.update(self.evidence.policy_version.as_bytes());
         hasher.update(self.evidence.contract_hash.as_bytes());
 
+        // Only hashed when present, so entries without detail keep the
+        // hashes they had before the field existed.
+        if let Some(detail) = &self.detail {
+            hasher.update(detail.as_bytes());
+        }
+
         match &self.prev_hash {
             Some(prev) => hasher.update(prev.as_bytes()),
             None => hasher.update(b"GENESIS"),
@@ -50,9 +227,216 @@ impl ActionLogEntry {
         let digest = hasher.finalize();
         hex::encode(digest)
     }
+}
 
-    pub fn with_computed_hash(mut self) -> Self {
-        self.entry_hash = self.compute_entry_hash();
-        self
+/// Destination for runtime-emitted audit entries.
+///
+/// The runtime links every new entry to `head()` before appending it,
+/// so a sink only has to store entries in order and remember the last hash.
+pub trait AuditSink: Send {
+    /// `entry_hash` of the most recent entry, `None` for an empty log.
+    fn head(&self) -> Option<String>;
+
+    fn append(&mut self, entry: ActionLogEntry) -> std::io::Result<()>;
+
+    /// Periodic housekeeping (e.g. time-based anchoring). Called by the
+    /// runtime before every record and by `AdrRuntime::tick_audit`.
+    fn tick(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    /// Closes the current segment, e.g. at a plan checkpoint, so that
+    /// everything logged so far is fixed. Sinks without segments do
+    /// nothing.
+    fn seal(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    /// Every entry written so far, in order. Used for diagnostic export;
+    /// sinks that cannot read back return `Unsupported`.
+    fn export(&self) -> std::io::Result<Vec<ActionLogEntry>> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "audit sink cannot export entries",
+        ))
+    }
+}
+
+/// In-memory sink. Clones share the same log, so a test can keep a handle
+/// while the runtime owns the boxed sink.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryAuditSink {
+    entries: Arc<Mutex<Vec<ActionLogEntry>>>,
+}
+
+impl MemoryAuditSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> Vec<ActionLogEntry> {
+        self.entries.lock().expect("audit log poisoned").clone()
+    }
+}
+
+impl AuditSink for MemoryAuditSink {
+    fn head(&self) -> Option<String> {
+        self.entries
+            .lock()
+            .expect("audit log poisoned")
+            .last()
+            .map(|e| e.entry_hash.clone())
+    }
+
+    fn append(&mut self, entry: ActionLogEntry) -> std::io::Result<()> {
+        self.entries.lock().expect("audit log poisoned").push(entry);
+        Ok(())
     }
-}
\ No newline at end of file
+
+    fn export(&self) -> std::io::Result<Vec<ActionLogEntry>> {
+        Ok(self.entries())
+    }
+}
+
+/// Why a chain failed verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainBreakReason {
+    /// The record could not be read or parsed.
+    Malformed(String),
+    /// `hash_version` is not known to this build.
+    UnsupportedHashVersion(u32),
+    /// `hash_version` is lower than the preceding entry's. Schemes only
+    /// move forward, so an older (weaker) one after a newer one is a
+    /// downgrade, not a legacy log.
+    HashVersionDowngrade { previous: u32, found: u32 },
+    /// `entry_hash` does not match the recomputed hash.
+    HashMismatch { stored: String, computed: String },
+    /// `prev_hash` does not point at the preceding entry.
+    /// `expected == None` means the entry should have been GENESIS.
+    PrevHashMismatch {
+        expected: Option<String>,
+        found: Option<String>,
+    },
+}
+
+/// First broken link found while walking a chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainBreak {
+    /// 0-based position of the offending entry.
+    pub index: usize,
+    pub reason: ChainBreakReason,
+}
+
+/// Verifies a sequence of entries from GENESIS onwards.
+/// Returns the number of verified entries or the first break.
+pub fn verify_entries<'a, I>(entries: I) -> Result<usize, ChainBreak>
+where
+    I: IntoIterator<Item = &'a ActionLogEntry>,
+{
+    let mut verifier = ChainVerifier::default();
+    for entry in entries {
+        verifier.check(entry)?;
+    }
+    Ok(verifier.count)
+}
+
+/// Incremental chain check shared by in-memory and file verification.
+#[derive(Debug, Default)]
+pub(crate) struct ChainVerifier {
+    count: usize,
+    head: Option<String>,
+    /// `hash_version` of the preceding entry.
+    version: Option<u32>,
+}
+
+impl ChainVerifier {
+    pub(crate) fn count(&self) -> usize {
+        self.count
+    }
+
+    pub(crate) fn check(&mut self, entry: &ActionLogEntry) -> Result<(), ChainBreak> {
+        let index = self.count;
+
+        if entry.prev_hash != self.head {
+            return Err(ChainBreak {
+                index,
+                reason: ChainBreakReason::PrevHashMismatch {
+                    expected: self.head.clone(),
+                    found: entry.prev_hash.clone(),
+                },
+            });
+        }
+
+        if !matches!(
+            entry.hash_version,
+            HASH_VERSION_LEGACY | HASH_VERSION_CANONICAL | HASH_VERSION_TRUST
+        ) {
+            return Err(ChainBreak {
+                index,
+                reason: ChainBreakReason::UnsupportedHashVersion(entry.hash_version),
+            });
+        }
+
+        if let Some(previous) = self.version.filter(|v| entry.hash_version < *v) {
+            return Err(ChainBreak {
+                index,
+                reason: ChainBreakReason::HashVersionDowngrade {
+                    previous,
+                    found: entry.hash_version,
+                },
+            });
+        }
+
+        let computed = entry.compute_entry_hash();
+        if computed != entry.entry_hash {
+            return Err(ChainBreak {
+                index,
+                reason: ChainBreakReason::HashMismatch {
+                    stored: entry.entry_hash.clone(),
+                    computed,
+                },
+            });
+        }
+
+        self.head = Some(entry.entry_hash.clone());
+        self.version = Some(entry.hash_version);
+        self.count += 1;
+        Ok(())
+    }
+}
+
+/// Current UTC time as `YYYY-MM-DDTHH:MM:SSZ`.
+pub fn timestamp_utc_now() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format_timestamp_utc(secs)
+}
+
+/// Formats seconds since the Unix epoch as `YYYY-MM-DDTHH:MM:SSZ`.
+pub fn format_timestamp_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days (H. Hinnant), proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        (rem % 3_600) / 60,
+        rem % 60
+    )
+}
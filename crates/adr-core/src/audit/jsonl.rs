@@ -0,0 +1,195 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::{ActionLogEntry, AuditSink, ChainBreak, ChainBreakReason, ChainVerifier};
+
+/// What `JsonlAuditStore::open_with` does with a last record that does
+/// not parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TornTail {
+    /// Fail to open; the file is left untouched for inspection.
+    Refuse,
+    /// Cut off a last record that is unreadable and lacks its trailing
+    /// newline, i.e. an append torn by a crash. Every append ends with a
+    /// newline, so a record that has one is never cut; it is corruption
+    /// and still refused. The number of bytes cut is reported by
+    /// `JsonlAuditStore::truncated_bytes`.
+    Truncate,
+}
+
+/// Append-only audit log, one JSON-encoded `ActionLogEntry` per line.
+///
+/// Every append is flushed to disk with `fsync` before it returns, so an
+/// entry the runtime reports as written survives a crash. Reopening an
+/// existing file resumes the chain from its last `entry_hash`. Blank
+/// lines are skipped by `open`, `export` and `verify_chain` alike.
+#[derive(Debug)]
+pub struct JsonlAuditStore {
+    path: PathBuf,
+    file: File,
+    head: Option<String>,
+    len: usize,
+    truncated_bytes: u64,
+}
+
+impl JsonlAuditStore {
+    /// Opens `path`, creating it if missing. Equivalent to `open_with`
+    /// and `TornTail::Refuse`.
+    ///
+    /// Only the last record is parsed to recover the chain head; use
+    /// `verify_chain` to check the whole file.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::open_with(path, TornTail::Refuse)
+    }
+
+    /// Opens `path`, creating it if missing, and handles an unreadable
+    /// last record as `torn_tail` says. A readable last record that lost
+    /// its newline gets one, so the next append starts a new line.
+    pub fn open_with(path: impl AsRef<Path>, torn_tail: TornTail) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+
+        let mut bytes = Vec::new();
+        (&file).read_to_end(&mut bytes)?;
+
+        // Start offset and content of every non-blank line.
+        let mut records = Vec::new();
+        let mut offset = 0;
+        for chunk in bytes.split_inclusive(|b| *b == b'\n') {
+            if !chunk.trim_ascii().is_empty() {
+                records.push((offset, chunk));
+            }
+            offset += chunk.len();
+        }
+
+        let mut truncated_bytes = 0;
+        if let Some(&(start, last)) = records.last() {
+            let torn = !last.ends_with(b"\n");
+            match Self::parse_last(last) {
+                Ok(_) if torn => {
+                    file.write_all(b"\n")?;
+                    file.sync_data()?;
+                }
+                Ok(_) => {}
+                Err(_) if torn && torn_tail == TornTail::Truncate => {
+                    file.set_len(start as u64)?;
+                    file.sync_data()?;
+                    truncated_bytes = last.len() as u64;
+                    records.pop();
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        let head = match records.last() {
+            Some((_, last)) => Some(Self::parse_last(last)?.entry_hash),
+            None => None,
+        };
+
+        Ok(Self {
+            path,
+            file,
+            head,
+            len: records.len(),
+            truncated_bytes,
+        })
+    }
+
+    fn parse_last(line: &[u8]) -> io::Result<ActionLogEntry> {
+        serde_json::from_slice(line).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("last audit record is unreadable: {}", e),
+            )
+        })
+    }
+
+    /// Bytes of a torn last record cut off by `open_with`; 0 if none.
+    pub fn truncated_bytes(&self) -> u64 {
+        self.truncated_bytes
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of entries in the log.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl AuditSink for JsonlAuditStore {
+    fn head(&self) -> Option<String> {
+        self.head.clone()
+    }
+
+    /// Refuses entries that do not link to the current head, so the file
+    /// can never contain a chain break written by this store.
+    fn append(&mut self, entry: ActionLogEntry) -> io::Result<()> {
+        if entry.prev_hash != self.head {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "entry does not link to the current audit head",
+            ));
+        }
+
+        let mut line = serde_json::to_string(&entry)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        line.push('\n');
+
+        self.file.write_all(line.as_bytes())?;
+        self.file.sync_data()?;
+
+        self.head = Some(entry.entry_hash);
+        self.len += 1;
+        Ok(())
+    }
+
+    fn export(&self) -> io::Result<Vec<ActionLogEntry>> {
+        BufReader::new(File::open(&self.path)?)
+            .lines()
+            .filter(|l| !matches!(l, Ok(l) if l.trim().is_empty()))
+            .map(|line| {
+                serde_json::from_str(&line?)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+            .collect()
+    }
+}
+
+/// Walks a JSONL audit log and checks every entry: the recomputed
+/// `entry_hash`, the `prev_hash` link to its predecessor, and that only the
+/// first entry is GENESIS (`prev_hash == None`).
+///
+/// Returns the number of verified entries, or the first broken index.
+pub fn verify_chain(path: impl AsRef<Path>) -> Result<usize, ChainBreak> {
+    let malformed = |index: usize, msg: String| ChainBreak {
+        index,
+        reason: ChainBreakReason::Malformed(msg),
+    };
+
+    let file = File::open(path.as_ref()).map_err(|e| malformed(0, e.to_string()))?;
+    let mut verifier = ChainVerifier::default();
+
+    for line in BufReader::new(file).lines() {
+        let index = verifier.count();
+        let line = line.map_err(|e| malformed(index, e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: ActionLogEntry =
+            serde_json::from_str(&line).map_err(|e| malformed(index, e.to_string()))?;
+        verifier.check(&entry)?;
+    }
+
+    Ok(verifier.count())
+}
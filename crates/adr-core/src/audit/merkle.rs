@@ -0,0 +1,420 @@
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{
+    timestamp_utc_now, ActionKind, ActionLogEntry, AuditSink, Evidence, CURRENT_HASH_VERSION,
+};
+use crate::clock::Clock;
+
+/// Upper bound on entries per batch when the policy does not set one.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 1024;
+
+/// How the audit log is cut into Merkle batches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleConfig {
+    /// Seal at least this often, with an empty batch if nothing happened
+    /// (heartbeat anchor), so gaps in the log are detectable.
+    pub anchor_interval: Duration,
+    /// Seal early once a batch holds this many entries.
+    pub max_batch_size: usize,
+}
+
+impl MerkleConfig {
+    pub fn new(anchor_interval: Duration) -> Self {
+        Self {
+            anchor_interval,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Tree (RFC 6962 / RFC 9162 layout)
+// leaf = SHA256(0x00 || entry_hash), node = SHA256(0x01 || left || right).
+// The 0x00/0x01 prefixes keep leaves and inner nodes from being confused.
+// -----------------------------------------------------------------------------
+
+type Hash = [u8; 32];
+
+fn leaf_hash(entry_hash: &str) -> Hash {
+    let mut h = Sha256::new();
+    h.update([0x00]);
+    h.update(entry_hash.as_bytes());
+    h.finalize().into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut h = Sha256::new();
+    h.update([0x01]);
+    h.update(left);
+    h.update(right);
+    h.finalize().into()
+}
+
+/// Largest power of two strictly smaller than `n` (n >= 2).
+fn split_point(n: usize) -> usize {
+    let mut k = 1;
+    while k << 1 < n {
+        k <<= 1;
+    }
+    k
+}
+
+fn subtree_root(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => Sha256::digest([]).into(),
+        1 => leaves[0],
+        n => {
+            let k = split_point(n);
+            node_hash(&subtree_root(&leaves[..k]), &subtree_root(&leaves[k..]))
+        }
+    }
+}
+
+fn audit_path(index: usize, leaves: &[Hash]) -> Vec<Hash> {
+    if leaves.len() <= 1 {
+        return Vec::new();
+    }
+    let k = split_point(leaves.len());
+    if index < k {
+        let mut path = audit_path(index, &leaves[..k]);
+        path.push(subtree_root(&leaves[k..]));
+        path
+    } else {
+        let mut path = audit_path(index - k, &leaves[k..]);
+        path.push(subtree_root(&leaves[..k]));
+        path
+    }
+}
+
+/// Merkle root over the given entry hashes, hex-encoded.
+/// The root of an empty batch is SHA256 of the empty string.
+pub fn merkle_root(entry_hashes: &[String]) -> String {
+    let leaves: Vec<Hash> = entry_hashes.iter().map(|h| leaf_hash(h)).collect();
+    hex::encode(subtree_root(&leaves))
+}
+
+/// A closed batch of consecutive audit entries and its Merkle root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedBatch {
+    pub index: u64,
+    /// `entry_hash` of every entry in the batch, in log order.
+    pub entry_hashes: Vec<String>,
+    pub root: String,
+    /// `entry_hash` of the anchor entry that records `root` in the chain.
+    pub anchor_hash: String,
+}
+
+impl SealedBatch {
+    /// Recomputes the root from `entry_hashes`.
+    pub fn verify(&self) -> bool {
+        merkle_root(&self.entry_hashes) == self.root
+    }
+
+    /// Inclusion proof for one entry, without revealing the other entries.
+    pub fn prove(&self, entry_hash: &str) -> Option<InclusionProof> {
+        let leaf_index = self.entry_hashes.iter().position(|h| h == entry_hash)?;
+        let leaves: Vec<Hash> = self.entry_hashes.iter().map(|h| leaf_hash(h)).collect();
+
+        Some(InclusionProof {
+            batch_index: self.index,
+            leaf_index,
+            tree_size: leaves.len(),
+            path: audit_path(leaf_index, &leaves)
+                .iter()
+                .map(hex::encode)
+                .collect(),
+            root: self.root.clone(),
+            anchor_hash: self.anchor_hash.clone(),
+        })
+    }
+}
+
+/// Proof that one entry is part of a sealed batch.
+///
+/// A verifier needs the entry, this proof and the anchor entry named by
+/// `anchor_hash`; the rest of the log stays private.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub batch_index: u64,
+    pub leaf_index: usize,
+    pub tree_size: usize,
+    /// Sibling hashes from the leaf up to the root (hex).
+    pub path: Vec<String>,
+    pub root: String,
+    pub anchor_hash: String,
+}
+
+impl InclusionProof {
+    /// Checks that `entry_hash` sits at `leaf_index` under `root`
+    /// (RFC 9162, section 2.1.3.2).
+    pub fn verify(&self, entry_hash: &str) -> bool {
+        if self.leaf_index >= self.tree_size {
+            return false;
+        }
+
+        let mut fnode = self.leaf_index;
+        let mut snode = self.tree_size - 1;
+        let mut r = leaf_hash(entry_hash);
+
+        for p in &self.path {
+            let Ok(bytes) = hex::decode(p) else {
+                return false;
+            };
+            let Ok(p): Result<Hash, _> = bytes.try_into() else {
+                return false;
+            };
+            if snode == 0 {
+                return false;
+            }
+            if fnode & 1 == 1 || fnode == snode {
+                r = node_hash(&p, &r);
+                while fnode & 1 == 0 && fnode != 0 {
+                    fnode >>= 1;
+                    snode >>= 1;
+                }
+            } else {
+                r = node_hash(&r, &p);
+            }
+            fnode >>= 1;
+            snode >>= 1;
+        }
+
+        snode == 0 && hex::encode(r) == self.root
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Anchoring sink
+// -----------------------------------------------------------------------------
+
+struct AnchorState {
+    inner: Box<dyn AuditSink>,
+    clock: Arc<dyn Clock>,
+    config: MerkleConfig,
+    pending: Vec<String>,
+    batches: Vec<SealedBatch>,
+    last_anchor: Duration,
+    last_evidence: Evidence,
+}
+
+/// Audit sink decorator that seals the linear chain into Merkle batches.
+///
+/// Entries pass through to the inner sink unchanged. A batch is sealed when
+/// it reaches `max_batch_size` or when `anchor_interval` has elapsed at the
+/// next `tick`. Sealing appends an `ActionKind::Anchor` entry carrying the
+/// root to the inner chain; that anchor is the first leaf of the next batch,
+/// so consecutive batches are linked.
+///
+/// A failed seal never fails the `append` that filled the batch: that entry
+/// is already written. The batch stays open and the next `tick` retries the
+/// seal and returns its error.
+///
+/// Clones share state: keep one handle to request proofs while the runtime
+/// owns the boxed sink.
+#[derive(Clone)]
+pub struct MerkleAnchorer {
+    state: Arc<Mutex<AnchorState>>,
+}
+
+impl MerkleAnchorer {
+    /// Starts batching at index 0. Use `open` for a sink that already holds
+    /// entries.
+    pub fn new(inner: Box<dyn AuditSink>, config: MerkleConfig, clock: Arc<dyn Clock>) -> Self {
+        let last_anchor = clock.now();
+        Self {
+            state: Arc::new(Mutex::new(AnchorState {
+                inner,
+                clock,
+                config,
+                pending: Vec::new(),
+                batches: Vec::new(),
+                last_anchor,
+                last_evidence: Evidence {
+                    graph_version: String::new(),
+                    policy_version: String::new(),
+                    contract_hash: String::new(),
+                },
+            })),
+        }
+    }
+
+    /// Resumes over a sink with existing entries, e.g. a reopened
+    /// `JsonlAuditStore`. Sealed batches are rebuilt from the anchor entries
+    /// in the log, so batch indexes continue, the next batch links to the
+    /// last anchor and earlier entries keep their proofs. Entries after the
+    /// last anchor form the open batch.
+    ///
+    /// Fails if the sink cannot export or an anchor does not match the
+    /// entries before it.
+    pub fn open(
+        inner: Box<dyn AuditSink>,
+        config: MerkleConfig,
+        clock: Arc<dyn Clock>,
+    ) -> io::Result<Self> {
+        let entries = inner.export()?;
+        let anchorer = Self::new(inner, config, clock);
+        {
+            let mut state = anchorer.lock();
+            for entry in entries {
+                if entry.kind == ActionKind::Anchor {
+                    state.restore_batch(&entry)?;
+                } else {
+                    state.pending.push(entry.entry_hash);
+                }
+                state.last_evidence = entry.evidence;
+            }
+        }
+        Ok(anchorer)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, AnchorState> {
+        self.state.lock().expect("merkle anchor state poisoned")
+    }
+
+    /// Seals the current batch immediately, even if it is empty.
+    pub fn seal_now(&self) -> io::Result<SealedBatch> {
+        self.lock().seal()
+    }
+
+    pub fn batches(&self) -> Vec<SealedBatch> {
+        self.lock().batches.clone()
+    }
+
+    /// Inclusion proof for an entry in any sealed batch.
+    /// `None` if the entry is unknown or its batch is still open.
+    pub fn prove(&self, entry_hash: &str) -> Option<InclusionProof> {
+        self.lock().batches.iter().find_map(|b| b.prove(entry_hash))
+    }
+}
+
+impl AnchorState {
+    fn seal(&mut self) -> io::Result<SealedBatch> {
+        let entry_hashes = std::mem::take(&mut self.pending);
+        let root = merkle_root(&entry_hashes);
+        let index = self.batches.len() as u64;
+
+        let anchor = ActionLogEntry {
+            hash_version: CURRENT_HASH_VERSION,
+            node_id: Uuid::nil(),
+            kind: ActionKind::Anchor,
+            timestamp_utc: timestamp_utc_now(),
+            success: true,
+            evidence: self.last_evidence.clone(),
+            detail: Some(format!(
+                "merkle_root={} batch={} size={}",
+                root,
+                index,
+                entry_hashes.len()
+            )),
+            trust: None,
+            prev_hash: self.inner.head(),
+            entry_hash: String::new(),
+        }
+        .with_computed_hash();
+
+        let anchor_hash = anchor.entry_hash.clone();
+        if let Err(e) = self.inner.append(anchor) {
+            self.pending = entry_hashes;
+            return Err(e);
+        }
+
+        let batch = SealedBatch {
+            index,
+            entry_hashes,
+            root,
+            anchor_hash: anchor_hash.clone(),
+        };
+        self.batches.push(batch.clone());
+        self.pending.push(anchor_hash);
+        self.last_anchor = self.clock.now();
+        Ok(batch)
+    }
+
+    /// Closes the open batch with an anchor read back from the log.
+    fn restore_batch(&mut self, anchor: &ActionLogEntry) -> io::Result<()> {
+        let entry_hashes = std::mem::take(&mut self.pending);
+        let root = merkle_root(&entry_hashes);
+        let index = self.batches.len() as u64;
+        let expected = format!(
+            "merkle_root={} batch={} size={}",
+            root,
+            index,
+            entry_hashes.len()
+        );
+        if anchor.detail.as_deref() != Some(expected.as_str()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "anchor {} does not match batch {}",
+                    anchor.entry_hash, index
+                ),
+            ));
+        }
+
+        self.batches.push(SealedBatch {
+            index,
+            entry_hashes,
+            root,
+            anchor_hash: anchor.entry_hash.clone(),
+        });
+        self.pending.push(anchor.entry_hash.clone());
+        Ok(())
+    }
+
+    fn batch_full(&self) -> bool {
+        self.pending.len() >= self.config.max_batch_size
+    }
+
+    fn anchor_due(&self) -> bool {
+        self.clock.now().saturating_sub(self.last_anchor) >= self.config.anchor_interval
+    }
+}
+
+impl AuditSink for MerkleAnchorer {
+    fn head(&self) -> Option<String> {
+        self.lock().inner.head()
+    }
+
+    fn export(&self) -> io::Result<Vec<ActionLogEntry>> {
+        self.lock().inner.export()
+    }
+
+    fn append(&mut self, entry: ActionLogEntry) -> io::Result<()> {
+        // No time-based sealing here: `entry.prev_hash` is already fixed,
+        // an anchor slipped in front of it would break the chain.
+        let mut state = self.lock();
+        let hash = entry.entry_hash.clone();
+        state.last_evidence = entry.evidence.clone();
+        state.inner.append(entry)?;
+        state.pending.push(hash);
+
+        // The entry is written; a failed seal leaves the batch full and is
+        // retried and reported by the next `tick`.
+        if state.batch_full() {
+            let _ = state.seal();
+        }
+        Ok(())
+    }
+
+    /// Seals the open batch, even if it is not full or due yet.
+    fn seal(&mut self) -> io::Result<()> {
+        self.seal_now().map(|_| ())
+    }
+
+    /// Emits a heartbeat anchor once `anchor_interval` has elapsed,
+    /// whether or not any action was logged, and retries the seal of a
+    /// batch that filled up but could not be sealed.
+    fn tick(&mut self) -> io::Result<()> {
+        let mut state = self.lock();
+        if state.batch_full() || state.anchor_due() {
+            state.seal()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use ed25519_dalek::{Signature, Signer, Verifier};
+use serde::{Deserialize, Serialize};
+
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+use super::SealedBatch;
+
+const ROOT_SIGNATURE_DOMAIN: &[u8] = b"ADR-MERKLE-ROOT\0";
+
+/// Bytes covered by a root signature: domain tag, batch index (u64 BE),
+/// then root and anchor hash, each as u64 BE length + UTF-8 bytes.
+/// Binding the anchor hash ties the signature to one position in the chain.
+pub fn root_signing_bytes(batch: &SealedBatch) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(
+        ROOT_SIGNATURE_DOMAIN.len() + 8 + 16 + batch.root.len() + batch.anchor_hash.len(),
+    );
+    buf.extend_from_slice(ROOT_SIGNATURE_DOMAIN);
+    buf.extend_from_slice(&batch.index.to_be_bytes());
+    for s in [&batch.root, &batch.anchor_hash] {
+        buf.extend_from_slice(&(s.len() as u64).to_be_bytes());
+        buf.extend_from_slice(s.as_bytes());
+    }
+    buf
+}
+
+/// One party's signature over a sealed batch root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RootSignature {
+    pub role: String,
+    pub signer_id: Option<String>,
+    pub batch_index: u64,
+    pub root: String,
+    /// Ed25519 signature over `root_signing_bytes` (hex).
+    pub signature: String,
+}
+
+/// Holder of a private key for one `MerkleSigner` role.
+pub struct RootSigner {
+    role: String,
+    id: Option<String>,
+    key: SigningKey,
+}
+
+impl RootSigner {
+    pub fn new(role: impl Into<String>, id: Option<String>, key: SigningKey) -> Self {
+        Self {
+            role: role.into(),
+            id,
+            key,
+        }
+    }
+
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.key.verifying_key()
+    }
+
+    /// The matching entry for a verifier's key registry.
+    pub fn trusted(&self) -> TrustedSigner {
+        TrustedSigner {
+            role: self.role.clone(),
+            id: self.id.clone(),
+            key: self.verifying_key(),
+        }
+    }
+
+    pub fn sign(&self, batch: &SealedBatch) -> RootSignature {
+        let sig = self.key.sign(&root_signing_bytes(batch));
+        RootSignature {
+            role: self.role.clone(),
+            signer_id: self.id.clone(),
+            batch_index: batch.index,
+            root: batch.root.clone(),
+            signature: hex::encode(sig.to_bytes()),
+        }
+    }
+}
+
+/// Public key the verifier accepts for a role.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedSigner {
+    pub role: String,
+    pub id: Option<String>,
+    pub key: VerifyingKey,
+}
+
+/// A role whose signature counts toward the threshold. With `id` set,
+/// only that identity may sign for the role (e.g. a named certifier).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequiredSigner {
+    pub role: String,
+    pub id: Option<String>,
+}
+
+/// How many of `signers` must have signed a root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureRequirement {
+    pub signers: Vec<RequiredSigner>,
+    pub threshold: usize,
+}
+
+impl SignatureRequirement {
+    /// Every listed signer must sign.
+    pub fn all(signers: Vec<RequiredSigner>) -> Self {
+        let threshold = signers.len();
+        Self { signers, threshold }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureRejection {
+    /// No trusted key for this role/identity.
+    UnknownSigner,
+    /// The signature names a different batch or root.
+    WrongRoot,
+    /// The role is required with another identity.
+    SignerIdMismatch {
+        expected: String,
+    },
+    /// A valid signature for this role was already counted.
+    DuplicateRole,
+    Malformed,
+    BadSignature,
+}
+
+/// Outcome of checking a set of signatures against a requirement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureVerification {
+    /// Roles with a valid signature, in the order presented.
+    pub signed_roles: Vec<String>,
+    /// Required roles without a valid signature.
+    pub missing_roles: Vec<String>,
+    pub rejected: Vec<(String, SignatureRejection)>,
+    pub satisfied: bool,
+}
+
+/// Checks `signatures` over `batch` against the trusted keys and reports
+/// which roles signed and whether `requirement` is met.
+pub fn verify_root_signatures(
+    batch: &SealedBatch,
+    signatures: &[RootSignature],
+    trusted: &[TrustedSigner],
+    requirement: &SignatureRequirement,
+) -> SignatureVerification {
+    let message = root_signing_bytes(batch);
+    let mut signed_roles: Vec<String> = Vec::new();
+    let mut rejected = Vec::new();
+
+    for sig in signatures {
+        match check_one(sig, batch, &message, trusted, requirement) {
+            Err(reason) => rejected.push((sig.role.clone(), reason)),
+            Ok(()) if signed_roles.contains(&sig.role) => {
+                rejected.push((sig.role.clone(), SignatureRejection::DuplicateRole))
+            }
+            Ok(()) => signed_roles.push(sig.role.clone()),
+        }
+    }
+
+    let missing_roles: Vec<String> = requirement
+        .signers
+        .iter()
+        .filter(|r| !signed_roles.contains(&r.role))
+        .map(|r| r.role.clone())
+        .collect();
+    let counted = requirement.signers.len() - missing_roles.len();
+
+    SignatureVerification {
+        signed_roles,
+        missing_roles,
+        rejected,
+        satisfied: counted >= requirement.threshold,
+    }
+}
+
+fn check_one(
+    sig: &RootSignature,
+    batch: &SealedBatch,
+    message: &[u8],
+    trusted: &[TrustedSigner],
+    requirement: &SignatureRequirement,
+) -> Result<(), SignatureRejection> {
+    if sig.batch_index != batch.index || sig.root != batch.root {
+        return Err(SignatureRejection::WrongRoot);
+    }
+
+    if let Some(expected) = requirement
+        .signers
+        .iter()
+        .find(|r| r.role == sig.role)
+        .and_then(|r| r.id.as_ref())
+    {
+        if sig.signer_id.as_ref() != Some(expected) {
+            return Err(SignatureRejection::SignerIdMismatch {
+                expected: expected.clone(),
+            });
+        }
+    }
+
+    let key = trusted
+        .iter()
+        .find(|t| t.role == sig.role && t.id == sig.signer_id)
+        .ok_or(SignatureRejection::UnknownSigner)?;
+
+    let bytes: [u8; 64] = hex::decode(&sig.signature)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or(SignatureRejection::Malformed)?;
+
+    key.key
+        .verify(message, &Signature::from_bytes(&bytes))
+        .map_err(|_| SignatureRejection::BadSignature)
+}
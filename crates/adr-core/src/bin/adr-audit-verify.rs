@@ -0,0 +1,25 @@
+//! Offline verification of a JSONL audit log.
+//!
+//! Usage: adr-audit-verify <audit.jsonl>
+//! Exit code 0 if the chain is intact, 1 if it is broken, 2 on usage error.
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let (Some(path), None) = (args.next(), args.next()) else {
+        eprintln!("usage: adr-audit-verify <audit.jsonl>");
+        return ExitCode::from(2);
+    };
+
+    match adr_core::verify_chain(&path) {
+        Ok(count) => {
+            println!("{}: OK ({} entries)", path, count);
+            ExitCode::SUCCESS
+        }
+        Err(brk) => {
+            println!("{}: BROKEN at entry {}: {:?}", path, brk.index, brk.reason);
+            ExitCode::from(1)
+        }
+    }
+}
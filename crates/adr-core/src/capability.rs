@@ -1,18 +1,119 @@
-use core::sync::atomic::{AtomicU64, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use sha2::{Digest, Sha256};
+
+use crate::capability_scope::{CapabilityScope, EffectTarget};
+
+/// SHA-256 binding the capabilities granted in `caps` to the policy and
+/// graph version a plan was resolved against, hex-encoded. The resolver
+/// stamps it on `ExecutionPlan::cap_scope_hash`; the runtime recomputes it
+/// before the plan starts.
+///
+/// Covers the granted mask, the classes granted without a scope and the
+/// scoped grants, so narrowing or widening a scope (`fs:/data/out` to
+/// `fs:/`) changes the hash as much as granting another class. Scopes are
+/// hashed sorted and without duplicates, in the form `parse` reads.
+pub fn cap_scope_hash(caps: &CapabilitySet, policy_hash: &str, graph_version: &str) -> String {
+    let mut scopes: Vec<String> = caps.scopes().iter().map(|s| s.to_string()).collect();
+    scopes.sort();
+    scopes.dedup();
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"adr-cap-scope-v2\n");
+    hasher.update(format!("{:016x}\n{:016x}\n", caps.mask(), caps.unscoped_mask()).as_bytes());
+    for scope in &scopes {
+        hasher.update(format!("{}\n{}\n", scope.len(), scope).as_bytes());
+    }
+    hasher.update(format!("{}\n{}", policy_hash, graph_version).as_bytes());
+    hex::encode(hasher.finalize())
+}
 
 /// Minimal deterministic CapabilitySet (Phase 8 skeleton).
+///
+/// `bits` holds every granted class and stays the fast path. A class
+/// granted through `allow_scoped` only reaches the targets of its scopes;
+/// one granted through `allow_mask` reaches all of them.
 #[derive(Debug)]
 pub struct CapabilitySet {
     bits: AtomicU64,
+    /// Classes granted without a scope.
+    unscoped: AtomicU64,
+    scopes: Mutex<Vec<CapabilityScope>>,
+    /// Set by a Hard Stop / Freeze; no grants until an authorized resume.
+    sealed: AtomicBool,
 }
 
 impl CapabilitySet {
     pub fn new() -> Self {
-        Self { bits: AtomicU64::new(0) }
+        Self {
+            bits: AtomicU64::new(0),
+            unscoped: AtomicU64::new(0),
+            scopes: Mutex::new(Vec::new()),
+            sealed: AtomicBool::new(false),
+        }
     }
 
-    pub fn allow_mask(&self, mask: u64) {
+    /// Grants `mask` for every target of its classes. Returns false,
+    /// granting nothing, while sealed.
+    pub fn allow_mask(&self, mask: u64) -> bool {
+        if self.sealed.load(Ordering::SeqCst) {
+            return false;
+        }
+        self.unscoped.fetch_or(mask, Ordering::SeqCst);
         self.bits.fetch_or(mask, Ordering::SeqCst);
+        // A seal that raced with the grant wins: the revoking side stores
+        // `sealed` before clearing `bits`, so clearing again is idempotent.
+        if self.sealed.load(Ordering::SeqCst) {
+            self.unscoped.store(0, Ordering::SeqCst);
+            self.bits.store(0, Ordering::SeqCst);
+            return false;
+        }
+        true
+    }
+
+    /// Grants the class of `scope`, limited to the targets inside it.
+    /// Returns false, granting nothing, while sealed.
+    pub fn allow_scoped(&self, scope: CapabilityScope) -> bool {
+        // Revocation takes the same lock before clearing, so a grant made
+        // under it is either cleared afterwards or sees the seal.
+        let mut scopes = self.scopes_guard();
+        if self.sealed.load(Ordering::SeqCst) {
+            return false;
+        }
+        self.bits.fetch_or(scope.class(), Ordering::SeqCst);
+        scopes.push(scope);
+        true
+    }
+
+    /// Scopes granted through `allow_scoped`.
+    pub fn scopes(&self) -> Vec<CapabilityScope> {
+        self.scopes_guard().clone()
+    }
+
+    /// Classes granted through `allow_mask`, reaching every target.
+    pub fn unscoped_mask(&self) -> u64 {
+        self.unscoped.load(Ordering::SeqCst)
+    }
+
+    /// True if the class of `target` is granted and `target` lies inside
+    /// one of its scopes, or the class was granted without a scope.
+    pub fn permits(&self, target: &EffectTarget) -> bool {
+        let class = target.class();
+        if !self.has_mask(class) {
+            return false;
+        }
+        if self.unscoped.load(Ordering::SeqCst) & class == class {
+            return true;
+        }
+        self.scopes_guard()
+            .iter()
+            .any(|scope| scope.permits(target))
+    }
+
+    /// All currently granted bits.
+    pub fn mask(&self) -> u64 {
+        self.bits.load(Ordering::SeqCst)
     }
 
     pub fn has_mask(&self, mask: u64) -> bool {
@@ -21,6 +122,36 @@ impl CapabilitySet {
 
     /// P8 requirement: atomic revocation.
     pub fn revoke_all(&self) {
+        let mut scopes = self.scopes_guard();
         self.bits.store(0, Ordering::SeqCst);
+        self.unscoped.store(0, Ordering::SeqCst);
+        scopes.clear();
+    }
+
+    /// Revokes everything and refuses further grants until `unseal`.
+    pub fn revoke_and_seal(&self) {
+        self.sealed.store(true, Ordering::SeqCst);
+        self.revoke_all();
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.sealed.load(Ordering::SeqCst)
+    }
+
+    /// Only the runtime's authorized resume lifts a seal.
+    pub(crate) fn unseal(&self) {
+        self.sealed.store(false, Ordering::SeqCst);
+    }
+
+    /// Revocation must not panic, and a poisoned lock still holds a
+    /// consistent list: every update is a single push or clear.
+    fn scopes_guard(&self) -> MutexGuard<'_, Vec<CapabilityScope>> {
+        self.scopes.lock().unwrap_or_else(|e| e.into_inner())
     }
-}
\ No newline at end of file
+}
+
+impl Default for CapabilitySet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,3 +1,5 @@
+use crate::graph::Effect;
+
 pub const CAP_FS_WRITE: u64 = 1 << 0;
 pub const CAP_NET_EXTERNAL: u64 = 1 << 1;
 pub const CAP_ACTUATOR_CONTROL: u64 = 1 << 2;
@@ -10,3 +12,14 @@ pub fn capability_name_to_mask(name: &str) -> Option<u64> {
         _ => None,
     }
 }
+
+/// Capability class an effect needs whatever its node declares; `None`
+/// for effects without side effects.
+pub fn effect_capability(effect: &Effect) -> Option<u64> {
+    match effect {
+        Effect::None => None,
+        Effect::FsWrite => Some(CAP_FS_WRITE),
+        Effect::NetExternal => Some(CAP_NET_EXTERNAL),
+        Effect::ActuatorControl => Some(CAP_ACTUATOR_CONTROL),
+    }
+}
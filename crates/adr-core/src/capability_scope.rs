@@ -0,0 +1,189 @@
+use std::fmt;
+use std::path::{Component, PathBuf};
+
+use crate::capability_ids::{
+    capability_name_to_mask, CAP_ACTUATOR_CONTROL, CAP_FS_WRITE, CAP_NET_EXTERNAL,
+};
+
+/// Part of a coarse capability class a grant is limited to, as in SPEC P1
+/// `capability fs("/data/out")` and `capability net("api.example.com")`.
+/// The class bit stays the fast path; a scope only narrows which concrete
+/// targets the class reaches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CapabilityScope {
+    /// `CAP_FS_WRITE` for this path and everything below it, compared
+    /// component by component.
+    PathPrefix(PathBuf),
+    /// `CAP_NET_EXTERNAL` for hosts matching `pattern`, on `port` or on
+    /// any port. `*.example.com` matches every subdomain but not
+    /// `example.com` itself, `*` matches every host.
+    Host { pattern: String, port: Option<u16> },
+    /// `CAP_ACTUATOR_CONTROL` for one actuator.
+    Actuator(String),
+}
+
+/// Concrete target of an effect, checked against granted scopes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EffectTarget {
+    /// Path where the effect lands, e.g. inside an `FsWriteHandler`'s
+    /// root; absolute for the built-in handler.
+    Path(PathBuf),
+    Host { host: String, port: Option<u16> },
+    Actuator(String),
+}
+
+impl CapabilityScope {
+    /// Parses `fs:/data/out`, `net:api.example.com`, `net:*.example.com:443`
+    /// or `actuator:valve-3`. Long class names (`fs_write:...`) are
+    /// accepted as well. Returns `None` for an unknown class or an empty
+    /// scope.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (class, scope) = text.split_once(':')?;
+        if scope.is_empty() {
+            return None;
+        }
+        let class = match class {
+            "fs" => CAP_FS_WRITE,
+            "net" => CAP_NET_EXTERNAL,
+            "actuator" => CAP_ACTUATOR_CONTROL,
+            other => capability_name_to_mask(other)?,
+        };
+        match class {
+            CAP_FS_WRITE => Some(CapabilityScope::PathPrefix(PathBuf::from(scope))),
+            CAP_NET_EXTERNAL => {
+                let (pattern, port) = split_port(scope);
+                (!pattern.is_empty()).then(|| CapabilityScope::Host {
+                    pattern: pattern.to_ascii_lowercase(),
+                    port,
+                })
+            }
+            _ => Some(CapabilityScope::Actuator(scope.to_string())),
+        }
+    }
+
+    /// Class bit the scope narrows.
+    pub fn class(&self) -> u64 {
+        match self {
+            CapabilityScope::PathPrefix(_) => CAP_FS_WRITE,
+            CapabilityScope::Host { .. } => CAP_NET_EXTERNAL,
+            CapabilityScope::Actuator(_) => CAP_ACTUATOR_CONTROL,
+        }
+    }
+
+    /// True if `target` lies inside this scope. Paths with `..` are never
+    /// inside a scope, so a prefix cannot be left by walking up.
+    pub fn permits(&self, target: &EffectTarget) -> bool {
+        match (self, target) {
+            (CapabilityScope::PathPrefix(prefix), EffectTarget::Path(path)) => {
+                !path.components().any(|c| c == Component::ParentDir) && path.starts_with(prefix)
+            }
+            (
+                CapabilityScope::Host { pattern, port },
+                EffectTarget::Host {
+                    host,
+                    port: target_port,
+                },
+            ) => host_matches(pattern, host) && port.is_none_or(|p| Some(p) == *target_port),
+            (CapabilityScope::Actuator(id), EffectTarget::Actuator(target)) => id == target,
+            _ => false,
+        }
+    }
+}
+
+/// The form `parse` reads: `fs:/data/out`, `net:*.example.com:443`,
+/// `actuator:valve-3`.
+impl fmt::Display for CapabilityScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapabilityScope::PathPrefix(prefix) => write!(f, "fs:{}", prefix.display()),
+            CapabilityScope::Host { pattern, port } => match (pattern.contains(':'), port) {
+                (true, Some(port)) => write!(f, "net:[{}]:{}", pattern, port),
+                (false, Some(port)) => write!(f, "net:{}:{}", pattern, port),
+                (_, None) => write!(f, "net:{}", pattern),
+            },
+            CapabilityScope::Actuator(id) => write!(f, "actuator:{}", id),
+        }
+    }
+}
+
+/// Same notation as a scope: `fs:/data/out/a.json`,
+/// `net:api.example.com:443`, `actuator:valve-3`.
+impl fmt::Display for EffectTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EffectTarget::Path(path) => write!(f, "fs:{}", path.display()),
+            EffectTarget::Host { host, port } => match (host.contains(':'), port) {
+                (true, Some(port)) => write!(f, "net:[{}]:{}", host, port),
+                (false, Some(port)) => write!(f, "net:{}:{}", host, port),
+                (_, None) => write!(f, "net:{}", host),
+            },
+            EffectTarget::Actuator(id) => write!(f, "actuator:{}", id),
+        }
+    }
+}
+
+impl EffectTarget {
+    /// Class bit a target of this kind needs.
+    pub fn class(&self) -> u64 {
+        match self {
+            EffectTarget::Path(_) => CAP_FS_WRITE,
+            EffectTarget::Host { .. } => CAP_NET_EXTERNAL,
+            EffectTarget::Actuator(_) => CAP_ACTUATOR_CONTROL,
+        }
+    }
+}
+
+/// Host and port of `url`. Without an explicit port, `http` and `https`
+/// get their default port. Returns `None` if there is no host.
+pub fn url_host(url: &str) -> Option<(&str, Option<u16>)> {
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, url),
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let authority = authority.rsplit('@').next().unwrap_or("");
+    let (host, port) = split_port(authority);
+    if host.is_empty() {
+        return None;
+    }
+    let port = port.or(match scheme {
+        Some(s) if s.eq_ignore_ascii_case("https") => Some(443),
+        Some(s) if s.eq_ignore_ascii_case("http") => Some(80),
+        _ => None,
+    });
+    Some((host, port))
+}
+
+/// Splits a trailing `:port`; bracketed IPv6 hosts keep their colons.
+pub fn split_port(authority: &str) -> (&str, Option<u16>) {
+    if let Some(rest) = authority.strip_prefix('[') {
+        return match rest.split_once(']') {
+            Some((host, tail)) => (host, tail.strip_prefix(':').and_then(|p| p.parse().ok())),
+            None => (authority, None),
+        };
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => match port.parse() {
+            Ok(port) => (host, Some(port)),
+            Err(_) => (authority, None),
+        },
+        _ => (authority, None),
+    }
+}
+
+fn host_matches(pattern: &str, host: &str) -> bool {
+    if host.is_empty() {
+        return false;
+    }
+    if pattern == "*" {
+        return true;
+    }
+    match pattern.strip_prefix("*.") {
+        Some(domain) => {
+            let (host, domain) = (host.as_bytes(), domain.as_bytes());
+            let split = host.len().saturating_sub(domain.len());
+            split > 1 && host[split - 1] == b'.' && host[split..].eq_ignore_ascii_case(domain)
+        }
+        None => host.eq_ignore_ascii_case(pattern),
+    }
+}
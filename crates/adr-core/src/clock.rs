@@ -0,0 +1,80 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Monotonic time source. Injected wherever the runtime measures intervals
+/// (audit anchors, watchdog), so tests can advance time without sleeping.
+pub trait Clock: Send + Sync {
+    /// Time elapsed since an arbitrary, fixed origin.
+    fn now(&self) -> Duration;
+}
+
+/// Calendar time, for instants a human signs or reads (approval issue
+/// and expiry times). Unlike `Clock` it may jump.
+pub trait WallClock: Send + Sync {
+    /// Time since the Unix epoch.
+    fn unix_now(&self) -> Duration;
+}
+
+/// Real monotonic clock.
+#[derive(Debug, Clone)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+impl WallClock for SystemClock {
+    fn unix_now(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+    }
+}
+
+/// Clock that only moves when told to. Clones share the same time.
+/// As a `WallClock` it starts at the Unix epoch.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.nanos.fetch_add(by.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
+
+impl WallClock for ManualClock {
+    fn unix_now(&self) -> Duration {
+        self.now()
+    }
+}
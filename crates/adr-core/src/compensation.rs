@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::effect_handler::EffectError;
+use crate::graph::{Node, NodeId};
+
+/// Undoes the effect of an already executed node
+/// (the `on_soft_stop` action of the node's stop handlers).
+///
+/// Registered on the runtime under an action name; plans refer to it
+/// through `ExecutionPlan::compensations`. Like effect handlers, compensators only run
+/// while the runtime is below Halted.
+pub trait Compensator: Send + Sync {
+    fn compensate(&self, node: &Node) -> Result<(), EffectError>;
+}
+
+/// Compensating action name per node of a plan. Nodes without an entry
+/// stay committed when a Soft Stop aborts the plan.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Compensations {
+    actions: HashMap<NodeId, String>,
+}
+
+impl Compensations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, node: NodeId, action: impl Into<String>) -> Option<String> {
+        self.actions.insert(node, action.into())
+    }
+
+    pub fn get(&self, node: &NodeId) -> Option<&str> {
+        self.actions.get(node).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+
+impl<S: Into<String>> FromIterator<(NodeId, S)> for Compensations {
+    fn from_iter<I: IntoIterator<Item = (NodeId, S)>>(iter: I) -> Self {
+        Self {
+            actions: iter.into_iter().map(|(n, a)| (n, a.into())).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompensationError {
+    /// The plan names an action no compensator is registered for.
+    UnknownAction(String),
+    Failed(EffectError),
+    /// The runtime escalated past Stopping before this node's turn.
+    Interrupted,
+}
+
+/// Stable text recorded in audit details, like `EffectError`.
+impl fmt::Display for CompensationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompensationError::UnknownAction(action) => {
+                write!(f, "unknown_action action={}", action)
+            }
+            CompensationError::Failed(e) => write!(f, "failed {}", e),
+            CompensationError::Interrupted => f.write_str("interrupted"),
+        }
+    }
+}
+
+/// Outcome of rolling back a plan interrupted by Soft Stop.
+/// Node lists are in compensation order, i.e. reverse execution order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompensationReport {
+    /// Executed nodes whose compensation succeeded.
+    pub rolled_back: Vec<NodeId>,
+    /// Executed nodes without a compensating action; their effects stand.
+    pub committed: Vec<NodeId>,
+    /// Executed nodes whose compensation was attempted or due but did not
+    /// succeed; their effects may stand.
+    pub failed: Vec<(NodeId, CompensationError)>,
+}
+
+impl CompensationReport {
+    /// True if every executed node was either rolled back or had nothing
+    /// to roll back.
+    pub fn is_clean(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
@@ -1,4 +1,283 @@
-/// Marker trait for future effect execution backends.
-/// The runtime decides whether an effect may run; the handler will later
-/// define how an approved effect is carried out.
-pub trait EffectHandler {}
+use std::fmt;
+use std::fs::{self, File};
+#[cfg(not(unix))]
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::capability_scope::{url_host, EffectTarget};
+use crate::graph::{Effect, Node};
+
+/// Input handed to an effect handler. Each variant carries what the
+/// corresponding `Effect` needs to be carried out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EffectPayload {
+    None,
+    FsWrite { path: PathBuf, contents: Vec<u8> },
+    NetExternal { url: String, body: Vec<u8> },
+    ActuatorCommand { actuator: String, command: String },
+}
+
+impl EffectPayload {
+    /// Concrete target the payload names, as given. Handlers may place it
+    /// elsewhere; see `EffectHandler::target`.
+    pub fn target(&self) -> Option<EffectTarget> {
+        match self {
+            EffectPayload::None => None,
+            EffectPayload::FsWrite { path, .. } => Some(EffectTarget::Path(path.clone())),
+            EffectPayload::NetExternal { url, .. } => {
+                // A URL without a host lies inside no scope.
+                let (host, port) = url_host(url).unwrap_or(("", None));
+                Some(EffectTarget::Host {
+                    host: host.to_string(),
+                    port,
+                })
+            }
+            EffectPayload::ActuatorCommand { actuator, .. } => {
+                Some(EffectTarget::Actuator(actuator.clone()))
+            }
+        }
+    }
+}
+
+/// Result of a performed effect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EffectOutput {
+    /// No handler was involved (pure node or no backend registered).
+    None,
+    FsWritten {
+        path: PathBuf,
+        bytes_written: u64,
+    },
+    NetResponse {
+        status: u16,
+        body: Vec<u8>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EffectError {
+    /// The payload does not belong to the effect the handler serves.
+    PayloadMismatch {
+        expected: Effect,
+    },
+    /// Target path would leave the handler's sandbox root.
+    PathEscapesSandbox(PathBuf),
+    Io(String),
+    /// The effect ran but its postcondition does not hold.
+    PostconditionFailed(String),
+    /// The handler panicked; carries the panic message if it had one.
+    HandlerPanicked(String),
+}
+
+/// Stable text recorded in audit details: an error code, then the
+/// variant's data as `key=value`.
+impl fmt::Display for EffectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EffectError::PayloadMismatch { expected } => {
+                write!(f, "payload_mismatch expected={}", expected.as_str())
+            }
+            EffectError::PathEscapesSandbox(path) => {
+                write!(f, "path_escapes_sandbox path={}", path.display())
+            }
+            EffectError::Io(e) => write!(f, "io error={}", e),
+            EffectError::PostconditionFailed(why) => write!(f, "postcondition_failed {}", why),
+            EffectError::HandlerPanicked(message) => {
+                write!(f, "handler_panicked message={}", message)
+            }
+        }
+    }
+}
+
+/// Execution backend for one kind of `Effect`.
+/// The runtime decides whether an effect may run; the handler defines
+/// how an approved effect is carried out. Handlers are only invoked after
+/// the kill switch, state, capability and exec-class gates have passed.
+pub trait EffectHandler: Send + Sync {
+    fn handle(&self, node: &Node, payload: &EffectPayload) -> Result<EffectOutput, EffectError>;
+
+    /// Where `payload` actually lands when this handler carries it out.
+    /// The runtime checks it against the granted capability scope before
+    /// `handle` runs. Defaults to the payload's own target.
+    fn target(&self, payload: &EffectPayload) -> Option<EffectTarget> {
+        payload.target()
+    }
+}
+
+/// Built-in `Effect::FsWrite` backend confined to a root directory.
+///
+/// Target paths must be relative and may not contain `..`, root or prefix
+/// components. Symlinks along the path are refused. On Unix the path is
+/// walked one directory handle at a time with `O_NOFOLLOW`, so a link
+/// swapped in after a check cannot redirect the write outside the root.
+#[derive(Debug)]
+pub struct FsWriteHandler {
+    root: PathBuf,
+}
+
+impl FsWriteHandler {
+    /// Creates the root directory if needed and pins it to its canonical path.
+    pub fn new(root: impl AsRef<Path>) -> std::io::Result<Self> {
+        fs::create_dir_all(root.as_ref())?;
+        Ok(Self {
+            root: fs::canonicalize(root.as_ref())?,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Lexical check: a non-empty path of normal components only.
+    fn check_relative(relative: &Path) -> Result<(), EffectError> {
+        if relative.as_os_str().is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(EffectError::PathEscapesSandbox(relative.to_path_buf()));
+        }
+        Ok(())
+    }
+
+    /// Tells a refused symlink from other I/O errors once an open failed.
+    /// Only classifies the error; the open itself never followed a link.
+    fn open_error(&self, relative: &Path, prefix: &Path, e: std::io::Error) -> EffectError {
+        let at = self.root.join(prefix);
+        if fs::symlink_metadata(&at).is_ok_and(|m| m.file_type().is_symlink()) {
+            EffectError::PathEscapesSandbox(relative.to_path_buf())
+        } else {
+            EffectError::Io(e.to_string())
+        }
+    }
+
+    /// Creates missing parents and opens the target for writing, each step
+    /// relative to the directory handle opened before it.
+    #[cfg(unix)]
+    fn open_target(&self, relative: &Path) -> Result<File, EffectError> {
+        use std::ffi::CString;
+        use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+        use std::os::unix::ffi::OsStrExt;
+
+        let owned = |fd: libc::c_int| {
+            if fd < 0 {
+                Err(std::io::Error::last_os_error())
+            } else {
+                // SAFETY: `fd` was just returned by a successful open and
+                // is owned by nobody else.
+                Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+            }
+        };
+        let c_string = |bytes: &[u8]| {
+            CString::new(bytes)
+                .map_err(|_| EffectError::PathEscapesSandbox(relative.to_path_buf()))
+        };
+        let dir_flags = libc::O_RDONLY | libc::O_DIRECTORY | libc::O_NOFOLLOW | libc::O_CLOEXEC;
+
+        let root = c_string(self.root.as_os_str().as_bytes())?;
+        // SAFETY: `root` is a valid NUL-terminated string for the call.
+        let mut dir = owned(unsafe { libc::open(root.as_ptr(), dir_flags) })
+            .map_err(|e| EffectError::Io(e.to_string()))?;
+
+        let names: Vec<&std::ffi::OsStr> = relative.iter().collect();
+        let (file_name, parents) = names.split_last().expect("checked non-empty");
+        let mut prefix = PathBuf::new();
+        for name in parents {
+            prefix.push(name);
+            let c_name = c_string(name.as_bytes())?;
+            // SAFETY: `dir` is an open directory and `c_name` a single
+            // NUL-terminated path component.
+            if unsafe { libc::mkdirat(dir.as_raw_fd(), c_name.as_ptr(), 0o755) } != 0 {
+                let e = std::io::Error::last_os_error();
+                if e.kind() != std::io::ErrorKind::AlreadyExists {
+                    return Err(EffectError::Io(e.to_string()));
+                }
+            }
+            // SAFETY: as above.
+            dir = owned(unsafe { libc::openat(dir.as_raw_fd(), c_name.as_ptr(), dir_flags) })
+                .map_err(|e| self.open_error(relative, &prefix, e))?;
+        }
+
+        let c_name = c_string(file_name.as_bytes())?;
+        let file_flags = libc::O_WRONLY
+            | libc::O_CREAT
+            | libc::O_TRUNC
+            | libc::O_NOFOLLOW
+            | libc::O_CLOEXEC;
+        // SAFETY: `dir` is an open directory and `c_name` a single
+        // NUL-terminated path component.
+        let fd = unsafe {
+            libc::openat(
+                dir.as_raw_fd(),
+                c_name.as_ptr(),
+                file_flags,
+                0o644 as libc::c_uint,
+            )
+        };
+        owned(fd)
+            .map(File::from)
+            .map_err(|e| self.open_error(relative, relative, e))
+    }
+
+    /// Without `openat`, the opened file is canonicalized and checked to
+    /// still lie inside the root before anything is written.
+    #[cfg(not(unix))]
+    fn open_target(&self, relative: &Path) -> Result<File, EffectError> {
+        let escapes = || EffectError::PathEscapesSandbox(relative.to_path_buf());
+        let io = |e: std::io::Error| EffectError::Io(e.to_string());
+
+        let mut current = self.root.clone();
+        for component in relative.components() {
+            current.push(component);
+            if fs::symlink_metadata(&current).is_ok_and(|m| m.file_type().is_symlink()) {
+                return Err(escapes());
+            }
+        }
+        if let Some(parent) = current.parent() {
+            fs::create_dir_all(parent).map_err(io)?;
+        }
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .open(&current)
+            .map_err(io)?;
+        if !fs::canonicalize(&current).map_err(io)?.starts_with(&self.root) {
+            return Err(escapes());
+        }
+        file.set_len(0).map_err(io)?;
+        Ok(file)
+    }
+}
+
+impl EffectHandler for FsWriteHandler {
+    fn handle(&self, _node: &Node, payload: &EffectPayload) -> Result<EffectOutput, EffectError> {
+        let EffectPayload::FsWrite { path, contents } = payload else {
+            return Err(EffectError::PayloadMismatch {
+                expected: Effect::FsWrite,
+            });
+        };
+
+        Self::check_relative(path)?;
+        let mut file = self.open_target(path)?;
+        let io = |e: std::io::Error| EffectError::Io(e.to_string());
+        file.write_all(contents).map_err(io)?;
+        file.sync_all().map_err(io)?;
+
+        Ok(EffectOutput::FsWritten {
+            path: self.root.join(path),
+            bytes_written: contents.len() as u64,
+        })
+    }
+
+    /// The payload path joined to the root, so SPEC-style absolute grants
+    /// like `fs:/data/out` match the file actually written.
+    fn target(&self, payload: &EffectPayload) -> Option<EffectTarget> {
+        match payload {
+            EffectPayload::FsWrite { path, .. } => Some(EffectTarget::Path(self.root.join(path))),
+            other => other.target(),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// Condition that moves the runtime to Frozen once the policy lists it
+/// (`AdrRuntime::with_freeze_triggers`). Inactive triggers are not
+/// detected at all.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FreezeTrigger {
+    /// A handler reported `EffectError::PostconditionFailed`.
+    ContractFailure,
+    /// A plan node uses a capability mask missing from
+    /// `ExecutionPlan::capabilities`.
+    UnverifiedCapabilityUse,
+    /// A plan drops or weakens a human gate that the previous plan for
+    /// the same graph, or a gate still open, demands.
+    TrustTierDowngradeAttempt,
+    /// Unexpected capability scope change detected: the plan's
+    /// `cap_scope_hash` does not match the runtime, or the granted mask
+    /// changed while the plan ran.
+    CapScopeHashMismatch,
+    /// A deterministic graph reaches external input, or a parallel run
+    /// would start nodes of one layer that depend on each other together.
+    DeterministicModeViolation,
+}
+
+impl FreezeTrigger {
+    /// Name as written in policy.yaml and in the audited freeze cause.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FreezeTrigger::ContractFailure => "contract_failure",
+            FreezeTrigger::UnverifiedCapabilityUse => "unverified_capability_use",
+            FreezeTrigger::TrustTierDowngradeAttempt => "trust_tier_downgrade_attempt",
+            FreezeTrigger::CapScopeHashMismatch => "cap_scope_hash_mismatch",
+            FreezeTrigger::DeterministicModeViolation => "deterministic_mode_violation",
+        }
+    }
+}
@@ -1,6 +1,12 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::approval::Quorum;
+use crate::compensation::Compensations;
+use crate::effect_handler::EffectPayload;
+
 pub type NodeId = Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -9,17 +15,44 @@ pub enum ExecClass {
     Orchestrated,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum Effect {
     None,
     FsWrite,
     NetExternal,
+    ActuatorControl,
+}
+
+impl Effect {
+    /// Name as written in policy.yaml (`effect_prefix`) and audit details.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Effect::None => "none",
+            Effect::FsWrite => "fs_write",
+            Effect::NetExternal => "net_external",
+            Effect::ActuatorControl => "actuator_control",
+        }
+    }
+
+    /// Inverse of `as_str`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Effect::None,
+            Effect::FsWrite,
+            Effect::NetExternal,
+            Effect::ActuatorControl,
+        ]
+        .into_iter()
+        .find(|effect| effect.as_str() == name)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphHeader {
     pub graph_version: String,
-    /// Reserved for future deterministic-mode enforcement.
+    /// No external input (`Effect::NetExternal`) and, on parallel runs,
+    /// no layer with a node depending on another node of the same layer;
+    /// enforced by `FreezeTrigger::DeterministicModeViolation`.
     pub deterministic_mode: bool,
 }
 
@@ -43,5 +76,54 @@ pub struct Graph {
 pub struct ExecutionPlan {
     pub nodes: Vec<NodeId>,
     pub parallel: Vec<Vec<NodeId>>,
+    /// Nodes the executor pauses before until the checkpoint is acknowledged.
     pub checkpoints: Vec<NodeId>,
+    /// Nodes that only run with enough valid signed approvals.
+    #[serde(default)]
+    pub human_gates: Vec<HumanGate>,
+    /// Capability masks the resolver verified the plan against.
+    #[serde(default)]
+    pub capabilities: Vec<u64>,
+    /// `cap_scope_hash` of the scope the plan was resolved in. `None`
+    /// leaves the plan unbound, e.g. for plans built by hand.
+    #[serde(default)]
+    pub cap_scope_hash: Option<String>,
+    /// Compensating action per node, used by `execute_plan` when a Soft
+    /// Stop aborts the plan.
+    #[serde(default)]
+    pub compensations: Compensations,
+    /// Input per node for its effect handler, e.g. path and contents of
+    /// an `FsWrite`. Gated like an `execute_node_with` payload; nodes
+    /// without an entry run with `EffectPayload::None`.
+    #[serde(default)]
+    pub payloads: HashMap<NodeId, EffectPayload>,
+}
+
+impl ExecutionPlan {
+    /// Payload `node` is dispatched with.
+    pub fn payload(&self, node: &NodeId) -> &EffectPayload {
+        static NONE: EffectPayload = EffectPayload::None;
+        self.payloads.get(node).unwrap_or(&NONE)
+    }
+
+    pub fn human_gate(&self, node: &NodeId) -> Option<&HumanGate> {
+        self.human_gates.iter().find(|g| &g.node == node)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HumanGate {
+    pub node: NodeId,
+    #[serde(default)]
+    pub quorum: Quorum,
+}
+
+impl HumanGate {
+    /// Gate passed by one approval from any registered approver.
+    pub fn new(node: NodeId) -> Self {
+        Self {
+            node,
+            quorum: Quorum::default(),
+        }
+    }
 }
@@ -1,11 +1,103 @@
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+mod composite;
+mod local_http;
+#[cfg(unix)]
+mod named_pipe;
+#[cfg(unix)]
+mod unix_signal;
+
+pub use composite::CompositeChannel;
+pub use local_http::LocalHttpChannel;
+#[cfg(unix)]
+pub use named_pipe::LocalNamedPipeChannel;
+#[cfg(unix)]
+pub use unix_signal::{UnixSignalChannel, DEFAULT_SIGNAL_MAP};
+
+/// Ordered by severity: `SoftStop < HardStop < Freeze`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub enum StopSignal {
     SoftStop,
     HardStop,
     Freeze,
 }
 
+impl StopSignal {
+    /// Parses the operator command names used by the pipe and HTTP
+    /// channels: `soft_stop`, `hard_stop`, `freeze`.
+    pub fn from_command(command: &str) -> Option<Self> {
+        match command {
+            "soft_stop" => Some(StopSignal::SoftStop),
+            "hard_stop" => Some(StopSignal::HardStop),
+            "freeze" => Some(StopSignal::Freeze),
+            _ => None,
+        }
+    }
+
+    /// Command name of the signal; the inverse of `from_command`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StopSignal::SoftStop => "soft_stop",
+            StopSignal::HardStop => "hard_stop",
+            StopSignal::Freeze => "freeze",
+        }
+    }
+}
+
 /// Core interface: platform adapters implement this.
 pub trait KillSwitchChannel: Send + Sync {
     fn poll(&self) -> Option<StopSignal>;
-}
\ No newline at end of file
+}
+
+/// One latch per stop level. Setting is a single atomic store, so it may
+/// happen from a signal handler or a listener thread; `poll` reports the
+/// most severe level set. Latches stay set until `clear`.
+/// Clones share the same latches.
+#[derive(Debug, Clone, Default)]
+pub struct StopLatch {
+    soft_stop: Arc<AtomicBool>,
+    hard_stop: Arc<AtomicBool>,
+    freeze: Arc<AtomicBool>,
+}
+
+impl StopLatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn flag(&self, stop: StopSignal) -> &Arc<AtomicBool> {
+        match stop {
+            StopSignal::SoftStop => &self.soft_stop,
+            StopSignal::HardStop => &self.hard_stop,
+            StopSignal::Freeze => &self.freeze,
+        }
+    }
+
+    pub fn set(&self, stop: StopSignal) {
+        self.flag(stop).store(true, Ordering::SeqCst);
+    }
+
+    pub fn clear(&self) {
+        for flag in [&self.soft_stop, &self.hard_stop, &self.freeze] {
+            flag.store(false, Ordering::SeqCst);
+        }
+    }
+
+    pub fn poll(&self) -> Option<StopSignal> {
+        [
+            StopSignal::Freeze,
+            StopSignal::HardStop,
+            StopSignal::SoftStop,
+        ]
+        .into_iter()
+        .find(|&stop| self.flag(stop).load(Ordering::SeqCst))
+    }
+}
+
+/// A bare latch is itself a channel: an in-process software trigger.
+impl KillSwitchChannel for StopLatch {
+    fn poll(&self) -> Option<StopSignal> {
+        StopLatch::poll(self)
+    }
+}
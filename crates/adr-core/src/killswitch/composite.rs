@@ -0,0 +1,37 @@
+use super::{KillSwitchChannel, StopSignal};
+
+/// Polls several channels and reports the most severe signal
+/// (Freeze > HardStop > SoftStop). Every child is polled on each call.
+#[derive(Default)]
+pub struct CompositeChannel {
+    children: Vec<Box<dyn KillSwitchChannel>>,
+}
+
+impl CompositeChannel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_channel(mut self, channel: Box<dyn KillSwitchChannel>) -> Self {
+        self.push(channel);
+        self
+    }
+
+    pub fn push(&mut self, channel: Box<dyn KillSwitchChannel>) {
+        self.children.push(channel);
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+impl KillSwitchChannel for CompositeChannel {
+    fn poll(&self) -> Option<StopSignal> {
+        self.children.iter().filter_map(|c| c.poll()).max()
+    }
+}
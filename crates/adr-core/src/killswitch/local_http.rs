@@ -0,0 +1,287 @@
+use std::io::{self, BufRead, BufReader, Read, Take, Write};
+use std::net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::{KillSwitchChannel, StopLatch, StopSignal};
+
+/// Time a client has to send the whole request head.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_HEADER_LINES: usize = 64;
+/// Upper bound on request line plus headers.
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+/// Upper bound on input read and discarded after the response.
+const MAX_DRAIN_BYTES: u64 = 64 * 1024;
+/// Connections served at once; further ones get 503.
+const MAX_CONNECTIONS: usize = 16;
+
+/// Kill switch fed over HTTP on the loopback interface
+/// (`local_http` in policy.yaml).
+///
+/// `POST /soft_stop`, `POST /hard_stop` or `POST /freeze`. The listener
+/// binds to 127.0.0.1 only and drops connections from non-loopback peers.
+/// With a token set, requests must carry `Authorization: Bearer <token>`.
+///
+/// Each connection is served on its own thread and must deliver its request
+/// head within a fixed deadline and size limit, so a stalled or oversized
+/// request cannot delay a stop command on another connection.
+///
+/// Responses: 200 accepted, 401 bad or missing token, 403 non-loopback
+/// peer, 404 unknown command, 405 other methods, 400 malformed request,
+/// 408 request head not received in time, 431 request head too large,
+/// 503 too many open connections.
+pub struct LocalHttpChannel {
+    addr: SocketAddr,
+    latch: StopLatch,
+    shutdown: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl LocalHttpChannel {
+    /// Binds 127.0.0.1:`port` (0 picks a free port) and starts listening.
+    pub fn bind(port: u16, token: Option<String>) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        let addr = listener.local_addr()?;
+
+        let latch = StopLatch::new();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let latch = latch.clone();
+            let shutdown = Arc::clone(&shutdown);
+            thread::Builder::new()
+                .name("adr-kill-http".to_string())
+                .spawn(move || serve(listener, token, &latch, &shutdown))?
+        };
+
+        Ok(Self {
+            addr,
+            latch,
+            shutdown,
+            listener: Some(handle),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Releases all latches; see `UnixSignalChannel::clear`.
+    pub fn clear(&self) {
+        self.latch.clear();
+    }
+}
+
+impl KillSwitchChannel for LocalHttpChannel {
+    fn poll(&self) -> Option<StopSignal> {
+        self.latch.poll()
+    }
+}
+
+impl Drop for LocalHttpChannel {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake the blocking accept.
+        let _ = TcpStream::connect(self.addr);
+        if let Some(handle) = self.listener.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn serve(listener: TcpListener, token: Option<String>, latch: &StopLatch, shutdown: &AtomicBool) {
+    let token = Arc::new(token);
+    let active = Arc::new(AtomicUsize::new(0));
+
+    for stream in listener.incoming() {
+        if shutdown.load(Ordering::SeqCst) {
+            return;
+        }
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+        let _ = stream.set_write_timeout(Some(REQUEST_TIMEOUT));
+
+        // A stalled client must not hold up the next stop command, so each
+        // connection gets its own thread; the count keeps that bounded.
+        if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            active.fetch_sub(1, Ordering::SeqCst);
+            let _ = respond(&mut stream, 503);
+            continue;
+        }
+        let connection = Connection {
+            active: Arc::clone(&active),
+        };
+        let token = Arc::clone(&token);
+        let latch = latch.clone();
+        // If the spawn fails, the closure and its guard are dropped and
+        // the client sees a closed connection.
+        let _ = thread::Builder::new()
+            .name("adr-kill-http-conn".to_string())
+            .spawn(move || {
+                let _connection = connection;
+                serve_connection(stream, token.as_deref(), &latch);
+            });
+    }
+}
+
+/// Counts a connection as active until its thread ends.
+struct Connection {
+    active: Arc<AtomicUsize>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn serve_connection(mut stream: TcpStream, token: Option<&str>, latch: &StopLatch) {
+    let peer_is_loopback = stream
+        .peer_addr()
+        .map(|p| p.ip().is_loopback())
+        .unwrap_or(false);
+    let deadline = Instant::now() + REQUEST_TIMEOUT;
+    let status = if peer_is_loopback {
+        handle(&stream, deadline, token, latch)
+    } else {
+        403
+    };
+    if respond(&mut stream, status).is_err() {
+        return;
+    }
+
+    // Read what the client still sends until it closes, so closing with
+    // unread input does not reset the connection before the response
+    // arrives. Bounded by the request deadline and `MAX_DRAIN_BYTES`.
+    let _ = stream.shutdown(Shutdown::Write);
+    let _ = io::copy(
+        &mut DeadlineReader::new(&stream, deadline).take(MAX_DRAIN_BYTES),
+        &mut io::sink(),
+    );
+}
+
+/// Reads from the stream until `deadline`, however slowly the client
+/// sends. Every read waits at most for the time that is left.
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl<'a> DeadlineReader<'a> {
+    fn new(stream: &'a TcpStream, deadline: Instant) -> Self {
+        Self { stream, deadline }
+    }
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buf)
+    }
+}
+
+type HeadReader<'a> = BufReader<Take<DeadlineReader<'a>>>;
+
+/// Reads one line of the request head; `Err` carries the status to answer
+/// with. Running into the size cap ends the input early, like EOF.
+fn read_head_line(reader: &mut HeadReader, line: &mut String) -> Result<usize, u16> {
+    match reader.read_line(line) {
+        Ok(_) if reader.get_ref().limit() == 0 && !line.ends_with('\n') => Err(431),
+        Ok(n) => Ok(n),
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+            ) =>
+        {
+            Err(408)
+        }
+        Err(_) => Err(400),
+    }
+}
+
+fn handle(stream: &TcpStream, deadline: Instant, token: Option<&str>, latch: &StopLatch) -> u16 {
+    let mut reader = BufReader::new(DeadlineReader::new(stream, deadline).take(MAX_REQUEST_BYTES));
+
+    let mut request_line = String::new();
+    if let Err(status) = read_head_line(&mut reader, &mut request_line) {
+        return status;
+    }
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return 400;
+    };
+
+    let mut bearer = None;
+    let mut complete = false;
+    for _ in 0..MAX_HEADER_LINES {
+        let mut line = String::new();
+        match read_head_line(&mut reader, &mut line) {
+            Ok(0) => break,
+            Ok(_) if line.trim().is_empty() => {
+                complete = true;
+                break;
+            }
+            Ok(_) => {}
+            Err(status) => return status,
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("authorization") {
+                bearer = value.trim().strip_prefix("Bearer ").map(str::to_string);
+            }
+        }
+    }
+    if !complete {
+        return if reader.get_ref().limit() == 0 {
+            431
+        } else {
+            400
+        };
+    }
+
+    if let Some(expected) = token {
+        let presented = bearer.as_deref().unwrap_or("");
+        if !constant_time_eq(presented.as_bytes(), expected.as_bytes()) {
+            return 401;
+        }
+    }
+    if method != "POST" {
+        return 405;
+    }
+    match StopSignal::from_command(target.trim_start_matches('/')) {
+        Some(stop) => {
+            latch.set(stop);
+            200
+        }
+        None => 404,
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn respond(stream: &mut TcpStream, status: u16) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        431 => "Request Header Fields Too Large",
+        _ => "Service Unavailable",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status, reason
+    )
+}
@@ -0,0 +1,167 @@
+use std::ffi::CString;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::{KillSwitchChannel, StopLatch, StopSignal};
+
+const READ_INTERVAL: Duration = Duration::from_millis(20);
+/// Longest line kept while waiting for its '\n'; commands are a few bytes.
+const MAX_LINE_BYTES: usize = 256;
+
+/// Kill switch fed through a FIFO (`local_named_pipe` in policy.yaml).
+///
+/// One command per line: `soft_stop`, `hard_stop` or `freeze`, e.g.
+/// `echo freeze > /run/adr/kill`. Unknown lines are ignored, and so are
+/// lines longer than 256 bytes. If reading the FIFO fails the channel
+/// can no longer deliver commands, so it latches `HardStop`.
+/// The FIFO is created with mode 0600 if missing and removed on drop
+/// if this channel created it. Commands latch like `UnixSignalChannel`.
+pub struct LocalNamedPipeChannel {
+    path: PathBuf,
+    created: bool,
+    latch: StopLatch,
+    shutdown: Arc<AtomicBool>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl LocalNamedPipeChannel {
+    /// Opens (or creates) the FIFO at `path` and starts the reader thread.
+    /// Refuses an existing path that is not a FIFO.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let created = match std::fs::symlink_metadata(&path) {
+            Ok(meta) if meta.file_type().is_fifo() => false,
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} exists and is not a FIFO", path.display()),
+                ))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                mkfifo(&path)?;
+                true
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Non-blocking, so opening does not wait for a writer and the
+        // reader thread can notice shutdown.
+        let fifo = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&path)?;
+
+        let latch = StopLatch::new();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let reader = {
+            let latch = latch.clone();
+            let shutdown = Arc::clone(&shutdown);
+            thread::Builder::new()
+                .name("adr-kill-pipe".to_string())
+                .spawn(move || read_commands(fifo, &latch, &shutdown))?
+        };
+
+        Ok(Self {
+            path,
+            created,
+            latch,
+            shutdown,
+            reader: Some(reader),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Releases all latches; see `UnixSignalChannel::clear`.
+    pub fn clear(&self) {
+        self.latch.clear();
+    }
+}
+
+impl KillSwitchChannel for LocalNamedPipeChannel {
+    fn poll(&self) -> Option<StopSignal> {
+        self.latch.poll()
+    }
+}
+
+impl Drop for LocalNamedPipeChannel {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+        if self.created {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn mkfifo(path: &Path) -> io::Result<()> {
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    // SAFETY: `c_path` is a valid NUL-terminated string for the call.
+    if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn read_commands(mut fifo: File, latch: &StopLatch, shutdown: &AtomicBool) {
+    let mut pending = Vec::new();
+    // Set while skipping the rest of an oversized line.
+    let mut overlong = false;
+    let mut buf = [0u8; 256];
+
+    while !shutdown.load(Ordering::SeqCst) {
+        match fifo.read(&mut buf) {
+            Ok(n) if n > 0 => {
+                pending.extend_from_slice(&buf[..n]);
+                while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = pending.drain(..=pos).collect();
+                    if !std::mem::take(&mut overlong) {
+                        apply(&line, latch);
+                    }
+                }
+                if pending.len() > MAX_LINE_BYTES {
+                    pending.clear();
+                    overlong = true;
+                }
+                continue;
+            }
+            // EOF: the last writer closed. A final command may lack '\n'.
+            Ok(_) => {
+                if !std::mem::take(&mut overlong) && !pending.is_empty() {
+                    apply(&pending, latch);
+                }
+                pending.clear();
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            // Fail safe: without the reader no stop can arrive.
+            Err(_) => {
+                latch.set(StopSignal::HardStop);
+                return;
+            }
+        }
+        thread::sleep(READ_INTERVAL);
+    }
+}
+
+fn apply(line: &[u8], latch: &StopLatch) {
+    if let Some(stop) = std::str::from_utf8(line)
+        .ok()
+        .and_then(|l| StopSignal::from_command(l.trim()))
+    {
+        latch.set(stop);
+    }
+}
@@ -0,0 +1,84 @@
+use std::io;
+use std::os::raw::c_int;
+use std::sync::Arc;
+
+use signal_hook::consts::signal::{SIGTERM, SIGUSR1, SIGUSR2};
+use signal_hook::SigId;
+
+use super::{KillSwitchChannel, StopLatch, StopSignal};
+
+/// SIGTERM → SoftStop, SIGUSR1 → HardStop, SIGUSR2 → Freeze.
+pub const DEFAULT_SIGNAL_MAP: [(c_int, StopSignal); 3] = [
+    (SIGTERM, StopSignal::SoftStop),
+    (SIGUSR1, StopSignal::HardStop),
+    (SIGUSR2, StopSignal::Freeze),
+];
+
+/// Kill switch fed by POSIX signals (`unix_signal` in policy.yaml).
+///
+/// The signal handler only stores `true` into a latch, which is
+/// async-signal-safe. `poll` reads the latches and reports the most severe
+/// one. Latches stay set until `clear`, so a stop cannot be lost between
+/// two polls.
+///
+/// Registering a signal replaces its default action (SIGTERM no longer
+/// terminates the process). Handlers are removed on drop.
+pub struct UnixSignalChannel {
+    latch: StopLatch,
+    registered: Vec<SigId>,
+}
+
+impl UnixSignalChannel {
+    /// Registers `DEFAULT_SIGNAL_MAP`.
+    pub fn new() -> io::Result<Self> {
+        Self::with_map(&DEFAULT_SIGNAL_MAP)
+    }
+
+    /// Registers each `(signal, stop)` pair. Fails for signals that must
+    /// not be caught (SIGKILL, SIGSTOP, SIGSEGV, ...), leaving nothing
+    /// registered.
+    pub fn with_map(map: &[(c_int, StopSignal)]) -> io::Result<Self> {
+        let mut channel = Self {
+            latch: StopLatch::new(),
+            registered: Vec::with_capacity(map.len()),
+        };
+
+        if let Some(&(signal, _)) = map
+            .iter()
+            .find(|(s, _)| signal_hook::consts::FORBIDDEN.contains(s))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("signal {} cannot be caught", signal),
+            ));
+        }
+
+        for &(signal, stop) in map {
+            let flag = Arc::clone(channel.latch.flag(stop));
+            // On error, `channel` is dropped and unregisters what it has.
+            let id = signal_hook::flag::register(signal, flag)?;
+            channel.registered.push(id);
+        }
+        Ok(channel)
+    }
+
+    /// Releases all latches. Operator action after the cause is resolved;
+    /// the runtime state itself is not changed.
+    pub fn clear(&self) {
+        self.latch.clear();
+    }
+}
+
+impl KillSwitchChannel for UnixSignalChannel {
+    fn poll(&self) -> Option<StopSignal> {
+        self.latch.poll()
+    }
+}
+
+impl Drop for UnixSignalChannel {
+    fn drop(&mut self) {
+        for id in self.registered.drain(..) {
+            signal_hook::low_level::unregister(id);
+        }
+    }
+}
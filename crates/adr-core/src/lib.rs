@@ -7,19 +7,46 @@ pub mod graph;
 pub mod runtime;
 pub mod audit;
 pub mod capability_ids;
+pub mod capability_scope;
 pub mod effect_handler;
+pub mod clock;
+pub mod watchdog;
+pub mod resume;
+pub mod compensation;
+pub mod approval;
+pub mod freeze;
 
 
-pub use runtime::{AdrRuntime, AdrRuntimeError};
+pub use runtime::{AdrRuntime, AdrRuntimeError, DiagnosticSnapshot, FrozenRuntime};
 pub use runtime_state::RuntimeState;
 
-pub use graph::{Effect, ExecClass, ExecutionPlan, Graph, GraphHeader, Node, NodeId};
-pub use audit::{ActionKind, ActionLogEntry, Evidence};
+pub use graph::{Effect, ExecClass, ExecutionPlan, Graph, GraphHeader, HumanGate, Node, NodeId};
+pub use audit::{
+    verify_chain, verify_entries, ActionKind, ActionLogEntry, AuditSink, ChainBreak,
+    ChainBreakReason, Evidence, InclusionProof, JsonlAuditStore, MemoryAuditSink, MerkleAnchorer,
+    MerkleConfig, SealedBatch, TornTail, TrustRecord, CURRENT_HASH_VERSION,
+};
+pub use clock::{Clock, ManualClock, SystemClock, WallClock};
+pub use watchdog::Watchdog;
+pub use resume::{OperatorKey, ResumeChallenge, ResumeRefusal, ResumeRequest};
+pub use compensation::{
+    CompensationError, CompensationReport, Compensations, Compensator,
+};
+pub use approval::{
+    Approval, ApprovalRefusal, ApprovalScope, ApprovalStore, ApprovalTimeout, ApproverKey,
+    FileApprovalStore, MemoryApprovalStore, PendingGate, Quorum, TimeoutAction,
+};
+pub use freeze::FreezeTrigger;
 
+pub use capability::cap_scope_hash;
+pub use capability_scope::{CapabilityScope, EffectTarget};
 pub use capability_ids::{
     capability_name_to_mask,
+    effect_capability,
     CAP_ACTUATOR_CONTROL,
     CAP_FS_WRITE,
     CAP_NET_EXTERNAL,
 };
-pub use effect_handler::EffectHandler;
+pub use effect_handler::{
+    EffectError, EffectHandler, EffectOutput, EffectPayload, FsWriteHandler,
+};
@@ -0,0 +1,128 @@
+use std::fmt;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+use crate::killswitch::StopSignal;
+use crate::runtime_state::RuntimeState;
+
+const RESUME_DOMAIN: &[u8] = b"ADR-RESUME\0";
+
+/// Public key of an operator allowed to resume a stopped runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorKey {
+    pub operator_id: String,
+    pub key: VerifyingKey,
+}
+
+/// What a resume signature commits to: the state being left and the
+/// current audit head. The head changes with every entry, so a signed
+/// request cannot be replayed for a later stop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumeChallenge {
+    pub from_state: RuntimeState,
+    pub audit_head: Option<String>,
+}
+
+impl ResumeChallenge {
+    /// Domain tag, state code, then operator id, reason and audit head,
+    /// each as u64 BE length + UTF-8 bytes (empty head for a new log).
+    pub fn signing_bytes(&self, operator_id: &str, reason: &str) -> Vec<u8> {
+        let state_code: u8 = match self.from_state {
+            RuntimeState::Running => 0,
+            RuntimeState::Stopping => 1,
+            RuntimeState::Halted => 2,
+            RuntimeState::Frozen => 3,
+        };
+        let head = self.audit_head.as_deref().unwrap_or("");
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(RESUME_DOMAIN);
+        buf.push(state_code);
+        for s in [operator_id, reason, head] {
+            buf.extend_from_slice(&(s.len() as u64).to_be_bytes());
+            buf.extend_from_slice(s.as_bytes());
+        }
+        buf
+    }
+}
+
+/// Operator request to bring a stopped runtime back to `Running`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumeRequest {
+    pub operator_id: String,
+    pub reason: String,
+    /// Ed25519 signature over `ResumeChallenge::signing_bytes` (hex).
+    pub signature: String,
+}
+
+impl ResumeRequest {
+    pub fn sign(
+        challenge: &ResumeChallenge,
+        operator_id: impl Into<String>,
+        reason: impl Into<String>,
+        key: &SigningKey,
+    ) -> Self {
+        let operator_id = operator_id.into();
+        let reason = reason.into();
+        let sig = key.sign(&challenge.signing_bytes(&operator_id, &reason));
+        Self {
+            operator_id,
+            reason,
+            signature: hex::encode(sig.to_bytes()),
+        }
+    }
+
+    pub(crate) fn verify(
+        &self,
+        challenge: &ResumeChallenge,
+        operators: &[OperatorKey],
+    ) -> Result<(), ResumeRefusal> {
+        if self.reason.trim().is_empty() {
+            return Err(ResumeRefusal::MissingReason);
+        }
+        let operator = operators
+            .iter()
+            .find(|o| o.operator_id == self.operator_id)
+            .ok_or_else(|| ResumeRefusal::UnknownOperator(self.operator_id.clone()))?;
+
+        let bytes: [u8; 64] = hex::decode(&self.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or(ResumeRefusal::BadCredential)?;
+        operator
+            .key
+            .verify(
+                &challenge.signing_bytes(&self.operator_id, &self.reason),
+                &Signature::from_bytes(&bytes),
+            )
+            .map_err(|_| ResumeRefusal::BadCredential)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResumeRefusal {
+    /// The runtime is already `Running`.
+    NotStopped,
+    /// A kill switch channel still reports this signal.
+    KillSwitchAsserting(StopSignal),
+    UnknownOperator(String),
+    /// Signature malformed, by another key, or for another challenge.
+    BadCredential,
+    MissingReason,
+}
+
+/// Stable audit text: a refusal code, then the variant's data as
+/// `key=value`, like `EffectError`.
+impl fmt::Display for ResumeRefusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResumeRefusal::NotStopped => f.write_str("not_stopped"),
+            ResumeRefusal::KillSwitchAsserting(sig) => {
+                write!(f, "kill_switch_asserting signal={}", sig.as_str())
+            }
+            ResumeRefusal::UnknownOperator(id) => write!(f, "unknown_operator operator={}", id),
+            ResumeRefusal::BadCredential => f.write_str("bad_credential"),
+            ResumeRefusal::MissingReason => f.write_str("missing_reason"),
+        }
+    }
+}
@@ -1,38 +1,464 @@
-use crate::capability::CapabilitySet;
-use crate::graph::{Effect, ExecClass, Graph, Node};
+use std::collections::HashMap;
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use crate::audit::{
+    timestamp_utc_now, ActionKind, ActionLogEntry, AuditSink, Evidence, MemoryAuditSink,
+    TrustRecord, CURRENT_HASH_VERSION,
+};
+use crate::approval::{
+    Approval, ApprovalRefusal, ApprovalScope, ApprovalStore, ApprovalTimeout, ApproverKey,
+    MemoryApprovalStore, PendingGate, Quorum, TimeoutAction,
+};
+use crate::capability::{cap_scope_hash, CapabilitySet};
+use crate::capability_ids::effect_capability;
+use crate::clock::{SystemClock, WallClock};
+use crate::compensation::{CompensationError, CompensationReport, Compensations, Compensator};
+use crate::effect_handler::{EffectError, EffectHandler, EffectOutput, EffectPayload};
+use crate::freeze::FreezeTrigger;
+use crate::graph::{Effect, ExecClass, ExecutionPlan, Graph, HumanGate, Node, NodeId};
 use crate::killswitch::{KillSwitchChannel, StopSignal};
+use crate::resume::{OperatorKey, ResumeChallenge, ResumeRefusal, ResumeRequest};
 use crate::runtime_state::RuntimeState;
+use crate::watchdog::Watchdog;
+
+mod frozen;
+
+pub use frozen::{DiagnosticSnapshot, FrozenRuntime};
 
 #[derive(Debug)]
 pub enum AdrRuntimeError {
     StateBlocked(RuntimeState),
     RealtimeViolation,
     CapabilityNotGranted(u64),
+    /// The effect's target is of a class the node does not declare, or
+    /// lies outside the granted scope of that class.
+    CapabilityOutOfScope { capability: u64, target: String },
     PlanNodeMissing(crate::graph::NodeId),
+    EffectFailed(EffectError),
+    /// The audit sink rejected an entry. Treated as fatal: an action that
+    /// cannot be logged must not be reported as done.
+    AuditWriteFailed(String),
+    /// States only move up (Running < Stopping < Halted < Frozen);
+    /// going back requires `resume`.
+    StateDowngrade { from: RuntimeState, to: RuntimeState },
+    ResumeRefused(ResumeRefusal),
+    /// Entering Frozen succeeded, writing the diagnostic bundle did not.
+    DiagnosticExportFailed(String),
+    /// Soft Stop aborted a plan; executed nodes were compensated in
+    /// reverse order as far as they had a compensating action.
+    SoftStopAborted(CompensationReport),
+    /// The plan paused before this checkpoint node; continue it with
+    /// `acknowledge_checkpoint`.
+    CheckpointReached(NodeId),
+    /// A plan is paused at this checkpoint; nothing else may run as part
+    /// of a plan until it is acknowledged.
+    CheckpointPending(NodeId),
+    NoPendingCheckpoint,
+    /// The plan runs this node without the checkpoint the policy demands
+    /// before its effect.
+    CheckpointMissing(NodeId),
+    /// The plan paused before this human-gated node; continue it with
+    /// `submit_approval`.
+    ApprovalRequired(NodeId),
+    /// A plan waits for an approval of this node; nothing else may run
+    /// as part of a plan until one is accepted.
+    ApprovalPending(NodeId),
+    ApprovalRefused(ApprovalRefusal),
+    /// The approval store could not record a gate change. Treated like
+    /// an audit failure: an unrecorded gate must not be relied on.
+    ApprovalStoreFailed(String),
+    /// An active freeze trigger fired and the runtime is now Frozen; the
+    /// audited Freeze entry carries the reason.
+    FreezeTriggered(FreezeTrigger),
+    /// The plan was resolved against another capability scope, policy or
+    /// graph version than the runtime now has; resolve it again.
+    CapScopeMismatch { expected: String, actual: String },
 }
 
+/// Stable text recorded as the audit detail of a failed attempt: an error
+/// code, then the variant's data as `key=value`. Unlike `Debug` it does
+/// not follow renames in the code, so hashed entries keep their content.
+impl fmt::Display for AdrRuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use AdrRuntimeError::*;
+        match self {
+            StateBlocked(state) => write!(f, "state_blocked state={}", state.as_str()),
+            RealtimeViolation => f.write_str("realtime_violation"),
+            CapabilityNotGranted(mask) => {
+                write!(f, "capability_not_granted capability={:#x}", mask)
+            }
+            CapabilityOutOfScope { capability, target } => write!(
+                f,
+                "capability_out_of_scope capability={:#x} target={}",
+                capability, target
+            ),
+            PlanNodeMissing(node) => write!(f, "plan_node_missing node={}", node),
+            EffectFailed(e) => write!(f, "effect_failed {}", e),
+            AuditWriteFailed(e) => write!(f, "audit_write_failed error={}", e),
+            StateDowngrade { from, to } => write!(
+                f,
+                "state_downgrade from={} to={}",
+                from.as_str(),
+                to.as_str()
+            ),
+            ResumeRefused(refusal) => write!(f, "resume_refused {}", refusal),
+            DiagnosticExportFailed(e) => write!(f, "diagnostic_export_failed error={}", e),
+            SoftStopAborted(report) => write!(
+                f,
+                "soft_stop_aborted rolled_back={} committed={} failed={}",
+                report.rolled_back.len(),
+                report.committed.len(),
+                report.failed.len()
+            ),
+            CheckpointReached(node) => write!(f, "checkpoint_reached node={}", node),
+            CheckpointPending(node) => write!(f, "checkpoint_pending node={}", node),
+            NoPendingCheckpoint => f.write_str("no_pending_checkpoint"),
+            CheckpointMissing(node) => write!(f, "checkpoint_missing node={}", node),
+            ApprovalRequired(node) => write!(f, "approval_required node={}", node),
+            ApprovalPending(node) => write!(f, "approval_pending node={}", node),
+            ApprovalRefused(refusal) => write!(f, "approval_refused {}", refusal),
+            ApprovalStoreFailed(e) => write!(f, "approval_store_failed error={}", e),
+            FreezeTriggered(trigger) => {
+                write!(f, "freeze_triggered trigger={}", trigger.as_str())
+            }
+            CapScopeMismatch { expected, actual } => write!(
+                f,
+                "cap_scope_mismatch expected={} actual={}",
+                expected, actual
+            ),
+        }
+    }
+}
 
 pub struct AdrRuntime<C: KillSwitchChannel> {
     state: RuntimeState,
     kill: C,
     caps: CapabilitySet,
+    handlers: HashMap<Effect, Box<dyn EffectHandler>>,
+    /// Compensating actions by name, see `Compensations`.
+    compensators: HashMap<String, Box<dyn Compensator>>,
+    audit: Box<dyn AuditSink>,
+    /// Evidence stamped on every emitted entry.
+    evidence: Evidence,
+    watchdog: Option<Watchdog>,
+    /// Operators allowed to sign a resume.
+    operators: Vec<OperatorKey>,
+    last_plan: Option<ExecutionPlan>,
+    /// Capabilities held when the last Hard Stop / Freeze revoked them.
+    caps_at_stop: Option<u64>,
+    /// Where a bundle is written automatically on Freeze.
+    diagnostic_dir: Option<PathBuf>,
+    /// Plan waiting at a checkpoint or for an approval.
+    paused: Option<PausedPlan>,
+    /// Humans allowed to sign approvals for gated nodes.
+    approvers: Vec<ApproverKey>,
+    /// Judges approval issue and expiry times.
+    wall_clock: Arc<dyn WallClock>,
+    /// Accepted approvals not yet used, by gated node.
+    approvals: HashMap<NodeId, Vec<Approval>>,
+    /// Open gates, kept across restarts by a durable store.
+    approval_store: Box<dyn ApprovalStore>,
+    /// Deadline and timeout action for newly opened gates.
+    approval_timeout: Option<ApprovalTimeout>,
+    /// Detectors that freeze the runtime when they fire.
+    freeze_triggers: Vec<FreezeTrigger>,
+    /// Hash of the active policy, bound into `cap_scope_hash`.
+    policy_hash: String,
+    /// Effects a plan must place a checkpoint before.
+    checkpoint_before: Vec<Effect>,
+}
+
+/// Progress of one plan execution.
+struct PlanRun {
+    plan: ExecutionPlan,
+    compensations: Compensations,
+    /// `None` runs `plan.nodes` in order, `Some(n)` runs `plan.parallel`.
+    workers: Option<usize>,
+    /// Next index into `plan.nodes` or the layers.
+    next: usize,
+    executed: Vec<NodeId>,
+    /// Checkpoints of this run that have been acknowledged.
+    acknowledged: Vec<NodeId>,
+    /// Granted capability mask when the run started.
+    scope: u64,
+    /// `cap_scope_hash` of the grants when the run started, so a scope
+    /// narrowed or widened within an unchanged mask is noticed too.
+    scope_hash: String,
+}
+
+impl PlanRun {
+    fn awaits_ack(&self, node: &NodeId) -> bool {
+        self.plan.checkpoints.contains(node) && !self.acknowledged.contains(node)
+    }
+}
+
+struct PausedPlan {
+    run: PlanRun,
+    graph: Graph,
+    at: PauseReason,
+}
+
+#[derive(Clone, Copy)]
+enum PauseReason {
+    Checkpoint(NodeId),
+    Approval(NodeId),
 }
 
+/// Proof that side effects may run. Only `effect_permit` creates one and
+/// never in Halted or Frozen, and handler dispatch takes it by value, so
+/// no `EffectHandler` is reachable from a stopped runtime.
+struct EffectPermit(());
+
 impl<C: KillSwitchChannel> AdrRuntime<C> {
 	pub fn new(kill: C) -> Self {
 		Self {
 			state: RuntimeState::Running,
 			kill,
 			caps: CapabilitySet::new(),
+			handlers: HashMap::new(),
+			compensators: HashMap::new(),
+			audit: Box::new(MemoryAuditSink::new()),
+			evidence: Evidence {
+				graph_version: String::new(),
+				policy_version: String::new(),
+				contract_hash: String::new(),
+			},
+			watchdog: None,
+			operators: Vec::new(),
+			last_plan: None,
+			caps_at_stop: None,
+			diagnostic_dir: None,
+			paused: None,
+			approvers: Vec::new(),
+			wall_clock: Arc::new(SystemClock::new()),
+			approvals: HashMap::new(),
+			approval_store: Box::new(MemoryApprovalStore::new()),
+			approval_timeout: None,
+			freeze_triggers: Vec::new(),
+			policy_hash: String::new(),
+			checkpoint_before: Vec::new(),
 		}
 	}
 
+    /// Replaces the default in-memory audit sink.
+    /// New entries are chained onto the sink's current head.
+    pub fn with_audit_sink(mut self, sink: Box<dyn AuditSink>) -> Self {
+        self.audit = sink;
+        self
+    }
+
+    /// Policy version recorded in the evidence of every audit entry.
+    pub fn with_policy_version(mut self, policy_version: impl Into<String>) -> Self {
+        self.evidence.policy_version = policy_version.into();
+        self
+    }
+
+    /// Hash of the active policy (`CompiledPolicy::policy_hash`). Plans
+    /// stamped with a `cap_scope_hash` only run under the policy they
+    /// were resolved with.
+    pub fn with_policy_hash(mut self, policy_hash: impl Into<String>) -> Self {
+        self.policy_hash = policy_hash.into();
+        self
+    }
+
+    /// Effects the policy demands a checkpoint before
+    /// (`CompiledPolicy::checkpoint_before`). A plan that runs such a node
+    /// without listing it in `checkpoints` is refused with
+    /// `CheckpointMissing`, whoever built it.
+    pub fn with_checkpoint_before(mut self, effects: impl IntoIterator<Item = Effect>) -> Self {
+        self.checkpoint_before.extend(effects);
+        self
+    }
+
+    /// Halts the runtime and revokes all capabilities when the watchdog
+    /// misses a heartbeat. Checked before every node, like the kill switch.
+    pub fn with_watchdog(mut self, watchdog: Watchdog) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Handle for the supervising process; clones share the deadline.
+    pub fn watchdog(&self) -> Option<&Watchdog> {
+        self.watchdog.as_ref()
+    }
+
+    /// Feeds the watchdog. No-op without one.
+    pub fn heartbeat(&self) {
+        if let Some(w) = &self.watchdog {
+            w.heartbeat();
+        }
+    }
+
+    /// Enforces a missed watchdog deadline now instead of at the next node.
+    /// Call from the supervising loop so capabilities are revoked while idle.
+    pub fn check_watchdog(&mut self) -> Result<RuntimeState, AdrRuntimeError> {
+        if let Some(timeout) = self
+            .watchdog
+            .as_ref()
+            .filter(|w| w.expired())
+            .map(Watchdog::timeout)
+        {
+            self.escalate(
+                RuntimeState::Halted,
+                format!("watchdog timeout_ms={}", timeout.as_millis()),
+            )?;
+        }
+        Ok(self.state)
+    }
+
+    /// On Freeze, writes a diagnostic bundle (see `FrozenRuntime`) into a
+    /// new subdirectory of `dir`.
+    pub fn with_diagnostic_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.diagnostic_dir = Some(dir.into());
+        self
+    }
+
+    /// Hands a Frozen runtime over as `FrozenRuntime`, which cannot
+    /// execute until it is resumed. Any other state gives the runtime back
+    /// unchanged.
+    #[allow(clippy::result_large_err)] // the runtime itself is handed back
+    pub fn into_frozen(self) -> Result<FrozenRuntime<C>, Self> {
+        if self.state == RuntimeState::Frozen {
+            Ok(FrozenRuntime::new(self))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Adds an operator whose signature can authorize `resume`.
+    pub fn with_operator(mut self, operator: OperatorKey) -> Self {
+        self.operators.push(operator);
+        self
+    }
+
+    /// Adds a human whose signature can approve gated plan nodes.
+    pub fn with_approver(mut self, approver: ApproverKey) -> Self {
+        self.approvers.push(approver);
+        self
+    }
+
+    /// Replaces the system clock approvals are checked against.
+    pub fn with_wall_clock(mut self, clock: Arc<dyn WallClock>) -> Self {
+        self.wall_clock = clock;
+        self
+    }
+
+    /// Replaces the default in-memory approval store. Gates already in
+    /// the store stay open with their original deadline and role.
+    pub fn with_approval_store(mut self, store: Box<dyn ApprovalStore>) -> Self {
+        self.approval_store = store;
+        self
+    }
+
+    /// Gives every newly opened gate a deadline; see
+    /// `check_approval_timeouts`. Without one, gates wait indefinitely.
+    pub fn with_approval_timeout(mut self, timeout: ApprovalTimeout) -> Self {
+        self.approval_timeout = Some(timeout);
+        self
+    }
+
+    /// Activates the detectors for `triggers`, usually the policy's
+    /// `freeze_triggers`. A detector that fires moves the runtime to
+    /// Frozen and the operation fails with `FreezeTriggered`.
+    pub fn with_freeze_triggers(
+        mut self,
+        triggers: impl IntoIterator<Item = FreezeTrigger>,
+    ) -> Self {
+        self.freeze_triggers.extend(triggers);
+        self
+    }
+
+    /// Reports a condition detected outside the runtime, e.g. by a Layer 2
+    /// contract check. If `trigger` is active the runtime freezes with
+    /// `detail` in the audited cause and `FreezeTriggered` is returned;
+    /// otherwise nothing happens.
+    pub fn trip_freeze_trigger(
+        &mut self,
+        trigger: FreezeTrigger,
+        node: NodeId,
+        detail: &str,
+    ) -> Result<(), AdrRuntimeError> {
+        if !self.freeze_triggers.contains(&trigger) {
+            return Ok(());
+        }
+        let cause = format!("freeze_trigger={} node={} {}", trigger.as_str(), node, detail);
+        self.escalate(RuntimeState::Frozen, cause)?;
+        Err(AdrRuntimeError::FreezeTriggered(trigger))
+    }
+
     pub fn state(&self) -> RuntimeState {
         self.state
     }
 
-    pub fn set_state(&mut self, s: RuntimeState) {
-        self.state = s;
+    /// Raises the state with the same effects as the matching kill switch
+    /// signal. Lowering it is refused; use `resume`.
+    pub fn set_state(&mut self, s: RuntimeState) -> Result<(), AdrRuntimeError> {
+        if s < self.state {
+            return Err(AdrRuntimeError::StateDowngrade {
+                from: self.state,
+                to: s,
+            });
+        }
+        self.escalate(s, "set_state".to_string())
+    }
+
+    /// What an operator signs to resume from the current state.
+    pub fn resume_challenge(&self) -> ResumeChallenge {
+        ResumeChallenge {
+            from_state: self.state,
+            audit_head: self.audit.head(),
+        }
+    }
+
+    /// Returns a stopped runtime to `Running`.
+    ///
+    /// Requires a request signed by a registered operator over the current
+    /// `resume_challenge`, and is refused while any kill switch channel
+    /// still asserts. Granted and refused attempts are both audited with
+    /// operator, reason and the state being left. A tripped watchdog is
+    /// rearmed on success.
+    pub fn resume(&mut self, request: &ResumeRequest) -> Result<(), AdrRuntimeError> {
+        let challenge = self.resume_challenge();
+        let from = self.state;
+
+        let outcome = if from == RuntimeState::Running {
+            Err(ResumeRefusal::NotStopped)
+        } else if let Some(sig) = self.kill.poll() {
+            Err(ResumeRefusal::KillSwitchAsserting(sig))
+        } else {
+            request.verify(&challenge, &self.operators)
+        };
+
+        let detail = format!(
+            "operator={} from={} reason={}",
+            request.operator_id,
+            from.as_str(),
+            request.reason
+        );
+        match outcome {
+            Ok(()) => {
+                // A resume that cannot be audited does not happen.
+                self.append_entry(NodeId::nil(), ActionKind::Resume, true, Some(detail))?;
+                self.state = RuntimeState::Running;
+                // Capabilities stay revoked; the operator grants them anew.
+                self.caps.unseal();
+                self.caps_at_stop = None;
+                if let Some(w) = &self.watchdog {
+                    w.rearm();
+                }
+                Ok(())
+            }
+            Err(refusal) => {
+                let detail = format!("{} refused={}", detail, refusal);
+                self.append_entry(NodeId::nil(), ActionKind::Resume, false, Some(detail))?;
+                Err(AdrRuntimeError::ResumeRefused(refusal))
+            }
+        }
     }
 	
 	pub fn capabilities(&self) -> &CapabilitySet {
@@ -43,9 +469,29 @@ impl<C: KillSwitchChannel> AdrRuntime<C> {
 		&self.caps
 	}
 
+    /// Registers the backend that carries out `effect`.
+    /// At most one handler per effect; returns the handler it replaces.
+    pub fn register_effect_handler(
+        &mut self,
+        effect: Effect,
+        handler: Box<dyn EffectHandler>,
+    ) -> Option<Box<dyn EffectHandler>> {
+        self.handlers.insert(effect, handler)
+    }
+
+    /// Registers the compensating action plans refer to as `action`.
+    /// Returns the compensator it replaces.
+    pub fn register_compensator(
+        &mut self,
+        action: impl Into<String>,
+        compensator: Box<dyn Compensator>,
+    ) -> Option<Box<dyn Compensator>> {
+        self.compensators.insert(action.into(), compensator)
+    }
+
     /// Phase 8/9: noop execution to prove state gating and kill switch priority.
     pub fn execute_noop(&mut self) -> Result<(), AdrRuntimeError> {
-        self.poll_kill_switch();
+        self.poll_kill_switch()?;
 
         if self.state >= RuntimeState::Halted {
             return Err(AdrRuntimeError::StateBlocked(self.state));
@@ -55,10 +501,49 @@ impl<C: KillSwitchChannel> AdrRuntime<C> {
     }
 
     /// Phase 13: minimal executor for a single node.
-    /// No real IO yet - only deterministic dispatch rules.
-    pub fn execute_node(&mut self, node: &Node) -> Result<(), AdrRuntimeError> {
-        self.poll_kill_switch();
+    /// Equivalent to `execute_node_with` and an empty payload.
+    pub fn execute_node(&mut self, node: &Node) -> Result<EffectOutput, AdrRuntimeError> {
+        self.execute_node_with(node, &EffectPayload::None)
+    }
+
+    /// Runs all safety gates for `node`, then dispatches `payload` to the
+    /// handler registered for the node's effect. Without a registered
+    /// handler the node is approved but nothing is performed.
+    /// Every attempt, successful or not, is written to the audit sink.
+    pub fn execute_node_with(
+        &mut self,
+        node: &Node,
+        payload: &EffectPayload,
+    ) -> Result<EffectOutput, AdrRuntimeError> {
+        let result = self.run_node(node, payload);
+        self.record(node.id, ActionKind::Execute, result.as_ref().err())?;
+        if let Err(e) = &result {
+            self.check_contract(node.id, e)?;
+        }
+        result
+    }
 
+    fn run_node(
+        &mut self,
+        node: &Node,
+        payload: &EffectPayload,
+    ) -> Result<EffectOutput, AdrRuntimeError> {
+        self.poll_kill_switch()?;
+        let permit = self.gate(&self.handlers, node, payload)?;
+        Self::dispatch(&self.handlers, permit, node, payload)
+    }
+
+    /// State, capability and exec-class gates; the kill switch has been
+    /// polled by the caller. The effect's own capability class must be
+    /// granted even if the node does not declare it. The target of
+    /// `payload`, as the node's handler resolves it, must be of a class
+    /// the node declares and lie inside its granted scope.
+    fn gate(
+        &self,
+        handlers: &HashMap<Effect, Box<dyn EffectHandler>>,
+        node: &Node,
+        payload: &EffectPayload,
+    ) -> Result<EffectPermit, AdrRuntimeError> {
         if self.state >= RuntimeState::Halted {
             return Err(AdrRuntimeError::StateBlocked(self.state));
         }
@@ -73,13 +558,59 @@ impl<C: KillSwitchChannel> AdrRuntime<C> {
 
         match node.exec_class {
             ExecClass::RealtimeSafe => match node.effect {
-                Effect::None => Ok(()),
-                _ => Err(AdrRuntimeError::RealtimeViolation),
+                Effect::None => {}
+                _ => return Err(AdrRuntimeError::RealtimeViolation),
             },
             ExecClass::Orchestrated => match node.effect {
-                Effect::None | Effect::FsWrite | Effect::NetExternal => Ok(()),
+                Effect::None
+                | Effect::FsWrite
+                | Effect::NetExternal
+                | Effect::ActuatorControl => {}
             },
         }
+        if let Some(required) = effect_capability(&node.effect) {
+            if !self.caps.has_mask(required) {
+                return Err(AdrRuntimeError::CapabilityNotGranted(required));
+            }
+        }
+        let target = match handlers.get(&node.effect) {
+            Some(handler) => handler.target(payload),
+            None => payload.target(),
+        };
+        if let Some(target) = target {
+            let class = target.class();
+            let declared = node.capabilities.iter().any(|m| m & class == class);
+            if !declared || !self.caps.permits(&target) {
+                return Err(AdrRuntimeError::CapabilityOutOfScope {
+                    capability: class,
+                    target: target.to_string(),
+                });
+            }
+        }
+
+        self.effect_permit()
+            .ok_or(AdrRuntimeError::StateBlocked(self.state))
+    }
+
+    fn effect_permit(&self) -> Option<EffectPermit> {
+        (self.state < RuntimeState::Halted).then_some(EffectPermit(()))
+    }
+
+    fn dispatch(
+        handlers: &HashMap<Effect, Box<dyn EffectHandler>>,
+        _permit: EffectPermit,
+        node: &Node,
+        payload: &EffectPayload,
+    ) -> Result<EffectOutput, AdrRuntimeError> {
+        let Some(handler) = handlers.get(&node.effect) else {
+            return Ok(EffectOutput::None);
+        };
+        // A panic fails the node like any other handler error; unwinding
+        // through the runtime would skip its audit entry and, on a worker
+        // thread, lose the handlers taken by `run_layer`.
+        panic::catch_unwind(AssertUnwindSafe(|| handler.handle(node, payload)))
+            .unwrap_or_else(|panic| Err(EffectError::HandlerPanicked(panic_message(&*panic))))
+            .map_err(AdrRuntimeError::EffectFailed)
     }
 	
 	// NOTE:
@@ -87,41 +618,1034 @@ impl<C: KillSwitchChannel> AdrRuntime<C> {
 	// A node that has already started may finish when state == Stopping,
 	// but no new node may start unless the runtime is Running.
 	// This ensures the kill switch can stop plans between nodes.	
+	// A Soft Stop rolls back with the plan's own `compensations`.
 	pub fn execute_plan(
 		&mut self,
 		plan: &crate::graph::ExecutionPlan,
 		graph: &Graph,
 	) -> Result<Vec<crate::graph::NodeId>, AdrRuntimeError> {
-		let mut executed = Vec::new();
+		self.execute_plan_with_compensations(plan, graph, &plan.compensations)
+	}
+
+	/// Like `execute_plan`, with `compensations` in place of the plan's
+	/// own. A Soft Stop between nodes rolls the plan back saga style:
+	/// executed nodes are compensated in reverse order with their action
+	/// from `compensations`, each attempt audited as
+	/// `ActionKind::Compensate`. The result is reported through
+	/// `AdrRuntimeError::SoftStopAborted`.
+	pub fn execute_plan_with_compensations(
+		&mut self,
+		plan: &crate::graph::ExecutionPlan,
+		graph: &Graph,
+		compensations: &Compensations,
+	) -> Result<Vec<crate::graph::NodeId>, AdrRuntimeError> {
+		let run = self.start_run(plan, graph, compensations, None)?;
+		self.run_serial(run, graph)
+	}
+
+    /// Runs `plan.parallel` layer by layer, each layer on a pool of at
+    /// most `workers` threads (a plan without layers runs one node per
+    /// layer).
+    ///
+    /// Gates are the same as for `execute_plan`: the kill switch is polled
+    /// right before each node is handed to an idle worker, and only a
+    /// Running runtime starts a node, so nothing new starts after a Soft
+    /// Stop while nodes already in flight finish. A layer is only entered
+    /// once the previous one has drained, and a failed node stops the
+    /// plan after its layer drains.
+    ///
+    /// Execute entries are appended once a layer has drained, in layer
+    /// order, so the audit chain does not depend on thread scheduling.
+    /// A Soft Stop is compensated as in `execute_plan_with_compensations`.
+    pub fn execute_plan_parallel(
+        &mut self,
+        plan: &ExecutionPlan,
+        graph: &Graph,
+        compensations: &Compensations,
+        workers: usize,
+    ) -> Result<Vec<NodeId>, AdrRuntimeError> {
+        let run = self.start_run(plan, graph, compensations, Some(workers.max(1)))?;
+        self.run_parallel(run, graph)
+    }
+
+    /// Checkpoint the current plan is paused at, if any.
+    pub fn pending_checkpoint(&self) -> Option<NodeId> {
+        match self.paused.as_ref()?.at {
+            PauseReason::Checkpoint(node) => Some(node),
+            PauseReason::Approval(_) => None,
+        }
+    }
+
+    /// Human-gated node the current plan waits to have approved, if any.
+    pub fn pending_approval(&self) -> Option<NodeId> {
+        match self.paused.as_ref()?.at {
+            PauseReason::Approval(node) => Some(node),
+            PauseReason::Checkpoint(_) => None,
+        }
+    }
+
+    /// Gates that are open, including those opened before a restart.
+    pub fn open_gates(&self) -> Vec<PendingGate> {
+        self.approval_store.pending()
+    }
+
+    /// Accepts a signed approval for a human-gated node.
+    ///
+    /// The approver must be registered, the signature valid and the
+    /// wall clock inside the approval's window. Each approver counts once
+    /// per node. While a plan waits for an approval, only one scoped to
+    /// the waiting node, the plan's graph version and its `cap_scope_hash`
+    /// is accepted. An open gate also fixes graph version, plan binding
+    /// and the roles the approver must hold (the quorum's, or the one it
+    /// was escalated to). Once the gate's quorum is reached it closes and
+    /// a plan waiting at it continues where it stopped. Approvals for a node without an open
+    /// gate are kept for its next plan run and checked again when the
+    /// node is reached.
+    ///
+    /// Timeouts are applied first, so an approval arriving after its
+    /// gate's deadline is refused.
+    ///
+    /// Accepted and refused approvals are both audited as
+    /// `ActionKind::Approval`; accepted ones list every approver of the
+    /// node so far in the entry's `trust`. Returns every node the
+    /// continued plan executed, or nothing if no plan continued.
+    pub fn submit_approval(&mut self, approval: Approval) -> Result<Vec<NodeId>, AdrRuntimeError> {
+        let timed_out = self.check_approval_timeouts()?;
+        let node = approval.scope.node;
+        let waiting = self.pending_approval();
+        let gate = self.approval_store.get(&node);
+
+        let outcome = approval
+            .verify(&self.approvers, self.wall_clock.unix_now())
+            .and_then(|()| {
+                if gate.is_none() && timed_out.contains(&node) {
+                    return Err(ApprovalRefusal::TimedOut);
+                }
+                let scope_ok = match (&gate, waiting) {
+                    (_, Some(waiting)) if waiting != node => false,
+                    (Some(gate), _) => {
+                        approval.scope.graph_version == gate.graph_version
+                            && approval.scope.cap_scope_hash == gate.cap_scope_hash
+                    }
+                    (None, Some(_)) => self
+                        .paused
+                        .as_ref()
+                        .is_some_and(|p| approval.scope == self.plan_scope(&p.run, node)),
+                    (None, None) => true,
+                };
+                if !scope_ok {
+                    return Err(ApprovalRefusal::ScopeMismatch);
+                }
+                let duplicate = self.approvals.get(&node).is_some_and(|previous| {
+                    previous.iter().any(|a| a.approver_id == approval.approver_id)
+                });
+                if duplicate {
+                    return Err(ApprovalRefusal::DuplicateApprover(
+                        approval.approver_id.clone(),
+                    ));
+                }
+                match &gate {
+                    Some(gate)
+                        if !approval
+                            .approver(&self.approvers)?
+                            .has_any_role(gate.accepted_roles()) =>
+                    {
+                        Err(ApprovalRefusal::RoleMismatch {
+                            required: gate.accepted_roles().to_vec(),
+                        })
+                    }
+                    _ => Ok(()),
+                }
+            });
+
+        let mut detail = format!(
+            "approver={} graph_version={} expires_at={}",
+            approval.approver_id, approval.scope.graph_version, approval.expires_at
+        );
+        if let Err(refusal) = outcome {
+            let detail = format!("{} refused={}", detail, refusal);
+            self.append_entry(node, ActionKind::Approval, false, Some(detail))?;
+            return Err(AdrRuntimeError::ApprovalRefused(refusal));
+        }
+
+        let scope = approval.scope.clone();
+        self.approvals.entry(node).or_default().push(approval);
+        let roles = gate.as_ref().map_or(&[][..], |g| g.accepted_roles());
+        let approved_by = self.approved_by(&scope, roles);
+        let quorum_met = gate.as_ref().map(|g| {
+            detail = format!("{} approvals={}/{}", detail, approved_by.len(), g.quorum.count);
+            approved_by.len() >= g.quorum.count
+        });
+
+        self.append_entry_with_trust(
+            node,
+            ActionKind::Approval,
+            true,
+            Some(detail),
+            Some(TrustRecord { approved_by }),
+        )?;
+        if quorum_met != Some(true) {
+            return Ok(Vec::new());
+        }
+        self.approval_store
+            .remove(&node)
+            .map_err(|e| AdrRuntimeError::ApprovalStoreFailed(e.to_string()))?;
+
+        if waiting != Some(node) {
+            return Ok(Vec::new());
+        }
+        let PausedPlan { run, graph, .. } = self.paused.take().expect("approval pending");
+        match run.workers {
+            None => self.run_serial(run, &graph),
+            Some(_) => self.run_parallel(run, &graph),
+        }
+    }
+
+    /// Applies the timeout action to every open gate past its deadline
+    /// and returns those gates' nodes. Call from the supervising loop,
+    /// like `check_watchdog`.
+    ///
+    /// - `Reject` closes the gate and drops a plan waiting at it; nodes
+    ///   the plan executed stay committed.
+    /// - `Escalate` reopens the gate for the given role with a fresh
+    ///   deadline. An escalated gate that times out again is rejected.
+    /// - `SoftStop` closes the gate and enters Stopping. A plan waiting at
+    ///   the gate is compensated and `SoftStopAborted` returned.
+    ///
+    /// Each timeout is audited as a failed `ActionKind::Approval`.
+    pub fn check_approval_timeouts(&mut self) -> Result<Vec<NodeId>, AdrRuntimeError> {
+        let Some(timeout) = self.approval_timeout.clone() else {
+            return Ok(Vec::new());
+        };
+        let now = self.wall_clock.unix_now().as_secs();
+        let expired: Vec<PendingGate> = self
+            .approval_store
+            .pending()
+            .into_iter()
+            .filter(|g| g.deadline.is_some_and(|d| now >= d))
+            .collect();
+
+        let mut timed_out = Vec::with_capacity(expired.len());
+        for gate in expired {
+            timed_out.push(gate.node);
+            self.time_out(gate, &timeout, now)?;
+        }
+        Ok(timed_out)
+    }
+
+    fn time_out(
+        &mut self,
+        gate: PendingGate,
+        timeout: &ApprovalTimeout,
+        now: u64,
+    ) -> Result<(), AdrRuntimeError> {
+        let node = gate.node;
+        let store_failed = |e: std::io::Error| AdrRuntimeError::ApprovalStoreFailed(e.to_string());
+
+        let action = match &timeout.action {
+            TimeoutAction::Escalate { role } if gate.role.as_ref() == Some(role) => {
+                TimeoutAction::Reject
+            }
+            action => action.clone(),
+        };
+        match action {
+            TimeoutAction::Escalate { role } => {
+                let deadline = now.saturating_add(timeout.after.as_secs());
+                let detail = format!("timeout action=escalate role={} deadline={}", role, deadline);
+                self.approval_store
+                    .put(PendingGate {
+                        role: Some(role),
+                        deadline: Some(deadline),
+                        ..gate
+                    })
+                    .map_err(store_failed)?;
+                self.append_entry(node, ActionKind::Approval, false, Some(detail))
+            }
+            TimeoutAction::Reject => {
+                self.approval_store.remove(&node).map_err(store_failed)?;
+                self.append_entry(
+                    node,
+                    ActionKind::Approval,
+                    false,
+                    Some("timeout action=reject".to_string()),
+                )?;
+                if self.pending_approval() == Some(node) {
+                    self.paused = None;
+                }
+                Ok(())
+            }
+            TimeoutAction::SoftStop => {
+                self.approval_store.remove(&node).map_err(store_failed)?;
+                self.append_entry(
+                    node,
+                    ActionKind::Approval,
+                    false,
+                    Some("timeout action=soft_stop".to_string()),
+                )?;
+                self.escalate(RuntimeState::Stopping, format!("approval_timeout node={}", node))?;
+                if self.pending_approval() == Some(node) {
+                    let PausedPlan { run, graph, .. } =
+                        self.paused.take().expect("approval pending");
+                    let err = AdrRuntimeError::StateBlocked(RuntimeState::Stopping);
+                    self.abort_run(err, &run, &graph)?;
+                }
+                Ok(())
+            }
+        }
+    }
 
-		for node_id in &plan.nodes {
+    /// Acknowledges the pending checkpoint and continues the paused plan
+    /// where it stopped. The acknowledgement is audited with the operator.
+    /// Returns every node the plan executed, including those before the
+    /// checkpoint.
+    pub fn acknowledge_checkpoint(
+        &mut self,
+        checkpoint: NodeId,
+        operator_id: &str,
+    ) -> Result<Vec<NodeId>, AdrRuntimeError> {
+        let pending = self
+            .pending_checkpoint()
+            .ok_or(AdrRuntimeError::NoPendingCheckpoint)?;
+        if pending != checkpoint {
+            return Err(AdrRuntimeError::CheckpointPending(pending));
+        }
+
+        let PausedPlan { mut run, graph, .. } =
+            self.paused.take().expect("checkpoint pending");
+        self.append_entry(
+            checkpoint,
+            ActionKind::Checkpoint,
+            true,
+            Some(format!("acknowledged operator={}", operator_id)),
+        )?;
+        run.acknowledged.push(checkpoint);
+
+        match run.workers {
+            None => self.run_serial(run, &graph),
+            Some(_) => self.run_parallel(run, &graph),
+        }
+    }
+
+    fn start_run(
+        &mut self,
+        plan: &ExecutionPlan,
+        graph: &Graph,
+        compensations: &Compensations,
+        workers: Option<usize>,
+    ) -> Result<PlanRun, AdrRuntimeError> {
+        // A new plan would skip the checkpoint or gate the current one
+        // waits at.
+        match self.paused.as_ref().map(|p| p.at) {
+            Some(PauseReason::Checkpoint(node)) => {
+                return Err(AdrRuntimeError::CheckpointPending(node))
+            }
+            Some(PauseReason::Approval(node)) => {
+                return Err(AdrRuntimeError::ApprovalPending(node))
+            }
+            None => {}
+        }
+        if let Some(node) = self.missing_checkpoint(plan, graph) {
+            return Err(AdrRuntimeError::CheckpointMissing(node));
+        }
+        if let Some(expected) = &plan.cap_scope_hash {
+            let actual = cap_scope_hash(&self.caps, &self.policy_hash, &graph.header.graph_version);
+            if *expected != actual {
+                let detail = format!("plan={} runtime={}", expected, actual);
+                self.trip_freeze_trigger(
+                    FreezeTrigger::CapScopeHashMismatch,
+                    NodeId::nil(),
+                    &detail,
+                )?;
+                return Err(AdrRuntimeError::CapScopeMismatch {
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+        if graph.header.deterministic_mode && workers.is_some_and(|w| w > 1) {
+            if let Some((node, dependency)) = Self::layer_dependency(plan, graph) {
+                let detail = format!("depends_on={} in_layer", dependency);
+                self.trip_freeze_trigger(FreezeTrigger::DeterministicModeViolation, node, &detail)?;
+            }
+        }
+        if let Some(node) = self.weakened_gate(plan, graph) {
+            self.trip_freeze_trigger(
+                FreezeTrigger::TrustTierDowngradeAttempt,
+                node,
+                "human gate dropped or weakened",
+            )?;
+        }
+        self.evidence.graph_version = graph.header.graph_version.clone();
+        self.last_plan = Some(plan.clone());
+
+        Ok(PlanRun {
+            plan: plan.clone(),
+            compensations: compensations.clone(),
+            workers,
+            next: 0,
+            executed: Vec::new(),
+            acknowledged: Vec::new(),
+            scope: self.caps.mask(),
+            scope_hash: cap_scope_hash(&self.caps, &self.policy_hash, &graph.header.graph_version),
+        })
+    }
+
+    /// First node of `plan` whose effect needs a checkpoint the plan does
+    /// not have.
+    fn missing_checkpoint(&self, plan: &ExecutionPlan, graph: &Graph) -> Option<NodeId> {
+        plan.nodes
+            .iter()
+            .filter(|id| !plan.checkpoints.contains(id))
+            .find(|id| {
+                graph
+                    .nodes
+                    .iter()
+                    .any(|n| n.id == **id && self.checkpoint_before.contains(&n.effect))
+            })
+            .copied()
+    }
+
+    /// A node of a layer in `plan.parallel` that depends on another node
+    /// of the same layer, with that dependency. Run concurrently, which
+    /// of the two goes first is up to the scheduler.
+    fn layer_dependency(plan: &ExecutionPlan, graph: &Graph) -> Option<(NodeId, NodeId)> {
+        plan.parallel.iter().find_map(|layer| {
+            layer.iter().find_map(|id| {
+                let node = graph.nodes.iter().find(|n| n.id == *id)?;
+                let dependency = node.dependencies.iter().find(|dep| layer.contains(dep))?;
+                Some((*id, *dependency))
+            })
+        })
+    }
+
+    /// A gate `plan` drops or lowers the quorum of: one the previous plan
+    /// for the same graph version had, or one still open in the store.
+    fn weakened_gate(&self, plan: &ExecutionPlan, graph: &Graph) -> Option<NodeId> {
+        let version = &graph.header.graph_version;
+        let previous = self
+            .last_plan
+            .iter()
+            .filter(|_| &self.evidence.graph_version == version)
+            .flat_map(|p| p.human_gates.iter().cloned());
+        let open = self
+            .approval_store
+            .pending()
+            .into_iter()
+            .filter(|g| &g.graph_version == version)
+            .map(|g| HumanGate {
+                node: g.node,
+                quorum: g.quorum,
+            });
+        previous
+            .chain(open)
+            .filter(|gate| plan.nodes.contains(&gate.node))
+            .find(|gate| {
+                plan.human_gate(&gate.node)
+                    .is_none_or(|g| g.quorum.count < gate.quorum.count)
+            })
+            .map(|gate| gate.node)
+    }
+
+    /// Detectors run right before a plan node starts.
+    fn check_node(
+        &mut self,
+        run: &PlanRun,
+        graph: &Graph,
+        node: &Node,
+    ) -> Result<(), AdrRuntimeError> {
+        let granted = self.caps.mask();
+        let scope_hash = cap_scope_hash(&self.caps, &self.policy_hash, &graph.header.graph_version);
+        if granted != run.scope || scope_hash != run.scope_hash {
+            let detail = if granted == run.scope {
+                format!("granted={:#x} scopes changed", granted)
+            } else {
+                format!("granted={:#x} at_start={:#x}", granted, run.scope)
+            };
+            self.trip_freeze_trigger(FreezeTrigger::CapScopeHashMismatch, node.id, &detail)?;
+        }
+        if let Some(cap) = node
+            .capabilities
+            .iter()
+            .find(|cap| !run.plan.capabilities.contains(cap))
+        {
+            let detail = format!("capability={:#x}", cap);
+            self.trip_freeze_trigger(FreezeTrigger::UnverifiedCapabilityUse, node.id, &detail)?;
+        }
+        if graph.header.deterministic_mode && node.effect == Effect::NetExternal {
+            self.trip_freeze_trigger(
+                FreezeTrigger::DeterministicModeViolation,
+                node.id,
+                "effect=NetExternal",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Freezes on a failed postcondition if `ContractFailure` is active.
+    fn check_contract(
+        &mut self,
+        node: NodeId,
+        err: &AdrRuntimeError,
+    ) -> Result<(), AdrRuntimeError> {
+        if let AdrRuntimeError::EffectFailed(EffectError::PostconditionFailed(why)) = err {
+            self.trip_freeze_trigger(FreezeTrigger::ContractFailure, node, why)?;
+        }
+        Ok(())
+    }
+
+    fn run_serial(
+        &mut self,
+        mut run: PlanRun,
+        graph: &Graph,
+    ) -> Result<Vec<NodeId>, AdrRuntimeError> {
+        while let Some(node_id) = run.plan.nodes.get(run.next).copied() {
 			// Kill switch must be checked before each node in the plan.
-			self.poll_kill_switch();
+			self.poll_kill_switch()?;
 
 			// For plan execution, only the Running state may start a new node.
 			if self.state != RuntimeState::Running {
-				return Err(AdrRuntimeError::StateBlocked(self.state));
+				let err = AdrRuntimeError::StateBlocked(self.state);
+				self.record(node_id, ActionKind::Execute, Some(&err))?;
+				return self.abort_run(err, &run, graph);
+			}
+
+			if run.awaits_ack(&node_id) {
+				return self.pause(run, graph, PauseReason::Checkpoint(node_id));
+			}
+			if self.awaits_approval(&run, &node_id) {
+				return self.pause(run, graph, PauseReason::Approval(node_id));
 			}
 
-			let Some(node) = graph.nodes.iter().find(|n| &n.id == node_id) else {
-				return Err(AdrRuntimeError::PlanNodeMissing(*node_id));
+			let Some(node) = graph.nodes.iter().find(|n| n.id == node_id) else {
+				let err = AdrRuntimeError::PlanNodeMissing(node_id);
+				self.record(node_id, ActionKind::Execute, Some(&err))?;
+				return Err(err);
 			};
+			if let Err(err) = self.check_node(&run, graph, node) {
+				self.record(node_id, ActionKind::Execute, Some(&err))?;
+				return Err(err);
+			}
 
-			self.execute_node(node)?;
-			executed.push(*node_id);
+			let result = self.run_node(node, run.plan.payload(&node_id));
+			let trust = self.consume_approval(&run, &node_id);
+			self.record_with_trust(node_id, ActionKind::Execute, result.as_ref().err(), trust)?;
+			if let Err(e) = &result {
+				self.check_contract(node_id, e)?;
+			}
+			result?;
+			run.executed.push(node_id);
+			run.next += 1;
 		}
 
-		Ok(executed)
-	}
+		Ok(run.executed)
+    }
 
-	
-    fn poll_kill_switch(&mut self) {
+    fn run_parallel(
+        &mut self,
+        mut run: PlanRun,
+        graph: &Graph,
+    ) -> Result<Vec<NodeId>, AdrRuntimeError> {
+        let layers: Vec<Vec<NodeId>> = if run.plan.parallel.is_empty() {
+            run.plan.nodes.iter().map(|id| vec![*id]).collect()
+        } else {
+            run.plan.parallel.clone()
+        };
+        let workers = run.workers.unwrap_or(1);
+
+        while let Some(layer) = layers.get(run.next) {
+            // A layer with a checkpoint only starts once it is acknowledged,
+            // one with a human gate once every gate there is approved.
+            self.poll_kill_switch()?;
+            if self.state == RuntimeState::Running {
+                if let Some(checkpoint) = layer.iter().find(|id| run.awaits_ack(id)) {
+                    return self.pause(run, graph, PauseReason::Checkpoint(*checkpoint));
+                }
+                if let Some(gate) = layer.iter().find(|id| self.awaits_approval(&run, id)) {
+                    return self.pause(run, graph, PauseReason::Approval(*gate));
+                }
+            }
+
+            let mut failure = None;
+            for (node_id, result) in self.run_layer(&run, layer, graph, workers) {
+                let trust = self.consume_approval(&run, &node_id);
+                self.record_with_trust(node_id, ActionKind::Execute, result.as_ref().err(), trust)?;
+                match result {
+                    Ok(()) => run.executed.push(node_id),
+                    Err(e) => {
+                        failure.get_or_insert((node_id, e));
+                    }
+                }
+            }
+            if let Some((node_id, err)) = failure {
+                self.check_contract(node_id, &err)?;
+                return self.abort_run(err, &run, graph);
+            }
+            run.next += 1;
+        }
+        Ok(run.executed)
+    }
+
+    /// Ends a run that hit `err`; a Soft Stop is compensated first.
+    fn abort_run(
+        &mut self,
+        err: AdrRuntimeError,
+        run: &PlanRun,
+        graph: &Graph,
+    ) -> Result<Vec<NodeId>, AdrRuntimeError> {
+        match err {
+            AdrRuntimeError::StateBlocked(RuntimeState::Stopping) => {
+                let report = self.compensate(&run.executed, graph, &run.compensations)?;
+                Err(AdrRuntimeError::SoftStopAborted(report))
+            }
+            err => Err(err),
+        }
+    }
+
+    /// Whether `node_id` is a human gate of `run` short of its quorum of
+    /// usable approvals.
+    fn awaits_approval(&self, run: &PlanRun, node_id: &NodeId) -> bool {
+        let Some(gate) = run.plan.human_gate(node_id) else {
+            return false;
+        };
+        let roles = self.gate_roles(node_id, &gate.quorum);
+        self.approved_by(&self.plan_scope(run, *node_id), &roles)
+            .len()
+            < gate.quorum.count
+    }
+
+    /// Roles that count towards the gate of `node`: the one an open gate
+    /// was escalated to, otherwise the quorum's.
+    fn gate_roles(&self, node: &NodeId, quorum: &Quorum) -> Vec<String> {
+        match self.approval_store.get(node).and_then(|g| g.role) {
+            Some(role) => vec![role],
+            None => quorum.roles.clone(),
+        }
+    }
+
+    /// Scope an approval must have to count for `node` in `run`.
+    fn plan_scope(&self, run: &PlanRun, node: NodeId) -> ApprovalScope {
+        ApprovalScope {
+            node,
+            graph_version: self.evidence.graph_version.clone(),
+            cap_scope_hash: run.plan.cap_scope_hash.clone(),
+        }
+    }
+
+    /// Approvers whose approval is usable: for exactly `scope`, inside its
+    /// window, and by a registered approver holding one of `roles`.
+    fn approved_by(&self, scope: &ApprovalScope, roles: &[String]) -> Vec<String> {
+        let now = self.wall_clock.unix_now();
+        self.approvals
+            .get(&scope.node)
+            .into_iter()
+            .flatten()
+            .filter(|a| {
+                a.scope == *scope
+                    && a.check_window(now).is_ok()
+                    && a.approver(&self.approvers)
+                        .is_ok_and(|k| k.has_any_role(roles))
+            })
+            .map(|a| a.approver_id.clone())
+            .collect()
+    }
+
+    /// The gate for `node`: the one already open, e.g. from before a
+    /// restart, or a new one with a deadline from `approval_timeout`.
+    fn open_gate(
+        &mut self,
+        node: NodeId,
+        quorum: Quorum,
+        cap_scope_hash: Option<String>,
+    ) -> Result<PendingGate, AdrRuntimeError> {
+        if let Some(gate) = self.approval_store.get(&node) {
+            return Ok(gate);
+        }
+        let now = self.wall_clock.unix_now().as_secs();
+        let gate = PendingGate {
+            node,
+            graph_version: self.evidence.graph_version.clone(),
+            cap_scope_hash,
+            role: None,
+            quorum,
+            opened_at: now,
+            deadline: self
+                .approval_timeout
+                .as_ref()
+                .map(|t| now.saturating_add(t.after.as_secs())),
+        };
+        self.approval_store
+            .put(gate.clone())
+            .map_err(|e| AdrRuntimeError::ApprovalStoreFailed(e.to_string()))?;
+        Ok(gate)
+    }
+
+    /// Takes the approvals a gated node of `run` is about to run under;
+    /// approvals cover one execution.
+    fn consume_approval(&mut self, run: &PlanRun, node_id: &NodeId) -> Option<TrustRecord> {
+        let gate = run.plan.human_gate(node_id)?;
+        let roles = self.gate_roles(node_id, &gate.quorum);
+        let approved_by = self.approved_by(&self.plan_scope(run, *node_id), &roles);
+        self.approvals.remove(node_id);
+        Some(TrustRecord { approved_by })
+    }
+
+    /// Stops the run before a checkpoint or an unapproved gate: the pause
+    /// is audited, then the audit sink seals its current segment so
+    /// everything up to that node is fixed before a human looks at it.
+    fn pause(
+        &mut self,
+        run: PlanRun,
+        graph: &Graph,
+        at: PauseReason,
+    ) -> Result<Vec<NodeId>, AdrRuntimeError> {
+        let executed = run.executed.len();
+        let (node, kind, detail, err) = match at {
+            PauseReason::Checkpoint(node) => (
+                node,
+                ActionKind::Checkpoint,
+                format!("paused executed={}", executed),
+                AdrRuntimeError::CheckpointReached(node),
+            ),
+            PauseReason::Approval(node) => {
+                let quorum = run
+                    .plan
+                    .human_gate(&node)
+                    .map(|g| g.quorum.clone())
+                    .unwrap_or_default();
+                let gate = self.open_gate(node, quorum, run.plan.cap_scope_hash.clone())?;
+                let roles = match gate.accepted_roles() {
+                    [] => "any".to_string(),
+                    roles => roles.join(","),
+                };
+                let detail = format!(
+                    "awaiting executed={} quorum={} roles={} deadline={}",
+                    executed,
+                    gate.quorum.count,
+                    roles,
+                    gate.deadline.map_or("none".to_string(), |d| d.to_string()),
+                );
+                (
+                    node,
+                    ActionKind::Approval,
+                    detail,
+                    AdrRuntimeError::ApprovalRequired(node),
+                )
+            }
+        };
+        self.append_entry(node, kind, true, Some(detail))?;
+        self.audit
+            .seal()
+            .map_err(|e| AdrRuntimeError::AuditWriteFailed(e.to_string()))?;
+
+        self.paused = Some(PausedPlan {
+            run,
+            graph: graph.clone(),
+            at,
+        });
+        Err(err)
+    }
+
+    /// Runs one layer and returns the outcome of every node it attempted,
+    /// in layer order. Stops handing out nodes at the first one refused by
+    /// a gate; that node's refusal is the last outcome.
+    ///
+    /// Permits are issued by the coordinator right before a node goes to
+    /// an idle worker, so none wait in a queue. A Hard Stop or Freeze seen
+    /// while nodes are in flight keeps every further node from starting
+    /// but cannot recall those already running: their permits predate the
+    /// stop, their handlers run to completion and their outcomes are
+    /// audited.
+    fn run_layer(
+        &mut self,
+        run: &PlanRun,
+        layer: &[NodeId],
+        graph: &Graph,
+        workers: usize,
+    ) -> Vec<(NodeId, Result<(), AdrRuntimeError>)> {
+        // Workers only need the handlers; the coordinator keeps `self`
+        // for polling and gating.
+        let handlers = std::mem::take(&mut self.handlers);
+
+        let mut outcomes: Vec<Option<Result<(), AdrRuntimeError>>> =
+            layer.iter().map(|_| None).collect();
+        let mut attempted = 0;
+
+        let (job_tx, job_rx) = mpsc::channel::<(usize, EffectPermit, &Node, &EffectPayload)>();
+        let (done_tx, done_rx) = mpsc::channel();
+        let job_rx = Mutex::new(job_rx);
+
+        thread::scope(|scope| {
+
+            for _ in 0..workers.min(layer.len()) {
+                let (job_rx, done_tx, handlers) = (&job_rx, done_tx.clone(), &handlers);
+                scope.spawn(move || loop {
+                    let job = job_rx.lock().expect("job queue poisoned").recv();
+                    let Ok((index, permit, node, payload)) = job else { break };
+                    let result = Self::dispatch(handlers, permit, node, payload);
+                    let _ = done_tx.send((index, result.map(|_| ())));
+                });
+            }
+            drop(done_tx);
+
+            let mut in_flight = 0;
+            for (index, node_id) in layer.iter().enumerate() {
+                // Wait for an idle worker, so the gates below run right
+                // before the node actually starts.
+                if in_flight == workers {
+                    let (done, result) = done_rx.recv().expect("worker alive");
+                    outcomes[done] = Some(result);
+                    in_flight -= 1;
+                }
+
+                attempted = index + 1;
+                match self.admit(run, node_id, graph, &handlers) {
+                    Ok((permit, node)) => {
+                        job_tx
+                            .send((index, permit, node, run.plan.payload(node_id)))
+                            .expect("worker alive");
+                        in_flight += 1;
+                    }
+                    Err(e) => {
+                        outcomes[index] = Some(Err(e));
+                        break;
+                    }
+                }
+            }
+            drop(job_tx);
+
+            for (done, result) in done_rx {
+                outcomes[done] = Some(result);
+            }
+        });
+
+        self.handlers = handlers;
+        layer
+            .iter()
+            .zip(outcomes)
+            .take(attempted)
+            .map(|(id, outcome)| (*id, outcome.expect("attempted node has an outcome")))
+            .collect()
+    }
+
+    /// Gates for starting `node_id` as part of a plan, with the handlers
+    /// `run_layer` has taken out for its workers.
+    fn admit<'g>(
+        &mut self,
+        run: &PlanRun,
+        node_id: &NodeId,
+        graph: &'g Graph,
+        handlers: &HashMap<Effect, Box<dyn EffectHandler>>,
+    ) -> Result<(EffectPermit, &'g Node), AdrRuntimeError> {
+        self.poll_kill_switch()?;
+        if self.state != RuntimeState::Running {
+            return Err(AdrRuntimeError::StateBlocked(self.state));
+        }
+        let node = graph
+            .nodes
+            .iter()
+            .find(|n| &n.id == node_id)
+            .ok_or(AdrRuntimeError::PlanNodeMissing(*node_id))?;
+        self.check_node(run, graph, node)?;
+        Ok((self.gate(handlers, node, run.plan.payload(node_id))?, node))
+    }
+
+    /// Runs the compensations for `executed` in reverse order. Stops
+    /// compensating once the runtime escalates past Stopping; the rest
+    /// is reported as `CompensationError::Interrupted`.
+    fn compensate(
+        &mut self,
+        executed: &[NodeId],
+        graph: &Graph,
+        compensations: &Compensations,
+    ) -> Result<CompensationReport, AdrRuntimeError> {
+        let mut report = CompensationReport::default();
+
+        for node_id in executed.iter().rev() {
+            let Some(action) = compensations.get(node_id) else {
+                report.committed.push(*node_id);
+                continue;
+            };
+
+            self.poll_kill_switch()?;
+            let Some(permit) = self.effect_permit() else {
+                report
+                    .failed
+                    .push((*node_id, CompensationError::Interrupted));
+                continue;
+            };
+
+            let node = graph
+                .nodes
+                .iter()
+                .find(|n| &n.id == node_id)
+                .expect("executed node is in the graph");
+            let outcome = self.run_compensator(permit, action, node);
+
+            let detail = match &outcome {
+                Ok(()) => format!("action={}", action),
+                Err(e) => format!("action={} error={}", action, e),
+            };
+            self.append_entry(*node_id, ActionKind::Compensate, outcome.is_ok(), Some(detail))?;
+
+            match outcome {
+                Ok(()) => report.rolled_back.push(*node_id),
+                Err(e) => report.failed.push((*node_id, e)),
+            }
+        }
+        Ok(report)
+    }
+
+    fn run_compensator(
+        &self,
+        _permit: EffectPermit,
+        action: &str,
+        node: &Node,
+    ) -> Result<(), CompensationError> {
+        let compensator = self
+            .compensators
+            .get(action)
+            .ok_or_else(|| CompensationError::UnknownAction(action.to_string()))?;
+        // As in `dispatch`: a panic fails this compensation, the rest of
+        // the rollback and its audit entries still run.
+        panic::catch_unwind(AssertUnwindSafe(|| compensator.compensate(node)))
+            .unwrap_or_else(|panic| Err(EffectError::HandlerPanicked(panic_message(&*panic))))
+            .map_err(CompensationError::Failed)
+    }
+
+    /// Appends one hash-chained entry for `node_id` to the audit sink.
+    fn record(
+        &mut self,
+        node_id: NodeId,
+        kind: ActionKind,
+        failure: Option<&AdrRuntimeError>,
+    ) -> Result<(), AdrRuntimeError> {
+        self.record_with_trust(node_id, kind, failure, None)
+    }
+
+    fn record_with_trust(
+        &mut self,
+        node_id: NodeId,
+        kind: ActionKind,
+        failure: Option<&AdrRuntimeError>,
+        trust: Option<TrustRecord>,
+    ) -> Result<(), AdrRuntimeError> {
+        self.append_entry_with_trust(
+            node_id,
+            kind,
+            failure.is_none(),
+            failure.map(|e| e.to_string()),
+            trust,
+        )
+    }
+
+    fn append_entry(
+        &mut self,
+        node_id: NodeId,
+        kind: ActionKind,
+        success: bool,
+        detail: Option<String>,
+    ) -> Result<(), AdrRuntimeError> {
+        self.append_entry_with_trust(node_id, kind, success, detail, None)
+    }
+
+    fn append_entry_with_trust(
+        &mut self,
+        node_id: NodeId,
+        kind: ActionKind,
+        success: bool,
+        detail: Option<String>,
+        trust: Option<TrustRecord>,
+    ) -> Result<(), AdrRuntimeError> {
+        self.tick_audit()?;
+
+        let entry = ActionLogEntry {
+            hash_version: CURRENT_HASH_VERSION,
+            node_id,
+            kind,
+            timestamp_utc: timestamp_utc_now(),
+            success,
+            evidence: self.evidence.clone(),
+            detail,
+            trust,
+            prev_hash: self.audit.head(),
+            entry_hash: String::new(),
+        }
+        .with_computed_hash();
+
+        self.audit
+            .append(entry)
+            .map_err(|e| AdrRuntimeError::AuditWriteFailed(e.to_string()))
+    }
+
+    /// Lets the audit sink do periodic work such as heartbeat anchors.
+    /// Call from the supervising loop so anchors are emitted while idle.
+    pub fn tick_audit(&mut self) -> Result<(), AdrRuntimeError> {
+        self.audit
+            .tick()
+            .map_err(|e| AdrRuntimeError::AuditWriteFailed(e.to_string()))
+    }
+
+    fn poll_kill_switch(&mut self) -> Result<(), AdrRuntimeError> {
         if let Some(sig) = self.kill.poll() {
-            match sig {
-                StopSignal::Freeze => self.state = RuntimeState::Frozen,
-                StopSignal::HardStop => self.state = RuntimeState::Halted,
-                StopSignal::SoftStop => self.state = RuntimeState::Stopping,
+            let target = match sig {
+                StopSignal::Freeze => RuntimeState::Frozen,
+                StopSignal::HardStop => RuntimeState::Halted,
+                StopSignal::SoftStop => RuntimeState::Stopping,
+            };
+            self.escalate(target, format!("kill_switch={}", sig.as_str()))?;
+        }
+        self.check_watchdog()?;
+        Ok(())
+    }
+
+    /// The only way the state goes up (SPEC P8 stop levels):
+    /// - Stopping (Soft Stop): no new node starts, a running one finishes;
+    ///   capabilities stay so compensation can run.
+    /// - Halted (Hard Stop) / Frozen: all capabilities are revoked and
+    ///   sealed until an authorized `resume`.
+    ///
+    /// Each transition is audited once; a weaker or repeated signal is a
+    /// no-op, so a latched channel does not flood the log.
+    fn escalate(&mut self, to: RuntimeState, cause: String) -> Result<(), AdrRuntimeError> {
+        if to <= self.state {
+            return Ok(());
+        }
+        let from = self.state;
+        self.state = to;
+        if to >= RuntimeState::Halted && !self.caps.is_sealed() {
+            self.caps_at_stop = Some(self.caps.mask());
+            self.caps.revoke_and_seal();
+        }
+
+        let kind = match to {
+            RuntimeState::Frozen => ActionKind::Freeze,
+            RuntimeState::Stopping => ActionKind::SoftStop,
+            _ => ActionKind::Halt,
+        };
+        let detail = format!(
+            "from={} to={} cause={}",
+            from.as_str(),
+            to.as_str(),
+            cause
+        );
+        self.append_entry(NodeId::nil(), kind, true, Some(detail))?;
+
+        if to == RuntimeState::Frozen {
+            if let Some(dir) = &self.diagnostic_dir {
+                let bundle = dir.join(frozen::bundle_name());
+                self.export_diagnostics(&bundle)
+                    .map_err(|e| AdrRuntimeError::DiagnosticExportFailed(e.to_string()))?;
             }
         }
+        Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Text of a panic payload raised with a message, as `panic!` does.
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|m| m.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string payload".to_string())
+}
@@ -0,0 +1,206 @@
+use std::ffi::OsString;
+#[cfg(unix)]
+use std::fs::File;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::{AdrRuntime, AdrRuntimeError};
+use crate::audit::{timestamp_utc_now, ActionLogEntry};
+use crate::graph::ExecutionPlan;
+use crate::killswitch::KillSwitchChannel;
+use crate::resume::{ResumeChallenge, ResumeRequest};
+use crate::runtime_state::RuntimeState;
+
+/// Point-in-time view of a frozen runtime, written as `snapshot.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagnosticSnapshot {
+    pub taken_at_utc: String,
+    pub state: RuntimeState,
+    /// Capability bits held right before the freeze revoked them.
+    pub capabilities_at_stop: Option<u64>,
+    /// Capability bits held now (0 while frozen).
+    pub capabilities: u64,
+    pub capabilities_sealed: bool,
+    pub audit_head: Option<String>,
+    pub graph_version: String,
+    pub policy_version: String,
+    pub last_plan: Option<ExecutionPlan>,
+}
+
+/// A runtime in `RuntimeState::Frozen`, held as a type of its own
+/// (SPEC P8 Emergency Freeze: diagnose, export logs, no side effects).
+///
+/// Obtained by value from `AdrRuntime::into_frozen`. It has no execution
+/// method and no access to the effect handlers or compensators, so no
+/// `EffectHandler` can run while the runtime is held this way. `resume`
+/// is the only way back to an `AdrRuntime`.
+///
+/// The freeze itself happens during a runtime call (kill switch, freeze
+/// trigger, `set_state`); until the caller hands the runtime over, its
+/// state check refuses execution.
+pub struct FrozenRuntime<C: KillSwitchChannel> {
+    rt: Box<AdrRuntime<C>>,
+}
+
+impl<C: KillSwitchChannel> FrozenRuntime<C> {
+    pub(super) fn new(rt: AdrRuntime<C>) -> Self {
+        Self { rt: Box::new(rt) }
+    }
+
+    pub fn snapshot(&self) -> DiagnosticSnapshot {
+        self.rt.diagnostic_snapshot()
+    }
+
+    pub fn last_plan(&self) -> Option<&ExecutionPlan> {
+        self.rt.last_plan.as_ref()
+    }
+
+    pub fn audit_chain(&self) -> io::Result<Vec<ActionLogEntry>> {
+        self.rt.audit.export()
+    }
+
+    /// Writes a forensics bundle into `dir`, which must not exist yet:
+    /// - `snapshot.json` – `DiagnosticSnapshot`
+    /// - `audit.jsonl`   – the full audit chain, verifiable with
+    ///   `adr-audit-verify`; omitted if the audit sink cannot export
+    ///   (`Unsupported`), `snapshot.json` still names the chain head
+    /// - `last_plan.json` – the last executed plan, if any
+    ///
+    /// The files are written into a hidden sibling directory that is
+    /// renamed to `dir` once complete, so `dir` either holds the whole
+    /// bundle or does not exist. Existing evidence is never overwritten.
+    pub fn export_bundle(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        self.rt.export_diagnostics(dir.as_ref())
+    }
+
+    /// What an operator signs to resume; see `AdrRuntime::resume_challenge`.
+    pub fn resume_challenge(&self) -> ResumeChallenge {
+        self.rt.resume_challenge()
+    }
+
+    /// Resumes as `AdrRuntime::resume` does and hands the running runtime
+    /// back. A refused or unaudited resume returns the runtime still
+    /// frozen, with the error.
+    pub fn resume(
+        mut self,
+        request: &ResumeRequest,
+    ) -> Result<AdrRuntime<C>, (Self, AdrRuntimeError)> {
+        match self.rt.resume(request) {
+            Ok(()) => Ok(*self.rt),
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+impl<C: KillSwitchChannel> AdrRuntime<C> {
+    fn diagnostic_snapshot(&self) -> DiagnosticSnapshot {
+        DiagnosticSnapshot {
+            taken_at_utc: timestamp_utc_now(),
+            state: self.state,
+            capabilities_at_stop: self.caps_at_stop,
+            capabilities: self.caps.mask(),
+            capabilities_sealed: self.caps.is_sealed(),
+            audit_head: self.audit.head(),
+            graph_version: self.evidence.graph_version.clone(),
+            policy_version: self.evidence.policy_version.clone(),
+            last_plan: self.last_plan.clone(),
+        }
+    }
+
+    /// See `FrozenRuntime::export_bundle`; also used for the bundle written
+    /// automatically on Freeze.
+    pub(super) fn export_diagnostics(&self, dir: &Path) -> io::Result<()> {
+        let name = dir.file_name().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "bundle path has no name")
+        })?;
+        let parent = match dir.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs::create_dir_all(parent)?;
+        if fs::symlink_metadata(dir).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", dir.display()),
+            ));
+        }
+
+        let mut staging_name = OsString::from(".");
+        staging_name.push(name);
+        staging_name.push(".partial");
+        let staging = parent.join(staging_name);
+        fs::create_dir(&staging)?;
+
+        let result = self
+            .write_diagnostics(&staging)
+            .and_then(|()| fs::rename(&staging, dir))
+            .and_then(|()| sync_dir(parent));
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&staging);
+        }
+        result
+    }
+
+    fn write_diagnostics(&self, dir: &Path) -> io::Result<()> {
+        let snapshot = self.diagnostic_snapshot();
+        write_new(&dir.join("snapshot.json"), &to_json(&snapshot)?)?;
+
+        match self.audit.export() {
+            Ok(entries) => {
+                let mut chain = Vec::new();
+                for entry in entries {
+                    chain.extend_from_slice(&serde_json::to_vec(&entry).map_err(invalid)?);
+                    chain.push(b'\n');
+                }
+                write_new(&dir.join("audit.jsonl"), &chain)?;
+            }
+            Err(e) if e.kind() == io::ErrorKind::Unsupported => {}
+            Err(e) => return Err(e),
+        }
+
+        if let Some(plan) = &snapshot.last_plan {
+            write_new(&dir.join("last_plan.json"), &to_json(plan)?)?;
+        }
+        sync_dir(dir)
+    }
+}
+
+/// Unique, sortable directory name for an automatic bundle.
+pub(super) fn bundle_name() -> String {
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    format!(
+        "freeze-{}-{}-{}",
+        timestamp_utc_now().replace(':', ""),
+        std::process::id(),
+        SEQ.fetch_add(1, Ordering::SeqCst)
+    )
+}
+
+fn to_json<T: Serialize>(value: &T) -> io::Result<Vec<u8>> {
+    serde_json::to_vec_pretty(value).map_err(invalid)
+}
+
+fn invalid(e: serde_json::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Makes created or renamed entries in `dir` durable.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+fn write_new(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()
+}
@@ -1,8 +1,10 @@
 use core::cmp::Ordering;
 
+use serde::{Deserialize, Serialize};
+
 /// Safety priority order:
 /// Frozen > Halted > Stopping > Running
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum RuntimeState {
     Running,
     Stopping,
@@ -10,6 +12,18 @@ pub enum RuntimeState {
     Frozen,
 }
 
+impl RuntimeState {
+    /// Name as serialized; used in audited error details.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuntimeState::Running => "Running",
+            RuntimeState::Stopping => "Stopping",
+            RuntimeState::Halted => "Halted",
+            RuntimeState::Frozen => "Frozen",
+        }
+    }
+}
+
 impl Ord for RuntimeState {
     fn cmp(&self, other: &Self) -> Ordering {
         use RuntimeState::*;
@@ -0,0 +1,73 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::clock::Clock;
+
+struct WatchdogState {
+    timeout: Duration,
+    clock: Arc<dyn Clock>,
+    last_beat_nanos: AtomicU64,
+    tripped: AtomicBool,
+}
+
+/// Dead-man switch for the supervising process
+/// (`kill_switch.watchdog_timer` in policy.yaml).
+///
+/// The supervisor calls `heartbeat` at least once per `timeout`. Once a
+/// deadline is missed the watchdog stays tripped: late heartbeats do not
+/// undo it, only `rearm` does.
+///
+/// Clones share state, so the supervisor can beat from another thread
+/// while the runtime owns its copy.
+#[derive(Clone)]
+pub struct Watchdog {
+    inner: Arc<WatchdogState>,
+}
+
+impl Watchdog {
+    /// Starts armed, with the first deadline `timeout` from now.
+    pub fn new(timeout: Duration, clock: Arc<dyn Clock>) -> Self {
+        let now = clock.now().as_nanos() as u64;
+        Self {
+            inner: Arc::new(WatchdogState {
+                timeout,
+                clock,
+                last_beat_nanos: AtomicU64::new(now),
+                tripped: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.inner.timeout
+    }
+
+    pub fn heartbeat(&self) {
+        if !self.expired() {
+            let now = self.inner.clock.now().as_nanos() as u64;
+            self.inner.last_beat_nanos.store(now, Ordering::SeqCst);
+        }
+    }
+
+    /// True once a deadline has been missed; latches.
+    pub fn expired(&self) -> bool {
+        if self.inner.tripped.load(Ordering::SeqCst) {
+            return true;
+        }
+        let now = self.inner.clock.now();
+        let last = Duration::from_nanos(self.inner.last_beat_nanos.load(Ordering::SeqCst));
+        if now.saturating_sub(last) > self.inner.timeout {
+            self.inner.tripped.store(true, Ordering::SeqCst);
+            return true;
+        }
+        false
+    }
+
+    /// Clears a trip and starts a fresh deadline.
+    pub fn rearm(&self) {
+        let now = self.inner.clock.now().as_nanos() as u64;
+        self.inner.last_beat_nanos.store(now, Ordering::SeqCst);
+        self.inner.tripped.store(false, Ordering::SeqCst);
+    }
+}
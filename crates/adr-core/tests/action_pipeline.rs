@@ -1,5 +1,6 @@
 use adr_core::{
-    ActionKind, ActionLogEntry, AdrRuntime, Effect, Evidence, ExecClass, Graph, GraphHeader, Node,
+    ActionKind, AdrRuntime, Compensations, Effect, ExecClass, ExecutionPlan, Graph, GraphHeader,
+    MemoryAuditSink, Node, CAP_NET_EXTERNAL,
 };
 use adr_core::killswitch::{KillSwitchChannel, StopSignal};
 
@@ -33,23 +34,29 @@ fn action_pipeline_resolve_execute_log() {
     };
 
     // 2) Runtime executes noop successfully
-    let mut runtime = AdrRuntime::new(NoSignal);
+    let sink = MemoryAuditSink::new();
+    let mut runtime = AdrRuntime::new(NoSignal)
+        .with_audit_sink(Box::new(sink.clone()))
+        .with_policy_version("phase12-test-policy");
     runtime.execute_noop().expect("runtime execute ok");
+    runtime.capabilities().allow_mask(CAP_NET_EXTERNAL);
 
-    // 3) Create audit log entry for the executed node
-	let log = ActionLogEntry {
-		node_id: node.id,
-		kind: ActionKind::Execute,
-		timestamp_utc: "2026-03-02T23:00:00Z".to_string(),
-		success: true,
-		evidence: Evidence {
-			graph_version: graph.header.graph_version.clone(),
-			policy_version: "phase12-test-policy".to_string(),
-			contract_hash: "noop-contract".to_string(),
-		},
-		prev_hash: None,
-		entry_hash: "dummy_hash".to_string(),
-	};
+    // 3) Executing the node emits a hash-chained audit entry
+    let plan = ExecutionPlan {
+        nodes: vec![node.id],
+        parallel: vec![vec![node.id]],
+        checkpoints: vec![],
+        human_gates: vec![],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    };
+    runtime.execute_plan(&plan, &graph).expect("plan should execute");
+
+    let entries = sink.entries();
+    assert_eq!(entries.len(), 1);
+    let log = &entries[0];
 
     // 4) Assertions
     assert_eq!(graph.nodes.len(), 1);
@@ -58,4 +65,7 @@ fn action_pipeline_resolve_execute_log() {
     assert!(log.success);
     assert_eq!(log.node_id, node.id);
     assert_eq!(log.evidence.graph_version, "0.1");
+    assert_eq!(log.evidence.policy_version, "phase12-test-policy");
+    assert_eq!(log.prev_hash, None);
+    assert_eq!(log.entry_hash, log.compute_entry_hash());
 }
\ No newline at end of file
@@ -0,0 +1,199 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use adr_core::audit::SigningKey;
+use adr_core::killswitch::StopLatch;
+use adr_core::{
+    verify_entries, ActionKind, AdrRuntime, AdrRuntimeError, Approval, ApprovalRefusal,
+    ApprovalScope, ApproverKey, Compensations, Effect, ExecClass, ExecutionPlan, Graph,
+    GraphHeader, HumanGate, ManualClock, MemoryAuditSink, Node, NodeId, Quorum,
+};
+use uuid::Uuid;
+
+const NOW: u64 = 1_000_000;
+
+/// One actuator node gated by 2 of {operator, safety_officer}.
+fn plan() -> (Graph, ExecutionPlan) {
+    let actuate = Node {
+        id: Uuid::new_v4(),
+        label: "actuate".to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect: Effect::None,
+        capabilities: vec![],
+        dependencies: vec![],
+    };
+    let plan = ExecutionPlan {
+        nodes: vec![actuate.id],
+        parallel: vec![],
+        checkpoints: vec![],
+        human_gates: vec![HumanGate {
+            node: actuate.id,
+            quorum: Quorum {
+                count: 2,
+                roles: vec!["operator".to_string(), "safety_officer".to_string()],
+            },
+        }],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    };
+    let graph = Graph {
+        header: GraphHeader {
+            graph_version: "0.1".to_string(),
+            deterministic_mode: false,
+        },
+        nodes: vec![actuate],
+    };
+    (graph, plan)
+}
+
+fn key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn approver(id: &str, seed: u8, role: &str) -> ApproverKey {
+    ApproverKey {
+        approver_id: id.to_string(),
+        key: key(seed).verifying_key(),
+        roles: vec![role.to_string()],
+    }
+}
+
+fn setup() -> (AdrRuntime<StopLatch>, MemoryAuditSink) {
+    let log = MemoryAuditSink::new();
+    let clock = ManualClock::new();
+    clock.advance(Duration::from_secs(NOW));
+    let rt = AdrRuntime::new(StopLatch::new())
+        .with_audit_sink(Box::new(log.clone()))
+        .with_wall_clock(Arc::new(clock))
+        .with_approver(approver("alice", 1, "operator"))
+        .with_approver(approver("bob", 2, "safety_officer"))
+        .with_approver(approver("carol", 3, "viewer"));
+    (rt, log)
+}
+
+fn approve(node: NodeId, id: &str, seed: u8) -> Approval {
+    Approval::sign(
+        ApprovalScope {
+            node,
+            graph_version: "0.1".to_string(),
+            cap_scope_hash: None,
+        },
+        id,
+        NOW,
+        NOW + 3600,
+        &key(seed),
+    )
+}
+
+fn refused(result: Result<Vec<NodeId>, AdrRuntimeError>) -> ApprovalRefusal {
+    match result {
+        Err(AdrRuntimeError::ApprovalRefused(r)) => r,
+        other => panic!("expected ApprovalRefused, got {:?}", other),
+    }
+}
+
+#[test]
+fn node_runs_once_quorum_is_reached() {
+    let (mut rt, log) = setup();
+    let (graph, plan) = plan();
+    let node = plan.nodes[0];
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    assert!(rt
+        .submit_approval(approve(node, "alice", 1))
+        .unwrap()
+        .is_empty());
+    assert_eq!(rt.pending_approval(), Some(node));
+    assert_eq!(rt.open_gates().len(), 1);
+
+    assert_eq!(
+        rt.submit_approval(approve(node, "bob", 2)).unwrap(),
+        vec![node]
+    );
+    assert!(rt.open_gates().is_empty());
+
+    let entries = log.entries();
+    let approvals: Vec<_> = entries
+        .iter()
+        .filter(|e| e.kind == ActionKind::Approval && e.success && e.trust.is_some())
+        .map(|e| e.trust.clone().unwrap().approved_by)
+        .collect();
+    assert_eq!(
+        approvals,
+        vec![
+            vec!["alice".to_string()],
+            vec!["alice".to_string(), "bob".to_string()],
+        ]
+    );
+    let executed = entries
+        .iter()
+        .find(|e| e.kind == ActionKind::Execute)
+        .unwrap();
+    assert_eq!(
+        executed.trust.as_ref().unwrap().approved_by,
+        vec!["alice".to_string(), "bob".to_string()]
+    );
+    assert!(verify_entries(&entries).is_ok());
+}
+
+#[test]
+fn same_approver_counts_once() {
+    let (mut rt, _log) = setup();
+    let (graph, plan) = plan();
+    let node = plan.nodes[0];
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    rt.submit_approval(approve(node, "alice", 1)).unwrap();
+    assert_eq!(
+        refused(rt.submit_approval(approve(node, "alice", 1))),
+        ApprovalRefusal::DuplicateApprover("alice".to_string())
+    );
+    assert_eq!(rt.pending_approval(), Some(node));
+}
+
+#[test]
+fn approver_outside_quorum_roles_is_refused() {
+    let (mut rt, log) = setup();
+    let (graph, plan) = plan();
+    let node = plan.nodes[0];
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    assert_eq!(
+        refused(rt.submit_approval(approve(node, "carol", 3))),
+        ApprovalRefusal::RoleMismatch {
+            required: vec!["operator".to_string(), "safety_officer".to_string()]
+        }
+    );
+    let refusal = log
+        .entries()
+        .into_iter()
+        .rfind(|e| e.kind == ActionKind::Approval)
+        .unwrap();
+    assert!(!refusal.success);
+    assert!(refusal
+        .detail
+        .unwrap()
+        .ends_with(" refused=role_mismatch required=operator,safety_officer"));
+}
+
+#[test]
+fn upfront_approvals_by_unlisted_roles_do_not_count() {
+    let (mut rt, _log) = setup();
+    let (graph, plan) = plan();
+    let node = plan.nodes[0];
+
+    // No gate is open yet, so roles are only checked when the node is
+    // reached.
+    rt.submit_approval(approve(node, "alice", 1)).unwrap();
+    rt.submit_approval(approve(node, "carol", 3)).unwrap();
+
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::ApprovalRequired(id) if id == node));
+
+    assert_eq!(
+        rt.submit_approval(approve(node, "bob", 2)).unwrap(),
+        vec![node]
+    );
+}
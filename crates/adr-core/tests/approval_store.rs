@@ -0,0 +1,297 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use adr_core::audit::SigningKey;
+use adr_core::killswitch::StopLatch;
+use adr_core::{
+    ActionKind, AdrRuntime, AdrRuntimeError, Approval, ApprovalRefusal, ApprovalScope,
+    ApprovalStore, ApprovalTimeout, ApproverKey, Compensations, Compensator, Effect, EffectError,
+    ExecClass, ExecutionPlan, FileApprovalStore, Graph, GraphHeader, HumanGate, ManualClock,
+    MemoryApprovalStore, MemoryAuditSink, Node, NodeId, PendingGate, Quorum, RuntimeState,
+    TimeoutAction,
+};
+use uuid::Uuid;
+
+const NOW: u64 = 1_000_000;
+const TIMEOUT: u64 = 300;
+
+fn node(label: &str) -> Node {
+    Node {
+        id: Uuid::new_v4(),
+        label: label.to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect: Effect::None,
+        capabilities: vec![],
+        dependencies: vec![],
+    }
+}
+
+/// a -> b with b human-gated.
+fn plan() -> (Graph, ExecutionPlan) {
+    let (a, b) = (node("a"), node("b"));
+    let plan = ExecutionPlan {
+        nodes: vec![a.id, b.id],
+        parallel: vec![],
+        checkpoints: vec![],
+        human_gates: vec![HumanGate::new(b.id)],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    };
+    let graph = Graph {
+        header: GraphHeader {
+            graph_version: "0.1".to_string(),
+            deterministic_mode: false,
+        },
+        nodes: vec![a, b],
+    };
+    (graph, plan)
+}
+
+fn temp_file() -> PathBuf {
+    std::env::temp_dir().join(format!("adr-approvals-{}.json", Uuid::new_v4()))
+}
+
+fn key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn approver(id: &str, seed: u8, role: &str) -> ApproverKey {
+    ApproverKey {
+        approver_id: id.to_string(),
+        key: key(seed).verifying_key(),
+        roles: vec![role.to_string()],
+    }
+}
+
+fn runtime(
+    store: Box<dyn ApprovalStore>,
+    action: TimeoutAction,
+) -> (AdrRuntime<StopLatch>, ManualClock, MemoryAuditSink) {
+    let log = MemoryAuditSink::new();
+    let clock = ManualClock::new();
+    clock.advance(Duration::from_secs(NOW));
+    let rt = AdrRuntime::new(StopLatch::new())
+        .with_audit_sink(Box::new(log.clone()))
+        .with_wall_clock(Arc::new(clock.clone()))
+        .with_approval_store(store)
+        .with_approval_timeout(ApprovalTimeout {
+            after: Duration::from_secs(TIMEOUT),
+            action,
+        })
+        .with_approver(approver("alice", 1, "operator"))
+        .with_approver(approver("bob", 2, "safety_officer"));
+    (rt, clock, log)
+}
+
+fn approve(node: NodeId, id: &str, seed: u8) -> Approval {
+    Approval::sign(
+        ApprovalScope {
+            node,
+            graph_version: "0.1".to_string(),
+            cap_scope_hash: None,
+        },
+        id,
+        NOW,
+        NOW + 3600,
+        &key(seed),
+    )
+}
+
+fn approval_details(log: &MemoryAuditSink) -> Vec<(bool, String)> {
+    log.entries()
+        .into_iter()
+        .filter(|e| e.kind == ActionKind::Approval)
+        .map(|e| (e.success, e.detail.unwrap()))
+        .collect()
+}
+
+#[test]
+fn pending_gate_survives_restart() {
+    let path = temp_file();
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+
+    let (mut rt, _clock, _log) = runtime(
+        Box::new(FileApprovalStore::open(&path).unwrap()),
+        TimeoutAction::Reject,
+    );
+    rt.execute_plan(&plan, &graph).unwrap_err();
+    let opened = rt.open_gates();
+    assert_eq!(
+        opened,
+        vec![PendingGate {
+            node: b,
+            graph_version: "0.1".to_string(),
+            cap_scope_hash: None,
+            role: None,
+            quorum: Quorum::default(),
+            opened_at: NOW,
+            deadline: Some(NOW + TIMEOUT),
+        }]
+    );
+    drop(rt);
+
+    // The restarted runtime sees the same gate; rerunning the plan keeps
+    // its deadline instead of opening a new one.
+    let (mut rt, clock, _log) = runtime(
+        Box::new(FileApprovalStore::open(&path).unwrap()),
+        TimeoutAction::Reject,
+    );
+    assert_eq!(rt.open_gates(), opened);
+    clock.advance(Duration::from_secs(60));
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::ApprovalRequired(id) if id == b));
+    assert_eq!(rt.open_gates(), opened);
+
+    assert_eq!(
+        rt.submit_approval(approve(b, "alice", 1)).unwrap(),
+        plan.nodes
+    );
+    assert!(rt.open_gates().is_empty());
+    assert!(FileApprovalStore::open(&path).unwrap().pending().is_empty());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn timed_out_gate_is_rejected_and_plan_dropped() {
+    let (mut rt, clock, log) = runtime(Box::new(MemoryApprovalStore::new()), TimeoutAction::Reject);
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    clock.advance(Duration::from_secs(TIMEOUT - 1));
+    assert!(rt.check_approval_timeouts().unwrap().is_empty());
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(rt.check_approval_timeouts().unwrap(), vec![b]);
+    assert_eq!(rt.pending_approval(), None);
+    assert!(rt.open_gates().is_empty());
+    assert_eq!(rt.state(), RuntimeState::Running);
+
+    assert_eq!(
+        approval_details(&log).last().unwrap(),
+        &(false, "timeout action=reject".to_string())
+    );
+}
+
+#[test]
+fn approval_arriving_after_deadline_is_refused() {
+    let (mut rt, clock, _log) =
+        runtime(Box::new(MemoryApprovalStore::new()), TimeoutAction::Reject);
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    clock.advance(Duration::from_secs(TIMEOUT));
+    match rt.submit_approval(approve(b, "alice", 1)) {
+        Err(AdrRuntimeError::ApprovalRefused(ApprovalRefusal::TimedOut)) => {}
+        other => panic!("expected TimedOut, got {:?}", other),
+    }
+    assert_eq!(rt.pending_approval(), None);
+}
+
+#[test]
+fn escalated_gate_needs_the_new_role() {
+    let escalate = TimeoutAction::Escalate {
+        role: "safety_officer".to_string(),
+    };
+    let (mut rt, clock, log) = runtime(Box::new(MemoryApprovalStore::new()), escalate);
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    clock.advance(Duration::from_secs(TIMEOUT));
+    assert_eq!(rt.check_approval_timeouts().unwrap(), vec![b]);
+
+    let gate = &rt.open_gates()[0];
+    assert_eq!(gate.role.as_deref(), Some("safety_officer"));
+    assert_eq!(gate.deadline, Some(NOW + 2 * TIMEOUT));
+    assert_eq!(rt.pending_approval(), Some(b));
+
+    match rt.submit_approval(approve(b, "alice", 1)) {
+        Err(AdrRuntimeError::ApprovalRefused(ApprovalRefusal::RoleMismatch { required })) => {
+            assert_eq!(required, vec!["safety_officer".to_string()])
+        }
+        other => panic!("expected RoleMismatch, got {:?}", other),
+    }
+    assert_eq!(
+        rt.submit_approval(approve(b, "bob", 2)).unwrap(),
+        plan.nodes
+    );
+
+    let details = approval_details(&log);
+    assert!(details.contains(&(
+        false,
+        format!(
+            "timeout action=escalate role=safety_officer deadline={}",
+            NOW + 2 * TIMEOUT
+        )
+    )));
+}
+
+#[test]
+fn escalated_gate_that_times_out_again_is_rejected() {
+    let escalate = TimeoutAction::Escalate {
+        role: "safety_officer".to_string(),
+    };
+    let (mut rt, clock, _log) = runtime(Box::new(MemoryApprovalStore::new()), escalate);
+    let (graph, plan) = plan();
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    clock.advance(Duration::from_secs(TIMEOUT));
+    rt.check_approval_timeouts().unwrap();
+    clock.advance(Duration::from_secs(TIMEOUT));
+    rt.check_approval_timeouts().unwrap();
+
+    assert!(rt.open_gates().is_empty());
+    assert_eq!(rt.pending_approval(), None);
+}
+
+struct Undo(Arc<Mutex<Vec<String>>>);
+impl Compensator for Undo {
+    fn compensate(&self, node: &Node) -> Result<(), EffectError> {
+        self.0.lock().unwrap().push(node.label.clone());
+        Ok(())
+    }
+}
+
+#[test]
+fn soft_stop_timeout_compensates_waiting_plan() {
+    let (mut rt, clock, _log) = runtime(
+        Box::new(MemoryApprovalStore::new()),
+        TimeoutAction::SoftStop,
+    );
+    let undone = Arc::new(Mutex::new(Vec::new()));
+    rt.register_compensator("undo", Box::new(Undo(Arc::clone(&undone))));
+
+    let (graph, plan) = plan();
+    let a = plan.nodes[0];
+    let compensations: Compensations = [(a, "undo")].into_iter().collect();
+    rt.execute_plan_with_compensations(&plan, &graph, &compensations)
+        .unwrap_err();
+
+    clock.advance(Duration::from_secs(TIMEOUT));
+    match rt.check_approval_timeouts() {
+        Err(AdrRuntimeError::SoftStopAborted(report)) => assert_eq!(report.rolled_back, vec![a]),
+        other => panic!("expected SoftStopAborted, got {:?}", other),
+    }
+    assert_eq!(rt.state(), RuntimeState::Stopping);
+    assert_eq!(*undone.lock().unwrap(), vec!["a"]);
+    assert!(rt.open_gates().is_empty());
+    assert_eq!(rt.pending_approval(), None);
+}
+
+#[test]
+fn unreadable_store_file_is_reported() {
+    let path = temp_file();
+    std::fs::write(&path, b"{ not json").unwrap();
+
+    let err = FileApprovalStore::open(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,284 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use adr_core::audit::SigningKey;
+use adr_core::killswitch::StopLatch;
+use adr_core::{
+    cap_scope_hash, verify_entries, ActionKind, AdrRuntime, AdrRuntimeError, Approval,
+    ApprovalRefusal, ApprovalScope, ApproverKey, Compensations, Effect, ExecClass, ExecutionPlan, Graph,
+    GraphHeader, HumanGate, ManualClock, MemoryAuditSink, Node, NodeId, TrustRecord,
+};
+use uuid::Uuid;
+
+const NOW: u64 = 1_000_000;
+
+fn node(label: &str) -> Node {
+    Node {
+        id: Uuid::new_v4(),
+        label: label.to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect: Effect::None,
+        capabilities: vec![],
+        dependencies: vec![],
+    }
+}
+
+/// a -> b -> c with b human-gated; layers [a], [b, c].
+fn plan() -> (Graph, ExecutionPlan) {
+    let (a, b, c) = (node("a"), node("b"), node("c"));
+    let plan = ExecutionPlan {
+        nodes: vec![a.id, b.id, c.id],
+        parallel: vec![vec![a.id], vec![b.id, c.id]],
+        checkpoints: vec![],
+        human_gates: vec![HumanGate::new(b.id)],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    };
+    let graph = Graph {
+        header: GraphHeader {
+            graph_version: "0.1".to_string(),
+            deterministic_mode: false,
+        },
+        nodes: vec![a, b, c],
+    };
+    (graph, plan)
+}
+
+fn key() -> SigningKey {
+    SigningKey::from_bytes(&[7; 32])
+}
+
+fn setup() -> (AdrRuntime<StopLatch>, ManualClock, MemoryAuditSink) {
+    let log = MemoryAuditSink::new();
+    let clock = ManualClock::new();
+    clock.advance(Duration::from_secs(NOW));
+    let rt = AdrRuntime::new(StopLatch::new())
+        .with_audit_sink(Box::new(log.clone()))
+        .with_wall_clock(Arc::new(clock.clone()))
+        .with_approver(ApproverKey {
+            approver_id: "alice".to_string(),
+            key: key().verifying_key(),
+            roles: vec!["operator".to_string()],
+        });
+    (rt, clock, log)
+}
+
+fn approve(node: NodeId, graph_version: &str) -> Approval {
+    Approval::sign(
+        ApprovalScope {
+            node,
+            graph_version: graph_version.to_string(),
+            cap_scope_hash: None,
+        },
+        "alice",
+        NOW - 10,
+        NOW + 60,
+        &key(),
+    )
+}
+
+fn refused(result: Result<Vec<NodeId>, AdrRuntimeError>) -> ApprovalRefusal {
+    match result {
+        Err(AdrRuntimeError::ApprovalRefused(r)) => r,
+        other => panic!("expected ApprovalRefused, got {:?}", other),
+    }
+}
+
+#[test]
+fn gated_node_waits_for_signed_approval() {
+    let (mut rt, _clock, log) = setup();
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::ApprovalRequired(id) if id == b));
+    assert_eq!(rt.pending_approval(), Some(b));
+    assert_eq!(rt.pending_checkpoint(), None);
+
+    let executed = rt.submit_approval(approve(b, "0.1")).unwrap();
+    assert_eq!(executed, plan.nodes);
+    assert_eq!(rt.pending_approval(), None);
+
+    let entries = log.entries();
+    let approved = entries
+        .iter()
+        .find(|e| e.kind == ActionKind::Approval && e.trust.is_some())
+        .unwrap();
+    assert_eq!(approved.node_id, b);
+    assert!(approved.success);
+    assert_eq!(
+        approved.trust,
+        Some(TrustRecord {
+            approved_by: vec!["alice".to_string()]
+        })
+    );
+
+    // Only the gated node's execution carries the approver.
+    let executions: Vec<_> = entries
+        .iter()
+        .filter(|e| e.kind == ActionKind::Execute)
+        .map(|e| (e.node_id, e.trust.clone()))
+        .collect();
+    assert_eq!(executions[0], (plan.nodes[0], None));
+    assert_eq!(executions[1].0, b);
+    assert_eq!(executions[1].1, approved.trust);
+    assert_eq!(executions[2], (plan.nodes[2], None));
+    assert!(verify_entries(&entries).is_ok());
+}
+
+#[test]
+fn approval_given_up_front_is_used_once() {
+    let (mut rt, _clock, _log) = setup();
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+
+    assert_eq!(
+        rt.submit_approval(approve(b, "0.1")).unwrap(),
+        Vec::<NodeId>::new()
+    );
+    assert_eq!(rt.execute_plan(&plan, &graph).unwrap(), plan.nodes);
+
+    // Consumed by the first run.
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::ApprovalRequired(id) if id == b));
+}
+
+#[test]
+fn parallel_plan_pauses_before_layer_with_gate() {
+    let (mut rt, _clock, _log) = setup();
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+
+    let err = rt
+        .execute_plan_parallel(&plan, &graph, &Compensations::new(), 2)
+        .unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::ApprovalRequired(id) if id == b));
+
+    let mut executed = rt.submit_approval(approve(b, "0.1")).unwrap();
+    executed.sort();
+    let mut expected = plan.nodes.clone();
+    expected.sort();
+    assert_eq!(executed, expected);
+}
+
+#[test]
+fn invalid_approvals_are_refused_and_audited() {
+    let (mut rt, clock, log) = setup();
+    let (graph, plan) = plan();
+    let (a, b) = (plan.nodes[0], plan.nodes[1]);
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    let stranger = Approval::sign(
+        approve(b, "0.1").scope,
+        "mallory",
+        NOW - 10,
+        NOW + 60,
+        &key(),
+    );
+    assert_eq!(
+        refused(rt.submit_approval(stranger)),
+        ApprovalRefusal::UnknownApprover("mallory".to_string())
+    );
+
+    let mut forged = approve(b, "0.1");
+    forged.expires_at += 3600;
+    assert_eq!(
+        refused(rt.submit_approval(forged)),
+        ApprovalRefusal::BadSignature
+    );
+
+    assert_eq!(
+        refused(rt.submit_approval(approve(a, "0.1"))),
+        ApprovalRefusal::ScopeMismatch
+    );
+    assert_eq!(
+        refused(rt.submit_approval(approve(b, "0.2"))),
+        ApprovalRefusal::ScopeMismatch
+    );
+
+    clock.advance(Duration::from_secs(60));
+    assert_eq!(
+        refused(rt.submit_approval(approve(b, "0.1"))),
+        ApprovalRefusal::Expired
+    );
+
+    assert_eq!(rt.pending_approval(), Some(b));
+    let refusals = log
+        .entries()
+        .into_iter()
+        .filter(|e| e.kind == ActionKind::Approval && !e.success)
+        .inspect(|e| assert!(e.trust.is_none()))
+        .count();
+    assert_eq!(refusals, 5);
+}
+
+#[test]
+fn approval_is_bound_to_the_plans_cap_scope_hash() {
+    let (rt, _clock, log) = setup();
+    let mut rt = rt.with_policy_hash("policy-a");
+    let (graph, mut plan) = plan();
+    let b = plan.nodes[1];
+    let bound = cap_scope_hash(rt.capabilities(), "policy-a", "0.1");
+    plan.cap_scope_hash = Some(bound.clone());
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    // An approval for the same node and graph version, but signed for
+    // another plan, is not reused.
+    assert_eq!(
+        refused(rt.submit_approval(approve(b, "0.1"))),
+        ApprovalRefusal::ScopeMismatch
+    );
+    let mut scope = approve(b, "0.1").scope;
+    scope.cap_scope_hash = Some("other-plan".to_string());
+    let other = Approval::sign(scope, "alice", NOW - 10, NOW + 60, &key());
+    assert_eq!(
+        refused(rt.submit_approval(other.clone())),
+        ApprovalRefusal::ScopeMismatch
+    );
+
+    // The binding is signed, so it cannot be rewritten afterwards.
+    let mut rebound = other;
+    rebound.scope.cap_scope_hash = Some(bound.clone());
+    assert_eq!(
+        refused(rt.submit_approval(rebound)),
+        ApprovalRefusal::BadSignature
+    );
+
+    let mut scope = approve(b, "0.1").scope;
+    scope.cap_scope_hash = Some(bound);
+    let approval = Approval::sign(scope, "alice", NOW - 10, NOW + 60, &key());
+    assert_eq!(rt.submit_approval(approval).unwrap(), plan.nodes);
+    assert!(verify_entries(&log.entries()).is_ok());
+}
+
+#[test]
+fn approval_that_expires_before_use_is_not_honoured() {
+    let (mut rt, clock, _log) = setup();
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+
+    rt.submit_approval(approve(b, "0.1")).unwrap();
+    clock.advance(Duration::from_secs(60));
+
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::ApprovalRequired(id) if id == b));
+}
+
+#[test]
+fn new_plan_is_refused_while_approval_pending() {
+    let (mut rt, _clock, _log) = setup();
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    assert!(matches!(
+        rt.execute_plan(&plan, &graph),
+        Err(AdrRuntimeError::ApprovalPending(id)) if id == b
+    ));
+    assert!(matches!(
+        rt.acknowledge_checkpoint(b, "op"),
+        Err(AdrRuntimeError::NoPendingCheckpoint)
+    ));
+}
@@ -1,9 +1,10 @@
-use adr_core::{ActionKind, ActionLogEntry, Evidence, NodeId};
+use adr_core::{ActionKind, ActionLogEntry, Evidence, NodeId, CURRENT_HASH_VERSION};
 use uuid::Uuid;
 
 #[test]
 fn linear_hash_chain_changes_with_prev_hash() {
     let first = ActionLogEntry {
+        hash_version: CURRENT_HASH_VERSION,
         node_id: Uuid::new_v4() as NodeId,
         kind: ActionKind::Execute,
         timestamp_utc: "2026-03-03T10:00:00Z".to_string(),
@@ -13,12 +14,15 @@ fn linear_hash_chain_changes_with_prev_hash() {
             policy_version: "policy-1".to_string(),
             contract_hash: "contract-1".to_string(),
         },
+        detail: None,
+        trust: None,
         prev_hash: None,
         entry_hash: String::new(),
     }
     .with_computed_hash();
 
     let second = ActionLogEntry {
+        hash_version: CURRENT_HASH_VERSION,
         node_id: Uuid::new_v4() as NodeId,
         kind: ActionKind::Execute,
         timestamp_utc: "2026-03-03T10:01:00Z".to_string(),
@@ -28,6 +32,8 @@ fn linear_hash_chain_changes_with_prev_hash() {
             policy_version: "policy-1".to_string(),
             contract_hash: "contract-1".to_string(),
         },
+        detail: None,
+        trust: None,
         prev_hash: Some(first.entry_hash.clone()),
         entry_hash: String::new(),
     }
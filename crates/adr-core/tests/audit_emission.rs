@@ -0,0 +1,201 @@
+use adr_core::audit::format_timestamp_utc;
+use adr_core::killswitch::{KillSwitchChannel, StopSignal};
+use adr_core::{
+    ActionKind, ActionLogEntry, AdrRuntime, AdrRuntimeError, AuditSink, Compensations, Effect,
+    EffectError, EffectHandler, EffectOutput, EffectPayload, ExecClass, ExecutionPlan, Graph,
+    GraphHeader, MemoryAuditSink, Node, CAP_NET_EXTERNAL,
+};
+use uuid::Uuid;
+
+struct NoSignal;
+impl KillSwitchChannel for NoSignal {
+    fn poll(&self) -> Option<StopSignal> {
+        None
+    }
+}
+
+struct FreezeAlways;
+impl KillSwitchChannel for FreezeAlways {
+    fn poll(&self) -> Option<StopSignal> {
+        Some(StopSignal::Freeze)
+    }
+}
+
+struct FailingSink;
+impl AuditSink for FailingSink {
+    fn head(&self) -> Option<String> {
+        None
+    }
+
+    fn append(&mut self, _entry: ActionLogEntry) -> std::io::Result<()> {
+        Err(std::io::Error::other("disk full"))
+    }
+}
+
+struct Unreachable;
+impl EffectHandler for Unreachable {
+    fn handle(&self, _node: &Node, _payload: &EffectPayload) -> Result<EffectOutput, EffectError> {
+        Err(EffectError::Io("connection refused".to_string()))
+    }
+}
+
+fn node(exec_class: ExecClass, effect: Effect, capabilities: Vec<u64>) -> Node {
+    Node {
+        id: Uuid::new_v4(),
+        label: "n".to_string(),
+        exec_class,
+        effect,
+        capabilities,
+        dependencies: vec![],
+    }
+}
+
+fn assert_chained(entries: &[ActionLogEntry]) {
+    for (i, entry) in entries.iter().enumerate() {
+        assert_eq!(entry.entry_hash, entry.compute_entry_hash());
+        let expected_prev = if i == 0 {
+            None
+        } else {
+            Some(entries[i - 1].entry_hash.clone())
+        };
+        assert_eq!(entry.prev_hash, expected_prev);
+    }
+}
+
+#[test]
+fn every_attempt_is_audited_and_chained() {
+    let sink = MemoryAuditSink::new();
+    let mut rt = AdrRuntime::new(NoSignal).with_audit_sink(Box::new(sink.clone()));
+
+    let ok = node(ExecClass::Orchestrated, Effect::None, vec![]);
+    let no_cap = node(ExecClass::Orchestrated, Effect::FsWrite, vec![1 << 3]);
+    let rt_violation = node(ExecClass::RealtimeSafe, Effect::NetExternal, vec![]);
+
+    rt.execute_node(&ok).expect("node should execute");
+    rt.execute_node(&no_cap).unwrap_err();
+    rt.execute_node(&rt_violation).unwrap_err();
+
+    let entries = sink.entries();
+    assert_eq!(entries.len(), 3);
+    assert_chained(&entries);
+
+    assert!(entries[0].success);
+    assert_eq!(entries[0].detail, None);
+    assert_eq!(entries[0].node_id, ok.id);
+
+    assert!(!entries[1].success);
+    assert_eq!(
+        entries[1].detail.as_deref(),
+        Some("capability_not_granted capability=0x8")
+    );
+
+    assert!(!entries[2].success);
+    assert_eq!(entries[2].detail.as_deref(), Some("realtime_violation"));
+    assert!(entries.iter().all(|e| e.kind == ActionKind::Execute));
+}
+
+#[test]
+fn effect_failure_detail_is_an_error_code_not_debug_output() {
+    let sink = MemoryAuditSink::new();
+    let mut rt = AdrRuntime::new(NoSignal).with_audit_sink(Box::new(sink.clone()));
+    rt.register_effect_handler(Effect::NetExternal, Box::new(Unreachable));
+    rt.capabilities().allow_mask(CAP_NET_EXTERNAL);
+
+    rt.execute_node(&node(ExecClass::Orchestrated, Effect::NetExternal, vec![]))
+        .unwrap_err();
+
+    assert_eq!(
+        sink.entries()[0].detail.as_deref(),
+        Some("effect_failed io error=connection refused")
+    );
+}
+
+#[test]
+fn state_blocked_attempt_is_audited() {
+    let sink = MemoryAuditSink::new();
+    let mut rt = AdrRuntime::new(FreezeAlways).with_audit_sink(Box::new(sink.clone()));
+
+    let n = node(ExecClass::Orchestrated, Effect::None, vec![]);
+    rt.execute_node(&n).unwrap_err();
+
+    // The Freeze transition itself, then the refused attempt.
+    let entries = sink.entries();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].kind, ActionKind::Freeze);
+    assert!(!entries[1].success);
+    assert_eq!(entries[1].detail.as_deref(), Some("state_blocked state=Frozen"));
+}
+
+#[test]
+fn plan_level_failures_are_audited_once() {
+    let sink = MemoryAuditSink::new();
+    let mut rt = AdrRuntime::new(NoSignal)
+        .with_audit_sink(Box::new(sink.clone()))
+        .with_policy_version("p-1");
+
+    let a = node(ExecClass::Orchestrated, Effect::None, vec![]);
+    let missing = Uuid::new_v4();
+    let graph = Graph {
+        header: GraphHeader {
+            graph_version: "0.7".to_string(),
+            deterministic_mode: true,
+        },
+        nodes: vec![a.clone()],
+    };
+    let plan = ExecutionPlan {
+        nodes: vec![a.id, missing],
+        parallel: vec![],
+        checkpoints: vec![],
+        human_gates: vec![],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    };
+
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::PlanNodeMissing(id) if id == missing));
+
+    let entries = sink.entries();
+    assert_eq!(entries.len(), 2);
+    assert_chained(&entries);
+    assert_eq!(entries[1].node_id, missing);
+    assert!(!entries[1].success);
+    assert!(entries
+        .iter()
+        .all(|e| e.evidence.graph_version == "0.7" && e.evidence.policy_version == "p-1"));
+}
+
+#[test]
+fn chain_resumes_from_existing_sink_head() {
+    let sink = MemoryAuditSink::new();
+    let n = node(ExecClass::Orchestrated, Effect::None, vec![]);
+
+    let mut first = AdrRuntime::new(NoSignal).with_audit_sink(Box::new(sink.clone()));
+    first.execute_node(&n).unwrap();
+
+    let mut second = AdrRuntime::new(NoSignal).with_audit_sink(Box::new(sink.clone()));
+    second.execute_node(&n).unwrap();
+
+    let entries = sink.entries();
+    assert_eq!(entries.len(), 2);
+    assert_chained(&entries);
+}
+
+#[test]
+fn audit_write_failure_is_reported() {
+    let mut rt = AdrRuntime::new(NoSignal).with_audit_sink(Box::new(FailingSink));
+
+    let err = rt
+        .execute_node(&node(ExecClass::Orchestrated, Effect::None, vec![]))
+        .unwrap_err();
+
+    assert!(matches!(err, AdrRuntimeError::AuditWriteFailed(_)));
+}
+
+#[test]
+fn timestamps_are_rfc3339_utc() {
+    assert_eq!(format_timestamp_utc(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_timestamp_utc(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(format_timestamp_utc(1_772_492_400), "2026-03-02T23:00:00Z");
+}
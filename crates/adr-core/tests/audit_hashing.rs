@@ -0,0 +1,166 @@
+use adr_core::audit::{HASH_VERSION_CANONICAL, HASH_VERSION_LEGACY, HASH_VERSION_TRUST};
+use adr_core::{verify_entries, ActionKind, ActionLogEntry, ChainBreakReason, Evidence, TrustRecord};
+use uuid::Uuid;
+
+fn fixed_entry(hash_version: u32) -> ActionLogEntry {
+    ActionLogEntry {
+        hash_version,
+        node_id: Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef),
+        kind: ActionKind::Execute,
+        timestamp_utc: "2026-03-03T10:00:00Z".to_string(),
+        success: true,
+        evidence: Evidence {
+            graph_version: "0.1".to_string(),
+            policy_version: "policy-1".to_string(),
+            contract_hash: "contract-1".to_string(),
+        },
+        detail: None,
+        trust: None,
+        prev_hash: None,
+        entry_hash: String::new(),
+    }
+    .with_computed_hash()
+}
+
+/// Pinned vectors: if either changes, previously written logs stop verifying.
+#[test]
+fn hash_vectors_are_stable() {
+    assert_eq!(
+        fixed_entry(HASH_VERSION_CANONICAL).entry_hash,
+        "e9eebf9fa6b102905a0f37b901f6290efc21d88d166ae5d6698e21ef0075cc32"
+    );
+    assert_eq!(
+        fixed_entry(HASH_VERSION_LEGACY).entry_hash,
+        "7da070e5239eb236e83cbd534974cedfc0aa6f4a0c5f06c0bb3c2d7097870e8e"
+    );
+}
+
+#[test]
+fn canonical_encoding_separates_field_boundaries() {
+    let mut a = fixed_entry(HASH_VERSION_CANONICAL);
+    a.evidence.graph_version = "0.1p".to_string();
+    a.evidence.policy_version = "olicy-1".to_string();
+    let a_legacy = ActionLogEntry {
+        hash_version: HASH_VERSION_LEGACY,
+        ..a.clone()
+    };
+    let b = fixed_entry(HASH_VERSION_CANONICAL);
+    let b_legacy = fixed_entry(HASH_VERSION_LEGACY);
+
+    // The legacy scheme cannot tell the two apart ...
+    assert_eq!(a_legacy.compute_entry_hash(), b_legacy.entry_hash);
+    // ... the canonical one can.
+    assert_ne!(a.compute_entry_hash(), b.entry_hash);
+}
+
+#[test]
+fn canonical_hash_does_not_depend_on_variant_names() {
+    let execute = fixed_entry(HASH_VERSION_CANONICAL);
+    let mut bytes = execute.canonical_bytes();
+
+    // Only the one-byte kind code identifies the action kind.
+    assert!(!String::from_utf8_lossy(&bytes).contains("Execute"));
+    let kind_offset = b"ADR-AUDIT\0".len() + 4 + 16;
+    assert_eq!(bytes[kind_offset], ActionKind::Execute.code());
+
+    bytes[kind_offset] = ActionKind::Halt.code();
+    let mut halt = execute.clone();
+    halt.kind = ActionKind::Halt;
+    assert_eq!(halt.canonical_bytes(), bytes);
+}
+
+#[test]
+fn legacy_entries_still_verify() {
+    let first = fixed_entry(HASH_VERSION_LEGACY);
+    let second = ActionLogEntry {
+        prev_hash: Some(first.entry_hash.clone()),
+        ..fixed_entry(HASH_VERSION_CANONICAL)
+    }
+    .with_computed_hash();
+
+    assert_eq!(verify_entries([&first, &second]), Ok(2));
+}
+
+#[test]
+fn legacy_entry_after_a_newer_one_is_a_downgrade() {
+    let first = fixed_entry(HASH_VERSION_TRUST);
+    let second = ActionLogEntry {
+        prev_hash: Some(first.entry_hash.clone()),
+        ..fixed_entry(HASH_VERSION_CANONICAL)
+    }
+    .with_computed_hash();
+
+    let brk = verify_entries([&first, &second]).unwrap_err();
+    assert_eq!(brk.index, 1);
+    assert_eq!(
+        brk.reason,
+        ChainBreakReason::HashVersionDowngrade {
+            previous: HASH_VERSION_TRUST,
+            found: HASH_VERSION_CANONICAL
+        }
+    );
+
+    // Moving forward is fine, going back from v2 to v1 is not.
+    let v2 = fixed_entry(HASH_VERSION_CANONICAL);
+    let upgraded = ActionLogEntry {
+        prev_hash: Some(v2.entry_hash.clone()),
+        ..fixed_entry(HASH_VERSION_TRUST)
+    }
+    .with_computed_hash();
+    assert_eq!(verify_entries([&v2, &upgraded]), Ok(2));
+    let legacy = ActionLogEntry {
+        prev_hash: Some(v2.entry_hash.clone()),
+        ..fixed_entry(HASH_VERSION_LEGACY)
+    }
+    .with_computed_hash();
+    let brk = verify_entries([&v2, &legacy]).unwrap_err();
+    assert_eq!(
+        brk.reason,
+        ChainBreakReason::HashVersionDowngrade {
+            previous: HASH_VERSION_CANONICAL,
+            found: HASH_VERSION_LEGACY
+        }
+    );
+}
+
+#[test]
+fn record_without_hash_version_is_read_as_legacy() {
+    let legacy = fixed_entry(HASH_VERSION_LEGACY);
+    let mut json: serde_json::Value = serde_json::to_value(&legacy).unwrap();
+    json.as_object_mut().unwrap().remove("hash_version");
+
+    let decoded: ActionLogEntry = serde_json::from_value(json).unwrap();
+
+    assert_eq!(decoded.hash_version, HASH_VERSION_LEGACY);
+    assert_eq!(verify_entries([&decoded]), Ok(1));
+}
+
+#[test]
+fn unknown_hash_version_is_reported() {
+    let mut entry = fixed_entry(HASH_VERSION_CANONICAL);
+    entry.hash_version = 99;
+
+    let brk = verify_entries([&entry]).unwrap_err();
+    assert_eq!(brk.reason, ChainBreakReason::UnsupportedHashVersion(99));
+}
+
+#[test]
+fn trust_record_is_covered_from_hash_version_3() {
+    let mut entry = fixed_entry(HASH_VERSION_TRUST);
+    entry.trust = Some(TrustRecord {
+        approved_by: vec!["alice".to_string()],
+    });
+    let entry = entry.with_computed_hash();
+    assert_eq!(verify_entries([&entry]), Ok(1));
+
+    let mut swapped = entry.clone();
+    swapped.trust = Some(TrustRecord {
+        approved_by: vec!["mallory".to_string()],
+    });
+    assert_ne!(swapped.compute_entry_hash(), entry.entry_hash);
+
+    // Older schemes cannot carry a trust record.
+    let mut v2 = fixed_entry(HASH_VERSION_CANONICAL);
+    v2.trust = entry.trust.clone();
+    assert!(verify_entries([&v2]).is_err());
+}
@@ -0,0 +1,290 @@
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use adr_core::audit::merkle_root;
+use adr_core::killswitch::{KillSwitchChannel, StopSignal};
+use adr_core::{
+    verify_chain, verify_entries, ActionKind, ActionLogEntry, AdrRuntime, AuditSink, Effect,
+    ExecClass, JsonlAuditStore, ManualClock, MemoryAuditSink, MerkleAnchorer, MerkleConfig, Node,
+};
+use uuid::Uuid;
+
+struct NoSignal;
+impl KillSwitchChannel for NoSignal {
+    fn poll(&self) -> Option<StopSignal> {
+        None
+    }
+}
+
+/// Memory sink that refuses anchor entries while `fail_anchors` is set.
+#[derive(Clone)]
+struct FlakySink {
+    log: MemoryAuditSink,
+    fail_anchors: Arc<AtomicBool>,
+}
+
+impl AuditSink for FlakySink {
+    fn head(&self) -> Option<String> {
+        self.log.head()
+    }
+
+    fn append(&mut self, entry: ActionLogEntry) -> io::Result<()> {
+        if entry.kind == ActionKind::Anchor && self.fail_anchors.load(Ordering::SeqCst) {
+            return Err(io::Error::other("disk full"));
+        }
+        self.log.append(entry)
+    }
+
+    fn export(&self) -> io::Result<Vec<ActionLogEntry>> {
+        self.log.export()
+    }
+}
+
+fn noop_node() -> Node {
+    Node {
+        id: Uuid::new_v4(),
+        label: "noop".to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect: Effect::None,
+        capabilities: vec![],
+        dependencies: vec![],
+    }
+}
+
+fn setup(
+    config: MerkleConfig,
+) -> (
+    AdrRuntime<NoSignal>,
+    MerkleAnchorer,
+    MemoryAuditSink,
+    ManualClock,
+) {
+    let clock = ManualClock::new();
+    let log = MemoryAuditSink::new();
+    let anchorer = MerkleAnchorer::new(Box::new(log.clone()), config, Arc::new(clock.clone()));
+    let rt = AdrRuntime::new(NoSignal).with_audit_sink(Box::new(anchorer.clone()));
+    (rt, anchorer, log, clock)
+}
+
+#[test]
+fn full_batch_is_sealed_and_anchored_in_chain() {
+    let mut config = MerkleConfig::new(Duration::from_secs(300));
+    config.max_batch_size = 4;
+    let (mut rt, anchorer, log, _clock) = setup(config);
+
+    for _ in 0..4 {
+        rt.execute_node(&noop_node()).unwrap();
+    }
+
+    let batches = anchorer.batches();
+    assert_eq!(batches.len(), 1);
+    assert!(batches[0].verify());
+
+    let entries = log.entries();
+    assert_eq!(entries.len(), 5);
+    let anchor = &entries[4];
+    assert_eq!(anchor.kind, ActionKind::Anchor);
+    assert_eq!(anchor.entry_hash, batches[0].anchor_hash);
+    assert!(anchor
+        .detail
+        .as_deref()
+        .unwrap()
+        .contains(&format!("merkle_root={}", batches[0].root)));
+    assert_eq!(verify_entries(&entries), Ok(5));
+}
+
+#[test]
+fn heartbeat_anchor_is_written_without_actions() {
+    let (mut rt, anchorer, log, clock) = setup(MerkleConfig::new(Duration::from_secs(60)));
+
+    rt.tick_audit().unwrap();
+    assert!(log.entries().is_empty());
+
+    clock.advance(Duration::from_secs(60));
+    rt.tick_audit().unwrap();
+
+    let batches = anchorer.batches();
+    assert_eq!(batches.len(), 1);
+    assert!(batches[0].entry_hashes.is_empty());
+    assert_eq!(batches[0].root, merkle_root(&[]));
+    assert_eq!(log.entries().len(), 1);
+    assert_eq!(log.entries()[0].kind, ActionKind::Anchor);
+}
+
+#[test]
+fn elapsed_interval_seals_before_next_record() {
+    let (mut rt, anchorer, log, clock) = setup(MerkleConfig::new(Duration::from_secs(60)));
+
+    rt.execute_node(&noop_node()).unwrap();
+    clock.advance(Duration::from_secs(61));
+    rt.execute_node(&noop_node()).unwrap();
+
+    let entries = log.entries();
+    let kinds: Vec<_> = entries.iter().map(|e| e.kind.clone()).collect();
+    assert_eq!(
+        kinds,
+        vec![ActionKind::Execute, ActionKind::Anchor, ActionKind::Execute]
+    );
+    assert_eq!(
+        anchorer.batches()[0].entry_hashes,
+        vec![entries[0].entry_hash.clone()]
+    );
+    assert_eq!(verify_entries(&entries), Ok(3));
+}
+
+#[test]
+fn consecutive_batches_are_linked_through_anchor() {
+    let mut config = MerkleConfig::new(Duration::from_secs(300));
+    config.max_batch_size = 2;
+    let (mut rt, anchorer, _log, _clock) = setup(config);
+
+    for _ in 0..3 {
+        rt.execute_node(&noop_node()).unwrap();
+    }
+
+    let batches = anchorer.batches();
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[1].entry_hashes[0], batches[0].anchor_hash);
+}
+
+#[test]
+fn inclusion_proofs_verify_for_every_position() {
+    for size in 1..=9 {
+        let mut config = MerkleConfig::new(Duration::from_secs(300));
+        config.max_batch_size = 64;
+        let (mut rt, anchorer, log, _clock) = setup(config);
+
+        for _ in 0..size {
+            rt.execute_node(&noop_node()).unwrap();
+        }
+        let batch = anchorer.seal_now().unwrap();
+        assert_eq!(batch.entry_hashes.len(), size);
+
+        for entry in log.entries().iter().take(size) {
+            let proof = anchorer.prove(&entry.entry_hash).unwrap();
+            assert_eq!(proof.root, batch.root);
+            assert!(proof.verify(&entry.entry_hash), "size {size}");
+        }
+    }
+}
+
+#[test]
+fn proof_rejects_other_entry_or_forged_path() {
+    let (mut rt, anchorer, log, _clock) = setup(MerkleConfig::new(Duration::from_secs(300)));
+    for _ in 0..5 {
+        rt.execute_node(&noop_node()).unwrap();
+    }
+    anchorer.seal_now().unwrap();
+
+    let entries = log.entries();
+    let proof = anchorer.prove(&entries[2].entry_hash).unwrap();
+    assert!(!proof.verify(&entries[3].entry_hash));
+
+    let mut forged = proof.clone();
+    forged.path[0] = "00".repeat(32);
+    assert!(!forged.verify(&entries[2].entry_hash));
+
+    let mut moved = proof;
+    moved.leaf_index = 3;
+    assert!(!moved.verify(&entries[2].entry_hash));
+}
+
+#[test]
+fn open_batch_has_no_proof() {
+    let (mut rt, anchorer, log, _clock) = setup(MerkleConfig::new(Duration::from_secs(300)));
+    rt.execute_node(&noop_node()).unwrap();
+
+    assert!(anchorer.prove(&log.entries()[0].entry_hash).is_none());
+    assert_eq!(anchorer.head(), Some(log.entries()[0].entry_hash.clone()));
+}
+
+#[test]
+fn reopened_store_continues_batches_and_keeps_proofs() {
+    let path = std::env::temp_dir().join(format!("adr-merkle-{}.jsonl", Uuid::new_v4()));
+    let mut config = MerkleConfig::new(Duration::from_secs(300));
+    config.max_batch_size = 2;
+    let clock = Arc::new(ManualClock::new());
+
+    let first = {
+        let store = JsonlAuditStore::open(&path).unwrap();
+        let anchorer = MerkleAnchorer::new(Box::new(store), config.clone(), clock.clone());
+        let mut rt = AdrRuntime::new(NoSignal).with_audit_sink(Box::new(anchorer.clone()));
+        for _ in 0..2 {
+            rt.execute_node(&noop_node()).unwrap();
+        }
+        anchorer.batches()
+    };
+    assert_eq!(first.len(), 1);
+
+    let store = JsonlAuditStore::open(&path).unwrap();
+    let anchorer = MerkleAnchorer::open(Box::new(store), config, clock).unwrap();
+    assert_eq!(anchorer.batches(), first);
+
+    let mut rt = AdrRuntime::new(NoSignal).with_audit_sink(Box::new(anchorer.clone()));
+    rt.execute_node(&noop_node()).unwrap();
+
+    let batches = anchorer.batches();
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[1].index, 1);
+    assert_eq!(batches[1].entry_hashes[0], batches[0].anchor_hash);
+    assert_eq!(batches[1].entry_hashes.len(), 2);
+    assert!(batches[1].verify());
+
+    let proof = anchorer.prove(&first[0].entry_hashes[0]).unwrap();
+    assert!(proof.verify(&first[0].entry_hashes[0]));
+    assert_eq!(verify_chain(&path), Ok(5));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn reopen_rejects_anchor_that_does_not_match_the_log() {
+    let mut config = MerkleConfig::new(Duration::from_secs(300));
+    config.max_batch_size = 2;
+    let (mut rt, _anchorer, log, clock) = setup(config.clone());
+    for _ in 0..2 {
+        rt.execute_node(&noop_node()).unwrap();
+    }
+
+    let mut entries = log.entries();
+    entries.remove(0);
+    let tampered = MemoryAuditSink::new();
+    for entry in entries {
+        tampered.clone().append(entry).unwrap();
+    }
+
+    let err = MerkleAnchorer::open(Box::new(tampered), config, Arc::new(clock))
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn failed_seal_does_not_fail_the_written_append() {
+    let mut config = MerkleConfig::new(Duration::from_secs(300));
+    config.max_batch_size = 2;
+    let fail_anchors = Arc::new(AtomicBool::new(true));
+    let sink = FlakySink {
+        log: MemoryAuditSink::new(),
+        fail_anchors: fail_anchors.clone(),
+    };
+    let log = sink.log.clone();
+    let anchorer = MerkleAnchorer::new(Box::new(sink), config, Arc::new(ManualClock::new()));
+    let mut rt = AdrRuntime::new(NoSignal).with_audit_sink(Box::new(anchorer.clone()));
+
+    rt.execute_node(&noop_node()).unwrap();
+    rt.execute_node(&noop_node()).unwrap();
+    assert_eq!(log.entries().len(), 2);
+    assert!(anchorer.batches().is_empty());
+
+    assert!(rt.tick_audit().is_err());
+
+    fail_anchors.store(false, Ordering::SeqCst);
+    rt.tick_audit().unwrap();
+    let batches = anchorer.batches();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].entry_hashes.len(), 2);
+    assert_eq!(verify_entries(&log.entries()), Ok(3));
+}
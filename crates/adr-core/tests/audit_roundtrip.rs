@@ -1,9 +1,10 @@
-use adr_core::{ActionKind, ActionLogEntry, Evidence, NodeId};
+use adr_core::{ActionKind, ActionLogEntry, Evidence, NodeId, CURRENT_HASH_VERSION};
 use uuid::Uuid;
 
 #[test]
 fn action_log_roundtrip_json() {
 	let entry = ActionLogEntry {
+		hash_version: CURRENT_HASH_VERSION,
 		node_id: Uuid::new_v4() as NodeId,
 		kind: ActionKind::Execute,
 		timestamp_utc: "2026-03-02T22:30:00Z".to_string(),
@@ -13,6 +14,8 @@ fn action_log_roundtrip_json() {
 			policy_version: "test-policy-1".to_string(),
 			contract_hash: "abc123".to_string(),
 		},
+		detail: None,
+		trust: None,
 		prev_hash: None,
 		entry_hash: String::new(),
 	}.with_computed_hash();
@@ -22,7 +25,7 @@ fn action_log_roundtrip_json() {
         serde_json::from_str(&json).expect("deserialize action log");
 
     assert_eq!(decoded.kind, ActionKind::Execute);
-    assert_eq!(decoded.success, true);
+    assert!(decoded.success);
     assert_eq!(decoded.evidence.graph_version, "0.1");
     assert_eq!(decoded.evidence.policy_version, "test-policy-1");
     assert_eq!(decoded.evidence.contract_hash, "abc123");
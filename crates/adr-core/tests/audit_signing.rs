@@ -0,0 +1,175 @@
+use adr_core::audit::{
+    merkle_root, verify_root_signatures, RequiredSigner, RootSigner, SealedBatch,
+    SignatureRejection, SignatureRequirement, SigningKey,
+};
+
+fn signer(role: &str, id: Option<&str>, seed: u8) -> RootSigner {
+    RootSigner::new(
+        role,
+        id.map(str::to_string),
+        SigningKey::from_bytes(&[seed; 32]),
+    )
+}
+
+fn batch(index: u64) -> SealedBatch {
+    let entry_hashes = vec!["aa".repeat(32), "bb".repeat(32)];
+    SealedBatch {
+        index,
+        root: merkle_root(&entry_hashes),
+        entry_hashes,
+        anchor_hash: "cc".repeat(32),
+    }
+}
+
+fn required(role: &str, id: Option<&str>) -> RequiredSigner {
+    RequiredSigner {
+        role: role.to_string(),
+        id: id.map(str::to_string),
+    }
+}
+
+fn medical() -> (Vec<RootSigner>, SignatureRequirement) {
+    let signers = vec![
+        signer("operator", None, 1),
+        signer("regulator", Some("bfarm"), 2),
+        signer("auditor", None, 3),
+    ];
+    let requirement = SignatureRequirement::all(vec![
+        required("operator", None),
+        required("regulator", Some("bfarm")),
+        required("auditor", None),
+    ]);
+    (signers, requirement)
+}
+
+#[test]
+fn all_parties_signing_satisfies_requirement() {
+    let (signers, requirement) = medical();
+    let trusted: Vec<_> = signers.iter().map(RootSigner::trusted).collect();
+    let b = batch(0);
+    let sigs: Vec<_> = signers.iter().map(|s| s.sign(&b)).collect();
+
+    let report = verify_root_signatures(&b, &sigs, &trusted, &requirement);
+
+    assert!(report.satisfied);
+    assert_eq!(
+        report.signed_roles,
+        vec!["operator", "regulator", "auditor"]
+    );
+    assert!(report.missing_roles.is_empty());
+    assert!(report.rejected.is_empty());
+}
+
+#[test]
+fn missing_party_is_reported() {
+    let (signers, requirement) = medical();
+    let trusted: Vec<_> = signers.iter().map(RootSigner::trusted).collect();
+    let b = batch(0);
+    let sigs = vec![signers[0].sign(&b), signers[2].sign(&b)];
+
+    let report = verify_root_signatures(&b, &sigs, &trusted, &requirement);
+
+    assert!(!report.satisfied);
+    assert_eq!(report.missing_roles, vec!["regulator"]);
+}
+
+#[test]
+fn partial_threshold_is_met() {
+    let (signers, mut requirement) = medical();
+    requirement.threshold = 2;
+    let trusted: Vec<_> = signers.iter().map(RootSigner::trusted).collect();
+    let b = batch(0);
+    let sigs = vec![signers[0].sign(&b), signers[1].sign(&b)];
+
+    assert!(verify_root_signatures(&b, &sigs, &trusted, &requirement).satisfied);
+}
+
+#[test]
+fn signature_for_other_batch_is_rejected() {
+    let (signers, requirement) = medical();
+    let trusted: Vec<_> = signers.iter().map(RootSigner::trusted).collect();
+    let b = batch(1);
+    let mut sig = signers[0].sign(&batch(0));
+    let report = verify_root_signatures(&b, &[sig.clone()], &trusted, &requirement);
+    assert_eq!(
+        report.rejected,
+        vec![("operator".to_string(), SignatureRejection::WrongRoot)]
+    );
+
+    // Relabelling the signature does not make it valid for the other batch.
+    sig.batch_index = 1;
+    let report = verify_root_signatures(&b, &[sig], &trusted, &requirement);
+    assert_eq!(
+        report.rejected,
+        vec![("operator".to_string(), SignatureRejection::BadSignature)]
+    );
+}
+
+#[test]
+fn untrusted_key_and_impersonation_are_rejected() {
+    let (signers, requirement) = medical();
+    let trusted: Vec<_> = signers.iter().map(RootSigner::trusted).collect();
+    let b = batch(0);
+
+    let forged_regulator = signer("regulator", Some("bfarm"), 9).sign(&b);
+    let wrong_identity = signer("regulator", Some("someone-else"), 2).sign(&b);
+    let stranger = signer("janitor", None, 4).sign(&b);
+
+    let report = verify_root_signatures(
+        &b,
+        &[forged_regulator, wrong_identity, stranger],
+        &trusted,
+        &requirement,
+    );
+
+    assert!(report.signed_roles.is_empty());
+    assert_eq!(
+        report.rejected,
+        vec![
+            ("regulator".to_string(), SignatureRejection::BadSignature),
+            (
+                "regulator".to_string(),
+                SignatureRejection::SignerIdMismatch {
+                    expected: "bfarm".to_string()
+                }
+            ),
+            ("janitor".to_string(), SignatureRejection::UnknownSigner),
+        ]
+    );
+}
+
+#[test]
+fn duplicate_role_counts_once() {
+    let (signers, requirement) = medical();
+    let trusted: Vec<_> = signers.iter().map(RootSigner::trusted).collect();
+    let b = batch(0);
+    let sigs = vec![
+        signers[0].sign(&b),
+        signers[0].sign(&b),
+        signers[2].sign(&b),
+    ];
+
+    let report = verify_root_signatures(&b, &sigs, &trusted, &requirement);
+
+    assert!(!report.satisfied);
+    assert_eq!(report.signed_roles, vec!["operator", "auditor"]);
+    assert_eq!(
+        report.rejected,
+        vec![("operator".to_string(), SignatureRejection::DuplicateRole)]
+    );
+}
+
+#[test]
+fn malformed_signature_is_rejected() {
+    let (signers, requirement) = medical();
+    let trusted: Vec<_> = signers.iter().map(RootSigner::trusted).collect();
+    let b = batch(0);
+    let mut sig = signers[0].sign(&b);
+    sig.signature = "zz".to_string();
+
+    let report = verify_root_signatures(&b, &[sig], &trusted, &requirement);
+    assert_eq!(
+        report.rejected,
+        vec![("operator".to_string(), SignatureRejection::Malformed)]
+    );
+}
@@ -0,0 +1,251 @@
+use std::path::PathBuf;
+
+use adr_core::killswitch::{KillSwitchChannel, StopSignal};
+use adr_core::{
+    verify_chain, verify_entries, ActionKind, ActionLogEntry, AdrRuntime, AuditSink,
+    ChainBreakReason, Effect, Evidence, ExecClass, JsonlAuditStore, Node, TornTail,
+    CURRENT_HASH_VERSION,
+};
+use uuid::Uuid;
+
+struct NoSignal;
+impl KillSwitchChannel for NoSignal {
+    fn poll(&self) -> Option<StopSignal> {
+        None
+    }
+}
+
+fn temp_log() -> PathBuf {
+    std::env::temp_dir().join(format!("adr-audit-{}.jsonl", Uuid::new_v4()))
+}
+
+fn entry(prev_hash: Option<String>, success: bool) -> ActionLogEntry {
+    ActionLogEntry {
+        hash_version: CURRENT_HASH_VERSION,
+        node_id: Uuid::new_v4(),
+        kind: ActionKind::Execute,
+        timestamp_utc: "2026-03-03T10:00:00Z".to_string(),
+        success,
+        evidence: Evidence {
+            graph_version: "0.1".to_string(),
+            policy_version: "policy-1".to_string(),
+            contract_hash: "contract-1".to_string(),
+        },
+        detail: None,
+        trust: None,
+        prev_hash,
+        entry_hash: String::new(),
+    }
+    .with_computed_hash()
+}
+
+fn write_chain(path: &PathBuf, n: usize) -> Vec<ActionLogEntry> {
+    let mut store = JsonlAuditStore::open(path).unwrap();
+    let mut written = Vec::new();
+    for _ in 0..n {
+        let e = entry(store.head(), true);
+        store.append(e.clone()).unwrap();
+        written.push(e);
+    }
+    written
+}
+
+#[test]
+fn store_appends_and_verifies() {
+    let path = temp_log();
+    let written = write_chain(&path, 3);
+
+    assert_eq!(verify_chain(&path), Ok(3));
+    assert_eq!(verify_entries(&written), Ok(3));
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn reopened_store_resumes_from_last_hash() {
+    let path = temp_log();
+    let written = write_chain(&path, 2);
+
+    let mut store = JsonlAuditStore::open(&path).unwrap();
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.head(), Some(written[1].entry_hash.clone()));
+
+    store.append(entry(store.head(), false)).unwrap();
+    assert_eq!(verify_chain(&path), Ok(3));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn store_refuses_entry_not_linked_to_head() {
+    let path = temp_log();
+    write_chain(&path, 1);
+
+    let mut store = JsonlAuditStore::open(&path).unwrap();
+    let err = store.append(entry(None, true)).unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(verify_chain(&path), Ok(1));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn runtime_writes_verifiable_log() {
+    let path = temp_log();
+    let store = JsonlAuditStore::open(&path).unwrap();
+    let mut rt = AdrRuntime::new(NoSignal).with_audit_sink(Box::new(store));
+
+    let node = Node {
+        id: Uuid::new_v4(),
+        label: "noop".to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect: Effect::None,
+        capabilities: vec![1 << 5],
+        dependencies: vec![],
+    };
+    rt.execute_node(&node).unwrap_err();
+    rt.capabilities().allow_mask(1 << 5);
+    rt.execute_node(&node).unwrap();
+
+    assert_eq!(verify_chain(&path), Ok(2));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn tampered_entry_reports_hash_mismatch_index() {
+    let path = temp_log();
+    write_chain(&path, 3);
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    lines[1] = lines[1].replace("\"success\":true", "\"success\":false");
+    std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+
+    let brk = verify_chain(&path).unwrap_err();
+    assert_eq!(brk.index, 1);
+    assert!(matches!(brk.reason, ChainBreakReason::HashMismatch { .. }));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn removed_entry_reports_prev_hash_mismatch() {
+    let path = temp_log();
+    write_chain(&path, 3);
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    std::fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+
+    let brk = verify_chain(&path).unwrap_err();
+    assert_eq!(brk.index, 1);
+    assert!(matches!(
+        brk.reason,
+        ChainBreakReason::PrevHashMismatch { .. }
+    ));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn first_entry_must_be_genesis() {
+    let chained = entry(Some("not-genesis".to_string()), true);
+
+    let brk = verify_entries([&chained]).unwrap_err();
+    assert_eq!(brk.index, 0);
+    assert_eq!(
+        brk.reason,
+        ChainBreakReason::PrevHashMismatch {
+            expected: None,
+            found: Some("not-genesis".to_string()),
+        }
+    );
+}
+
+#[test]
+fn truncated_record_is_malformed() {
+    let path = temp_log();
+    write_chain(&path, 2);
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, &content[..content.len() - 10]).unwrap();
+
+    let brk = verify_chain(&path).unwrap_err();
+    assert_eq!(brk.index, 1);
+    assert!(matches!(brk.reason, ChainBreakReason::Malformed(_)));
+    assert!(JsonlAuditStore::open(&path).is_err());
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn blank_lines_are_skipped_when_opening_exporting_and_verifying() {
+    let path = temp_log();
+    let written = write_chain(&path, 2);
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, content.replacen('\n', "\n\n", 1) + "\n  \n").unwrap();
+
+    let mut store = JsonlAuditStore::open(&path).unwrap();
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.head(), Some(written[1].entry_hash.clone()));
+    assert_eq!(store.export().unwrap(), written);
+    assert_eq!(verify_chain(&path), Ok(2));
+
+    store.append(entry(store.head(), true)).unwrap();
+    assert_eq!(verify_chain(&path), Ok(3));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn torn_tail_is_truncated_only_on_request() {
+    let path = temp_log();
+    let written = write_chain(&path, 2);
+    let content = std::fs::read_to_string(&path).unwrap();
+    let torn = &content[..content.len() - 10];
+    std::fs::write(&path, torn).unwrap();
+
+    assert!(JsonlAuditStore::open(&path).is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), torn);
+
+    let mut store = JsonlAuditStore::open_with(&path, TornTail::Truncate).unwrap();
+    assert_eq!(store.len(), 1);
+    assert_eq!(store.head(), Some(written[0].entry_hash.clone()));
+    let first_line = content.find('\n').unwrap() + 1;
+    assert_eq!(store.truncated_bytes(), (torn.len() - first_line) as u64);
+
+    store.append(entry(store.head(), true)).unwrap();
+    assert_eq!(verify_chain(&path), Ok(2));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn corrupt_last_record_with_newline_is_never_truncated() {
+    let path = temp_log();
+    write_chain(&path, 2);
+    let content = std::fs::read_to_string(&path).unwrap();
+    let corrupt = content.replace("\"hash_version\"", "\"hash_version");
+    std::fs::write(&path, &corrupt).unwrap();
+
+    assert!(JsonlAuditStore::open_with(&path, TornTail::Truncate).is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), corrupt);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn complete_last_record_missing_its_newline_gets_one() {
+    let path = temp_log();
+    write_chain(&path, 1);
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, content.trim_end()).unwrap();
+
+    let mut store = JsonlAuditStore::open(&path).unwrap();
+    store.append(entry(store.head(), true)).unwrap();
+
+    assert_eq!(verify_chain(&path), Ok(2));
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,172 @@
+use adr_core::capability::CapabilitySet;
+use adr_core::killswitch::StopLatch;
+use adr_core::{
+    cap_scope_hash, ActionKind, AdrRuntime, AdrRuntimeError, CapabilityScope, Compensations,
+    Effect, ExecClass, ExecutionPlan, FreezeTrigger, Graph, GraphHeader, MemoryAuditSink, Node,
+    RuntimeState, CAP_FS_WRITE, CAP_NET_EXTERNAL,
+};
+use uuid::Uuid;
+
+const POLICY: &str = "policy-sha";
+
+fn graph() -> Graph {
+    Graph {
+        header: GraphHeader {
+            graph_version: "0.1".to_string(),
+            deterministic_mode: false,
+        },
+        nodes: vec![Node {
+            id: Uuid::new_v4(),
+            label: "write".to_string(),
+            exec_class: ExecClass::Orchestrated,
+            effect: Effect::FsWrite,
+            capabilities: vec![CAP_FS_WRITE],
+            dependencies: vec![],
+        }],
+    }
+}
+
+fn stamped(graph: &Graph, granted: u64, policy_hash: &str) -> ExecutionPlan {
+    ExecutionPlan {
+        nodes: graph.nodes.iter().map(|n| n.id).collect(),
+        parallel: vec![],
+        checkpoints: vec![],
+        human_gates: vec![],
+        capabilities: vec![CAP_FS_WRITE],
+        cap_scope_hash: Some(cap_scope_hash(
+            &grants(granted, &[]),
+            policy_hash,
+            &graph.header.graph_version,
+        )),
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    }
+}
+
+fn grants(mask: u64, scopes: &[&str]) -> CapabilitySet {
+    let caps = CapabilitySet::new();
+    caps.allow_mask(mask);
+    for scope in scopes {
+        caps.allow_scoped(CapabilityScope::parse(scope).unwrap());
+    }
+    caps
+}
+
+fn runtime() -> (AdrRuntime<StopLatch>, MemoryAuditSink) {
+    let log = MemoryAuditSink::new();
+    let rt = AdrRuntime::new(StopLatch::new())
+        .with_audit_sink(Box::new(log.clone()))
+        .with_policy_hash(POLICY);
+    rt.capabilities().allow_mask(CAP_FS_WRITE);
+    (rt, log)
+}
+
+#[test]
+fn hash_covers_capabilities_policy_and_graph_version() {
+    let fs = grants(CAP_FS_WRITE, &[]);
+    let base = cap_scope_hash(&fs, POLICY, "0.1");
+
+    assert_eq!(
+        base,
+        cap_scope_hash(&grants(CAP_FS_WRITE, &[]), POLICY, "0.1")
+    );
+    assert_ne!(
+        base,
+        cap_scope_hash(&grants(CAP_NET_EXTERNAL, &[]), POLICY, "0.1")
+    );
+    assert_ne!(base, cap_scope_hash(&fs, "other", "0.1"));
+    assert_ne!(base, cap_scope_hash(&fs, POLICY, "0.2"));
+}
+
+#[test]
+fn hash_covers_scoped_grants() {
+    let hash = |mask, scopes: &[&str]| cap_scope_hash(&grants(mask, scopes), POLICY, "0.1");
+    let out = hash(0, &["fs:/data/out"]);
+
+    // Narrowing or widening a scope keeps the mask but not the hash.
+    assert_ne!(out, hash(0, &["fs:/"]));
+    assert_ne!(out, hash(0, &["fs:/data/out/tmp"]));
+    assert_ne!(out, hash(0, &["fs:/data/out", "fs:/etc"]));
+    assert_ne!(out, hash(CAP_FS_WRITE, &["fs:/data/out"]));
+    assert_ne!(out, hash(0, &["net:*.example.com", "fs:/data/out"]));
+
+    // The grant set counts, not the order it was granted in.
+    assert_eq!(out, hash(0, &["fs:/data/out", "fs:/data/out"]));
+    assert_eq!(
+        hash(0, &["net:*.example.com:443", "fs:/data/out"]),
+        hash(0, &["fs:/data/out", "net:*.example.com:443"])
+    );
+}
+
+#[test]
+fn widened_scope_refuses_the_plan() {
+    let log = MemoryAuditSink::new();
+    let mut rt = AdrRuntime::new(StopLatch::new())
+        .with_audit_sink(Box::new(log.clone()))
+        .with_policy_hash(POLICY);
+    rt.capabilities()
+        .allow_scoped(CapabilityScope::parse("fs:/data/out").unwrap());
+    let graph = graph();
+    let plan = ExecutionPlan {
+        cap_scope_hash: Some(cap_scope_hash(&grants(0, &["fs:/data/out"]), POLICY, "0.1")),
+        ..stamped(&graph, CAP_FS_WRITE, POLICY)
+    };
+    rt.capabilities()
+        .allow_scoped(CapabilityScope::parse("fs:/").unwrap());
+
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+
+    assert!(matches!(err, AdrRuntimeError::CapScopeMismatch { .. }));
+    assert!(log.entries().iter().all(|e| e.kind != ActionKind::Execute));
+}
+
+#[test]
+fn plan_runs_in_the_scope_it_was_resolved_in() {
+    let (mut rt, _log) = runtime();
+    let graph = graph();
+    let plan = stamped(&graph, CAP_FS_WRITE, POLICY);
+
+    assert_eq!(rt.execute_plan(&plan, &graph).unwrap(), plan.nodes);
+}
+
+#[test]
+fn changed_scope_or_policy_refuses_the_plan() {
+    let (mut rt, log) = runtime();
+    let graph = graph();
+
+    let revoked = stamped(&graph, CAP_FS_WRITE | CAP_NET_EXTERNAL, POLICY);
+    let err = rt.execute_plan(&revoked, &graph).unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::CapScopeMismatch { .. }));
+
+    let other_policy = stamped(&graph, CAP_FS_WRITE, "older-policy");
+    let err = rt.execute_plan(&other_policy, &graph).unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::CapScopeMismatch { .. }));
+
+    assert_eq!(rt.state(), RuntimeState::Running);
+    assert!(log.entries().iter().all(|e| e.kind != ActionKind::Execute));
+}
+
+#[test]
+fn mismatch_freezes_when_the_trigger_is_active() {
+    let (rt, log) = runtime();
+    let mut rt = rt.with_freeze_triggers([FreezeTrigger::CapScopeHashMismatch]);
+    let graph = graph();
+    let plan = stamped(&graph, CAP_FS_WRITE | CAP_NET_EXTERNAL, POLICY);
+
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+
+    assert!(matches!(
+        err,
+        AdrRuntimeError::FreezeTriggered(FreezeTrigger::CapScopeHashMismatch)
+    ));
+    assert_eq!(rt.state(), RuntimeState::Frozen);
+    let freeze = log
+        .entries()
+        .into_iter()
+        .find(|e| e.kind == ActionKind::Freeze)
+        .unwrap();
+    assert!(freeze
+        .detail
+        .unwrap()
+        .contains("cause=freeze_trigger=cap_scope_hash_mismatch"));
+}
@@ -0,0 +1,255 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use adr_core::killswitch::StopLatch;
+use adr_core::{
+    AdrRuntime, AdrRuntimeError, CapabilityScope, Effect, EffectError, EffectHandler, EffectOutput,
+    EffectPayload, EffectTarget, ExecClass, FsWriteHandler, Node, CAP_ACTUATOR_CONTROL,
+    CAP_FS_WRITE, CAP_NET_EXTERNAL,
+};
+use uuid::Uuid;
+
+fn scope(text: &str) -> CapabilityScope {
+    CapabilityScope::parse(text).unwrap()
+}
+
+fn path(path: &str) -> EffectTarget {
+    EffectTarget::Path(PathBuf::from(path))
+}
+
+fn host(host: &str, port: Option<u16>) -> EffectTarget {
+    EffectTarget::Host {
+        host: host.to_string(),
+        port,
+    }
+}
+
+fn actuator(id: &str) -> EffectTarget {
+    EffectTarget::Actuator(id.to_string())
+}
+
+struct Recording(Arc<Mutex<usize>>);
+impl EffectHandler for Recording {
+    fn handle(&self, _node: &Node, _payload: &EffectPayload) -> Result<EffectOutput, EffectError> {
+        *self.0.lock().unwrap() += 1;
+        Ok(EffectOutput::None)
+    }
+}
+
+fn node(effect: Effect, capabilities: Vec<u64>) -> Node {
+    Node {
+        id: Uuid::new_v4(),
+        label: "scoped".to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect,
+        capabilities,
+        dependencies: vec![],
+    }
+}
+
+#[test]
+fn scopes_parse_from_spec_notation() {
+    assert_eq!(
+        scope("fs:/data/out"),
+        CapabilityScope::PathPrefix(PathBuf::from("/data/out"))
+    );
+    assert_eq!(
+        scope("net:API.example.com:8443"),
+        CapabilityScope::Host {
+            pattern: "api.example.com".to_string(),
+            port: Some(8443)
+        }
+    );
+    assert_eq!(
+        scope("actuator_control:valve-3"),
+        CapabilityScope::Actuator("valve-3".to_string())
+    );
+    assert_eq!(scope("net:*.example.com").class(), CAP_NET_EXTERNAL);
+    assert_eq!(CapabilityScope::parse("teleport:x"), None);
+    assert_eq!(CapabilityScope::parse("fs:"), None);
+    assert_eq!(CapabilityScope::parse("fs_write"), None);
+}
+
+#[test]
+fn targets_match_paths_hosts_ports_and_actuators() {
+    let out = scope("fs:/data/out");
+    assert!(out.permits(&path("/data/out/a.json")));
+    assert!(!out.permits(&path("/data/output")));
+    assert!(!out.permits(&path("/data/out/../etc")));
+
+    let sub = scope("net:*.example.com");
+    assert!(sub.permits(&host("api.example.com", Some(443))));
+    assert!(!sub.permits(&host("example.com", Some(443))));
+    assert!(!sub.permits(&host("example.com.evil", Some(443))));
+
+    let port = scope("net:api.example.com:443");
+    assert!(port.permits(&host("API.example.com", Some(443))));
+    assert!(!port.permits(&host("api.example.com", Some(80))));
+    assert!(!port.permits(&host("api.example.com", None)));
+
+    let valve = scope("actuator:valve-3");
+    assert!(valve.permits(&actuator("valve-3")));
+    assert!(!valve.permits(&actuator("valve-4")));
+    assert!(!valve.permits(&path("valve-3")));
+}
+
+#[test]
+fn capability_set_checks_targets_against_scoped_grants() {
+    let rt = AdrRuntime::new(StopLatch::new());
+    let caps = rt.capabilities();
+    let valve = actuator("valve-3");
+    assert!(!caps.permits(&valve));
+
+    assert!(caps.allow_scoped(scope("actuator:valve-3")));
+    assert!(caps.has_mask(CAP_ACTUATOR_CONTROL));
+    assert!(caps.permits(&valve));
+    assert!(!caps.permits(&actuator("valve-4")));
+
+    // An unscoped grant of the class reaches every target.
+    caps.allow_mask(CAP_ACTUATOR_CONTROL);
+    assert!(caps.permits(&actuator("valve-4")));
+
+    caps.revoke_and_seal();
+    assert!(caps.scopes().is_empty());
+    assert!(!caps.permits(&valve));
+    assert!(!caps.allow_scoped(scope("actuator:valve-3")));
+    assert_eq!(caps.mask(), 0);
+}
+
+#[test]
+fn execute_node_refuses_targets_outside_the_granted_scope() {
+    let calls = Arc::new(Mutex::new(0));
+    let mut rt = AdrRuntime::new(StopLatch::new());
+    rt.register_effect_handler(Effect::FsWrite, Box::new(Recording(calls.clone())));
+    rt.register_effect_handler(Effect::NetExternal, Box::new(Recording(calls.clone())));
+    rt.capabilities().allow_scoped(scope("fs:out"));
+    rt.capabilities()
+        .allow_scoped(scope("net:api.example.com:443"));
+    let write = node(Effect::FsWrite, vec![CAP_FS_WRITE]);
+    let send = node(Effect::NetExternal, vec![CAP_NET_EXTERNAL]);
+    let fs = |path: &str| EffectPayload::FsWrite {
+        path: PathBuf::from(path),
+        contents: vec![],
+    };
+    let net = |url: &str| EffectPayload::NetExternal {
+        url: url.to_string(),
+        body: vec![],
+    };
+
+    rt.execute_node_with(&write, &fs("out/emails.json"))
+        .unwrap();
+    rt.execute_node_with(&send, &net("https://api.example.com/v1"))
+        .unwrap();
+
+    for (node, payload) in [
+        (&write, fs("secrets/key")),
+        (&write, fs("out/../secrets/key")),
+        (&send, net("http://api.example.com/v1")),
+        (&send, net("https://evil.example/v1")),
+        (&send, net("not a url")),
+    ] {
+        match rt.execute_node_with(node, &payload) {
+            Err(AdrRuntimeError::CapabilityOutOfScope { capability, .. }) => {
+                assert_eq!(capability, node.capabilities[0])
+            }
+            other => panic!(
+                "expected CapabilityOutOfScope for {:?}, got {:?}",
+                payload, other
+            ),
+        }
+    }
+    assert_eq!(*calls.lock().unwrap(), 2);
+}
+
+fn out_of_scope(result: Result<EffectOutput, AdrRuntimeError>) -> (u64, String) {
+    match result {
+        Err(AdrRuntimeError::CapabilityOutOfScope { capability, target }) => (capability, target),
+        other => panic!("expected CapabilityOutOfScope, got {:?}", other),
+    }
+}
+
+#[test]
+fn absolute_scope_matches_writes_inside_the_handler_root() {
+    let root = std::env::temp_dir().join(format!("adr-scope-{}", Uuid::new_v4()));
+    let handler = FsWriteHandler::new(&root).unwrap();
+    let out = handler.root().join("out");
+    let mut rt = AdrRuntime::new(StopLatch::new());
+    rt.register_effect_handler(Effect::FsWrite, Box::new(handler));
+    rt.capabilities()
+        .allow_scoped(scope(&format!("fs:{}", out.display())));
+    let write = node(Effect::FsWrite, vec![CAP_FS_WRITE]);
+    let fs = |path: &str| EffectPayload::FsWrite {
+        path: PathBuf::from(path),
+        contents: b"{}".to_vec(),
+    };
+
+    rt.execute_node_with(&write, &fs("out/emails.json"))
+        .unwrap();
+    assert_eq!(std::fs::read(out.join("emails.json")).unwrap(), b"{}");
+
+    let (capability, target) = out_of_scope(rt.execute_node_with(&write, &fs("secrets/key")));
+    assert_eq!(capability, CAP_FS_WRITE);
+    assert_eq!(
+        target,
+        format!(
+            "fs:{}",
+            root.canonicalize().unwrap().join("secrets/key").display()
+        )
+    );
+    assert!(!root.join("secrets").exists());
+}
+
+#[test]
+fn targets_of_undeclared_classes_are_refused() {
+    let calls = Arc::new(Mutex::new(0));
+    let mut rt = AdrRuntime::new(StopLatch::new());
+    rt.register_effect_handler(Effect::FsWrite, Box::new(Recording(calls.clone())));
+    rt.capabilities()
+        .allow_mask(CAP_FS_WRITE | CAP_NET_EXTERNAL);
+    let payload = EffectPayload::FsWrite {
+        path: PathBuf::from("out/emails.json"),
+        contents: vec![],
+    };
+
+    // Granted without a scope, but the node does not declare the class.
+    for node in [
+        node(Effect::FsWrite, vec![]),
+        node(Effect::FsWrite, vec![CAP_NET_EXTERNAL]),
+        node(Effect::None, vec![]),
+    ] {
+        let (capability, target) = out_of_scope(rt.execute_node_with(&node, &payload));
+        assert_eq!(capability, CAP_FS_WRITE);
+        assert_eq!(target, "fs:out/emails.json");
+    }
+    assert_eq!(*calls.lock().unwrap(), 0);
+}
+
+#[test]
+fn actuator_commands_are_checked_against_actuator_scopes() {
+    let calls = Arc::new(Mutex::new(0));
+    let mut rt = AdrRuntime::new(StopLatch::new());
+    rt.register_effect_handler(Effect::ActuatorControl, Box::new(Recording(calls.clone())));
+    rt.capabilities().allow_scoped(scope("actuator:valve-3"));
+    let control = node(Effect::ActuatorControl, vec![CAP_ACTUATOR_CONTROL]);
+    let command = |actuator: &str| EffectPayload::ActuatorCommand {
+        actuator: actuator.to_string(),
+        command: "close".to_string(),
+    };
+
+    rt.execute_node_with(&control, &command("valve-3")).unwrap();
+    let err = rt
+        .execute_node_with(&control, &command("valve-4"))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "capability_out_of_scope capability=0x4 target=actuator:valve-4"
+    );
+    assert_eq!(*calls.lock().unwrap(), 1);
+
+    // The effect needs its class even without a payload.
+    rt.capabilities().revoke_all();
+    assert!(matches!(
+        rt.execute_node(&control),
+        Err(AdrRuntimeError::CapabilityNotGranted(CAP_ACTUATOR_CONTROL))
+    ));
+}
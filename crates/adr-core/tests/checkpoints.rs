@@ -0,0 +1,188 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use adr_core::killswitch::{StopLatch, StopSignal};
+use adr_core::{
+    verify_entries, ActionKind, AdrRuntime, AdrRuntimeError, Compensations, Effect, ExecClass,
+    ExecutionPlan, Graph, GraphHeader, ManualClock, MemoryAuditSink, MerkleAnchorer, MerkleConfig,
+    Node, NodeId, RuntimeState,
+};
+use uuid::Uuid;
+
+fn node(label: &str) -> Node {
+    Node {
+        id: Uuid::new_v4(),
+        label: label.to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect: Effect::None,
+        capabilities: vec![],
+        dependencies: vec![],
+    }
+}
+
+/// a -> b -> c with a checkpoint before b; layers [a], [b, c].
+fn plan() -> (Graph, ExecutionPlan) {
+    let (a, b, c) = (node("a"), node("b"), node("c"));
+    let plan = ExecutionPlan {
+        nodes: vec![a.id, b.id, c.id],
+        parallel: vec![vec![a.id], vec![b.id, c.id]],
+        checkpoints: vec![b.id],
+        human_gates: vec![],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    };
+    let graph = Graph {
+        header: GraphHeader {
+            graph_version: "0.1".to_string(),
+            deterministic_mode: false,
+        },
+        nodes: vec![a, b, c],
+    };
+    (graph, plan)
+}
+
+fn setup() -> (
+    AdrRuntime<StopLatch>,
+    StopLatch,
+    MerkleAnchorer,
+    MemoryAuditSink,
+) {
+    let latch = StopLatch::new();
+    let log = MemoryAuditSink::new();
+    let anchorer = MerkleAnchorer::new(
+        Box::new(log.clone()),
+        MerkleConfig::new(Duration::from_secs(3600)),
+        Arc::new(ManualClock::new()),
+    );
+    let rt = AdrRuntime::new(latch.clone()).with_audit_sink(Box::new(anchorer.clone()));
+    (rt, latch, anchorer, log)
+}
+
+fn checkpoint_details(log: &MemoryAuditSink) -> Vec<(NodeId, String)> {
+    log.entries()
+        .into_iter()
+        .filter(|e| e.kind == ActionKind::Checkpoint)
+        .map(|e| (e.node_id, e.detail.unwrap()))
+        .collect()
+}
+
+#[test]
+fn plan_pauses_at_checkpoint_and_seals_the_segment() {
+    let (mut rt, _latch, anchorer, log) = setup();
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+
+    assert!(matches!(err, AdrRuntimeError::CheckpointReached(id) if id == b));
+    assert_eq!(rt.pending_checkpoint(), Some(b));
+
+    // Execute(a) + pause entry, sealed into one batch.
+    let batches = anchorer.batches();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].entry_hashes.len(), 2);
+    assert_eq!(
+        checkpoint_details(&log),
+        vec![(b, "paused executed=1".to_string())]
+    );
+}
+
+#[test]
+fn acknowledgement_continues_the_plan() {
+    let (mut rt, _latch, _anchorer, log) = setup();
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    let executed = rt.acknowledge_checkpoint(b, "alice").unwrap();
+
+    assert_eq!(executed, plan.nodes);
+    assert_eq!(rt.pending_checkpoint(), None);
+    assert_eq!(
+        checkpoint_details(&log)[1],
+        (b, "acknowledged operator=alice".to_string())
+    );
+    assert!(verify_entries(&log.entries()).is_ok());
+}
+
+#[test]
+fn paused_plan_cannot_be_skipped() {
+    let (mut rt, _latch, _anchorer, _log) = setup();
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+
+    assert!(matches!(
+        rt.acknowledge_checkpoint(b, "alice"),
+        Err(AdrRuntimeError::NoPendingCheckpoint)
+    ));
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    // Neither a new plan nor an acknowledgement for another node passes.
+    assert!(matches!(
+        rt.execute_plan(&plan, &graph),
+        Err(AdrRuntimeError::CheckpointPending(id)) if id == b
+    ));
+    assert!(matches!(
+        rt.acknowledge_checkpoint(plan.nodes[2], "alice"),
+        Err(AdrRuntimeError::CheckpointPending(id)) if id == b
+    ));
+    assert_eq!(rt.pending_checkpoint(), Some(b));
+}
+
+#[test]
+fn parallel_plan_pauses_before_the_layer() {
+    let (mut rt, _latch, _anchorer, log) = setup();
+    let (graph, plan) = plan();
+    let (a, b) = (plan.nodes[0], plan.nodes[1]);
+
+    let err = rt
+        .execute_plan_parallel(&plan, &graph, &Compensations::new(), 2)
+        .unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::CheckpointReached(id) if id == b));
+
+    let executed_before: Vec<_> = log
+        .entries()
+        .into_iter()
+        .filter(|e| e.kind == ActionKind::Execute)
+        .map(|e| e.node_id)
+        .collect();
+    assert_eq!(executed_before, vec![a]);
+
+    assert_eq!(rt.acknowledge_checkpoint(b, "alice").unwrap(), plan.nodes);
+}
+
+#[test]
+fn soft_stop_while_paused_compensates_on_acknowledgement() {
+    let (mut rt, latch, _anchorer, _log) = setup();
+    let (graph, plan) = plan();
+    let b = plan.nodes[1];
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    latch.set(StopSignal::SoftStop);
+    let err = rt.acknowledge_checkpoint(b, "alice").unwrap_err();
+
+    let AdrRuntimeError::SoftStopAborted(report) = err else {
+        panic!("expected SoftStopAborted, got {:?}", err);
+    };
+    assert_eq!(report.committed, vec![plan.nodes[0]]);
+    assert_eq!(rt.state(), RuntimeState::Stopping);
+}
+
+#[test]
+fn plan_without_a_policy_checkpoint_is_refused() {
+    let (rt, _latch, _anchorer, log) = setup();
+    let mut rt = rt.with_checkpoint_before([Effect::FsWrite]);
+    let (mut graph, mut plan) = plan();
+    graph.nodes[2].effect = Effect::FsWrite;
+    let c = plan.nodes[2];
+
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::CheckpointMissing(id) if id == c));
+    assert!(log.entries().is_empty());
+
+    plan.checkpoints.push(c);
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::CheckpointReached(_)));
+}
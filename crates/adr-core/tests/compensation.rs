@@ -0,0 +1,292 @@
+use std::sync::{Arc, Mutex};
+
+use adr_core::killswitch::{StopLatch, StopSignal};
+use adr_core::{
+    verify_entries, ActionKind, AdrRuntime, AdrRuntimeError, CompensationError, CompensationReport,
+    Compensations, Compensator, Effect, EffectError, EffectHandler, EffectOutput, EffectPayload,
+    ExecClass, ExecutionPlan, Graph, GraphHeader, MemoryAuditSink, Node, NodeId, RuntimeState,
+};
+use uuid::Uuid;
+
+/// Raises `signal` on the latch while executing the node labelled `at`.
+struct SignalAt {
+    latch: StopLatch,
+    at: &'static str,
+    signal: StopSignal,
+}
+impl EffectHandler for SignalAt {
+    fn handle(&self, node: &Node, _: &EffectPayload) -> Result<EffectOutput, EffectError> {
+        if node.label == self.at {
+            self.latch.set(self.signal);
+        }
+        Ok(EffectOutput::None)
+    }
+}
+
+/// Records the labels it compensated, in call order.
+struct Undo(Arc<Mutex<Vec<String>>>);
+impl Compensator for Undo {
+    fn compensate(&self, node: &Node) -> Result<(), EffectError> {
+        self.0.lock().unwrap().push(node.label.clone());
+        Ok(())
+    }
+}
+
+struct Broken;
+impl Compensator for Broken {
+    fn compensate(&self, _: &Node) -> Result<(), EffectError> {
+        Err(EffectError::Io("disk gone".to_string()))
+    }
+}
+
+struct Panicking;
+impl Compensator for Panicking {
+    fn compensate(&self, _: &Node) -> Result<(), EffectError> {
+        panic!("undo blew up")
+    }
+}
+
+/// Escalates to Hard Stop when called.
+struct HardStopOnUndo(StopLatch);
+impl Compensator for HardStopOnUndo {
+    fn compensate(&self, _: &Node) -> Result<(), EffectError> {
+        self.0.set(StopSignal::HardStop);
+        Ok(())
+    }
+}
+
+fn chain(labels: &[&str]) -> (Graph, ExecutionPlan) {
+    let nodes: Vec<Node> = labels
+        .iter()
+        .map(|l| Node {
+            id: Uuid::new_v4(),
+            label: l.to_string(),
+            exec_class: ExecClass::Orchestrated,
+            effect: Effect::None,
+            capabilities: vec![],
+            dependencies: vec![],
+        })
+        .collect();
+    let plan = ExecutionPlan {
+        nodes: nodes.iter().map(|n| n.id).collect(),
+        parallel: vec![],
+        checkpoints: vec![],
+        human_gates: vec![],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    };
+    let graph = Graph {
+        header: GraphHeader {
+            graph_version: "0.1".to_string(),
+            deterministic_mode: false,
+        },
+        nodes,
+    };
+    (graph, plan)
+}
+
+/// Runtime that soft-stops while executing the node labelled `at`.
+fn setup(at: &'static str) -> (AdrRuntime<StopLatch>, StopLatch, MemoryAuditSink) {
+    let latch = StopLatch::new();
+    let log = MemoryAuditSink::new();
+    let mut rt = AdrRuntime::new(latch.clone()).with_audit_sink(Box::new(log.clone()));
+    rt.register_effect_handler(
+        Effect::None,
+        Box::new(SignalAt {
+            latch: latch.clone(),
+            at,
+            signal: StopSignal::SoftStop,
+        }),
+    );
+    (rt, latch, log)
+}
+
+fn report(err: AdrRuntimeError) -> CompensationReport {
+    match err {
+        AdrRuntimeError::SoftStopAborted(report) => report,
+        other => panic!("expected SoftStopAborted, got {:?}", other),
+    }
+}
+
+fn id(graph: &Graph, label: &str) -> NodeId {
+    graph.nodes.iter().find(|n| n.label == label).unwrap().id
+}
+
+#[test]
+fn soft_stop_compensates_executed_nodes_in_reverse_order() {
+    let (mut rt, _latch, log) = setup("c");
+    let undone = Arc::new(Mutex::new(Vec::new()));
+    rt.register_compensator("undo", Box::new(Undo(Arc::clone(&undone))));
+
+    let (graph, plan) = chain(&["a", "b", "c", "d"]);
+    let compensations: Compensations = [(id(&graph, "a"), "undo"), (id(&graph, "c"), "undo")]
+        .into_iter()
+        .collect();
+
+    let err = rt
+        .execute_plan_with_compensations(&plan, &graph, &compensations)
+        .unwrap_err();
+    let report = report(err);
+
+    // c was in flight when the stop arrived and completes; d never starts.
+    assert_eq!(*undone.lock().unwrap(), vec!["c", "a"]);
+    assert_eq!(report.rolled_back, vec![id(&graph, "c"), id(&graph, "a")]);
+    assert_eq!(report.committed, vec![id(&graph, "b")]);
+    assert!(report.is_clean());
+    assert_eq!(rt.state(), RuntimeState::Stopping);
+
+    let compensated: Vec<_> = log
+        .entries()
+        .into_iter()
+        .filter(|e| e.kind == ActionKind::Compensate)
+        .map(|e| (e.node_id, e.success, e.detail.unwrap()))
+        .collect();
+    assert_eq!(
+        compensated,
+        vec![
+            (id(&graph, "c"), true, "action=undo".to_string()),
+            (id(&graph, "a"), true, "action=undo".to_string()),
+        ]
+    );
+    assert!(verify_entries(&log.entries()).is_ok());
+}
+
+#[test]
+fn failed_and_unknown_compensations_are_reported_and_audited() {
+    let (mut rt, _latch, log) = setup("b");
+    rt.register_compensator("broken", Box::new(Broken));
+
+    let (graph, plan) = chain(&["a", "b", "c"]);
+    let compensations: Compensations = [(id(&graph, "a"), "broken"), (id(&graph, "b"), "missing")]
+        .into_iter()
+        .collect();
+
+    let report = report(
+        rt.execute_plan_with_compensations(&plan, &graph, &compensations)
+            .unwrap_err(),
+    );
+
+    assert!(report.rolled_back.is_empty());
+    assert_eq!(
+        report.failed,
+        vec![
+            (
+                id(&graph, "b"),
+                CompensationError::UnknownAction("missing".to_string())
+            ),
+            (
+                id(&graph, "a"),
+                CompensationError::Failed(EffectError::Io("disk gone".to_string()))
+            ),
+        ]
+    );
+    assert!(!report.is_clean());
+
+    let failures = log
+        .entries()
+        .into_iter()
+        .filter(|e| e.kind == ActionKind::Compensate && !e.success)
+        .count();
+    assert_eq!(failures, 2);
+}
+
+#[test]
+fn panicking_compensator_fails_only_its_node() {
+    let (mut rt, _latch, log) = setup("b");
+    let undone = Arc::new(Mutex::new(Vec::new()));
+    rt.register_compensator("panics", Box::new(Panicking));
+    rt.register_compensator("undo", Box::new(Undo(Arc::clone(&undone))));
+
+    let (graph, plan) = chain(&["a", "b", "c"]);
+    let compensations: Compensations = [(id(&graph, "a"), "undo"), (id(&graph, "b"), "panics")]
+        .into_iter()
+        .collect();
+
+    let report = report(
+        rt.execute_plan_with_compensations(&plan, &graph, &compensations)
+            .unwrap_err(),
+    );
+
+    assert_eq!(report.rolled_back, vec![id(&graph, "a")]);
+    assert_eq!(
+        report.failed,
+        vec![(
+            id(&graph, "b"),
+            CompensationError::Failed(EffectError::HandlerPanicked("undo blew up".to_string()))
+        )]
+    );
+    assert_eq!(*undone.lock().unwrap(), vec!["a"]);
+
+    let failed = log
+        .entries()
+        .into_iter()
+        .find(|e| e.kind == ActionKind::Compensate && !e.success)
+        .unwrap();
+    assert_eq!(failed.node_id, id(&graph, "b"));
+    assert!(verify_entries(&log.entries()).is_ok());
+}
+
+#[test]
+fn hard_stop_during_compensation_interrupts_the_rest() {
+    let (mut rt, latch, _log) = setup("b");
+    let undone = Arc::new(Mutex::new(Vec::new()));
+    rt.register_compensator("undo", Box::new(Undo(Arc::clone(&undone))));
+    rt.register_compensator("escalate", Box::new(HardStopOnUndo(latch.clone())));
+
+    let (graph, plan) = chain(&["a", "b", "c"]);
+    let compensations: Compensations = [(id(&graph, "a"), "undo"), (id(&graph, "b"), "escalate")]
+        .into_iter()
+        .collect();
+
+    let report = report(
+        rt.execute_plan_with_compensations(&plan, &graph, &compensations)
+            .unwrap_err(),
+    );
+
+    assert_eq!(report.rolled_back, vec![id(&graph, "b")]);
+    assert_eq!(
+        report.failed,
+        vec![(id(&graph, "a"), CompensationError::Interrupted)]
+    );
+    assert!(undone.lock().unwrap().is_empty());
+    assert_eq!(rt.state(), RuntimeState::Halted);
+}
+
+#[test]
+fn completed_plan_is_not_compensated() {
+    let (mut rt, _latch, log) = setup("none");
+    let undone = Arc::new(Mutex::new(Vec::new()));
+    rt.register_compensator("undo", Box::new(Undo(Arc::clone(&undone))));
+
+    let (graph, plan) = chain(&["a", "b"]);
+    let compensations: Compensations = plan.nodes.iter().map(|n| (*n, "undo")).collect();
+
+    let executed = rt
+        .execute_plan_with_compensations(&plan, &graph, &compensations)
+        .unwrap();
+
+    assert_eq!(executed, plan.nodes);
+    assert!(undone.lock().unwrap().is_empty());
+    assert!(log
+        .entries()
+        .iter()
+        .all(|e| e.kind != ActionKind::Compensate));
+}
+
+#[test]
+fn execute_plan_compensates_with_the_plans_own_actions() {
+    let (mut rt, _latch, _log) = setup("b");
+    let undone = Arc::new(Mutex::new(Vec::new()));
+    rt.register_compensator("undo", Box::new(Undo(Arc::clone(&undone))));
+
+    let (graph, mut plan) = chain(&["a", "b", "c"]);
+    plan.compensations = [(id(&graph, "a"), "undo")].into_iter().collect();
+
+    let report = report(rt.execute_plan(&plan, &graph).unwrap_err());
+
+    assert_eq!(*undone.lock().unwrap(), vec!["a"]);
+    assert_eq!(report.rolled_back, vec![id(&graph, "a")]);
+    assert_eq!(report.committed, vec![id(&graph, "b")]);
+}
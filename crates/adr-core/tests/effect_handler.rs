@@ -0,0 +1,348 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use adr_core::killswitch::{KillSwitchChannel, StopSignal};
+use adr_core::{
+    AdrRuntime, AdrRuntimeError, CapabilityScope, Compensations, Effect, EffectError,
+    EffectHandler, EffectOutput, EffectPayload, ExecClass, ExecutionPlan, FsWriteHandler, Graph,
+    GraphHeader, Node, CAP_FS_WRITE, CAP_NET_EXTERNAL,
+};
+use uuid::Uuid;
+
+struct NoSignal;
+impl KillSwitchChannel for NoSignal {
+    fn poll(&self) -> Option<StopSignal> {
+        None
+    }
+}
+
+struct FreezeAlways;
+impl KillSwitchChannel for FreezeAlways {
+    fn poll(&self) -> Option<StopSignal> {
+        Some(StopSignal::Freeze)
+    }
+}
+
+/// Records every invocation so tests can assert the handler was (not) called.
+struct RecordingHandler(Arc<Mutex<Vec<Uuid>>>);
+impl EffectHandler for RecordingHandler {
+    fn handle(&self, node: &Node, _payload: &EffectPayload) -> Result<EffectOutput, EffectError> {
+        self.0.lock().unwrap().push(node.id);
+        Ok(EffectOutput::NetResponse {
+            status: 200,
+            body: vec![],
+        })
+    }
+}
+
+fn temp_root() -> PathBuf {
+    std::env::temp_dir().join(format!("adr-effect-{}", Uuid::new_v4()))
+}
+
+fn fs_write_node(capabilities: Vec<u64>) -> Node {
+    Node {
+        id: Uuid::new_v4(),
+        label: "store_emails".to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect: Effect::FsWrite,
+        capabilities,
+        dependencies: vec![],
+    }
+}
+
+#[test]
+fn fs_write_handler_writes_inside_root() {
+    let root = temp_root();
+    let mut rt = AdrRuntime::new(NoSignal);
+    rt.capabilities().allow_mask(CAP_FS_WRITE);
+    rt.register_effect_handler(
+        Effect::FsWrite,
+        Box::new(FsWriteHandler::new(&root).unwrap()),
+    );
+
+    let payload = EffectPayload::FsWrite {
+        path: PathBuf::from("out/emails.json"),
+        contents: b"[]".to_vec(),
+    };
+    let output = rt
+        .execute_node_with(&fs_write_node(vec![CAP_FS_WRITE]), &payload)
+        .expect("write should succeed");
+
+    let written = std::fs::canonicalize(&root)
+        .unwrap()
+        .join("out/emails.json");
+    assert_eq!(
+        output,
+        EffectOutput::FsWritten {
+            path: written.clone(),
+            bytes_written: 2
+        }
+    );
+    assert_eq!(std::fs::read(written).unwrap(), b"[]");
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn fs_write_handler_rejects_paths_outside_root() {
+    let root = temp_root();
+    let handler = FsWriteHandler::new(&root).unwrap();
+    let node = fs_write_node(vec![]);
+
+    for path in ["../escape.txt", "/etc/passwd", "a/../../b", ""] {
+        let payload = EffectPayload::FsWrite {
+            path: PathBuf::from(path),
+            contents: vec![],
+        };
+        match handler.handle(&node, &payload) {
+            Err(EffectError::PathEscapesSandbox(_)) => {}
+            other => panic!(
+                "expected PathEscapesSandbox for {:?}, got {:?}",
+                path, other
+            ),
+        }
+    }
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn fs_write_handler_refuses_symlink_inside_root() {
+    let root = temp_root();
+    let outside = temp_root();
+    std::fs::create_dir_all(&outside).unwrap();
+    let handler = FsWriteHandler::new(&root).unwrap();
+    std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+    let payload = EffectPayload::FsWrite {
+        path: PathBuf::from("link/x.txt"),
+        contents: vec![1],
+    };
+    let err = handler
+        .handle(&fs_write_node(vec![]), &payload)
+        .unwrap_err();
+
+    assert!(matches!(err, EffectError::PathEscapesSandbox(_)));
+    assert!(!outside.join("x.txt").exists());
+
+    std::fs::remove_dir_all(root).unwrap();
+    std::fs::remove_dir_all(outside).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn fs_write_handler_refuses_symlinked_target_file() {
+    let root = temp_root();
+    let outside = temp_root();
+    std::fs::create_dir_all(&outside).unwrap();
+    std::fs::write(outside.join("passwd"), b"keep").unwrap();
+    let handler = FsWriteHandler::new(&root).unwrap();
+    std::fs::create_dir_all(root.join("out")).unwrap();
+    std::os::unix::fs::symlink(outside.join("passwd"), root.join("out/emails.json")).unwrap();
+
+    let payload = EffectPayload::FsWrite {
+        path: PathBuf::from("out/emails.json"),
+        contents: b"[]".to_vec(),
+    };
+    let err = handler
+        .handle(&fs_write_node(vec![]), &payload)
+        .unwrap_err();
+
+    assert!(matches!(err, EffectError::PathEscapesSandbox(_)));
+    assert_eq!(std::fs::read(outside.join("passwd")).unwrap(), b"keep");
+
+    std::fs::remove_dir_all(root).unwrap();
+    std::fs::remove_dir_all(outside).unwrap();
+}
+
+#[test]
+fn fs_write_handler_rejects_foreign_payload() {
+    let root = temp_root();
+    let handler = FsWriteHandler::new(&root).unwrap();
+
+    let err = handler
+        .handle(&fs_write_node(vec![]), &EffectPayload::None)
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        EffectError::PayloadMismatch {
+            expected: Effect::FsWrite
+        }
+    );
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn handler_is_not_invoked_when_capability_gate_fails() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut rt = AdrRuntime::new(NoSignal);
+    rt.register_effect_handler(Effect::FsWrite, Box::new(RecordingHandler(calls.clone())));
+
+    let err = rt
+        .execute_node(&fs_write_node(vec![CAP_FS_WRITE]))
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        AdrRuntimeError::CapabilityNotGranted(CAP_FS_WRITE)
+    ));
+    assert!(calls.lock().unwrap().is_empty());
+}
+
+#[test]
+fn effect_needs_its_capability_even_if_the_node_declares_none() {
+    let root = temp_root();
+    let mut rt = AdrRuntime::new(NoSignal);
+    rt.register_effect_handler(
+        Effect::FsWrite,
+        Box::new(FsWriteHandler::new(&root).unwrap()),
+    );
+    let payload = EffectPayload::FsWrite {
+        path: PathBuf::from("undeclared.txt"),
+        contents: vec![1],
+    };
+
+    let err = rt
+        .execute_node_with(&fs_write_node(vec![]), &payload)
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        AdrRuntimeError::CapabilityNotGranted(CAP_FS_WRITE)
+    ));
+    assert!(!root.join("undeclared.txt").exists());
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn handler_is_not_invoked_when_frozen() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut rt = AdrRuntime::new(FreezeAlways);
+    rt.register_effect_handler(Effect::FsWrite, Box::new(RecordingHandler(calls.clone())));
+
+    let err = rt.execute_node(&fs_write_node(vec![])).unwrap_err();
+
+    assert!(matches!(err, AdrRuntimeError::StateBlocked(_)));
+    assert!(calls.lock().unwrap().is_empty());
+}
+
+#[test]
+fn handler_is_not_invoked_for_realtime_violation() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut rt = AdrRuntime::new(NoSignal);
+    rt.register_effect_handler(
+        Effect::NetExternal,
+        Box::new(RecordingHandler(calls.clone())),
+    );
+
+    let node = Node {
+        id: Uuid::new_v4(),
+        label: "rt_net".to_string(),
+        exec_class: ExecClass::RealtimeSafe,
+        effect: Effect::NetExternal,
+        capabilities: vec![],
+        dependencies: vec![],
+    };
+    let err = rt.execute_node(&node).unwrap_err();
+
+    assert!(matches!(err, AdrRuntimeError::RealtimeViolation));
+    assert!(calls.lock().unwrap().is_empty());
+}
+
+#[test]
+fn handler_is_dispatched_by_effect_and_can_be_replaced() {
+    let first = Arc::new(Mutex::new(Vec::new()));
+    let second = Arc::new(Mutex::new(Vec::new()));
+    let mut rt = AdrRuntime::new(NoSignal);
+
+    assert!(rt
+        .register_effect_handler(
+            Effect::NetExternal,
+            Box::new(RecordingHandler(first.clone()))
+        )
+        .is_none());
+    assert!(rt
+        .register_effect_handler(
+            Effect::NetExternal,
+            Box::new(RecordingHandler(second.clone()))
+        )
+        .is_some());
+
+    let node = Node {
+        id: Uuid::new_v4(),
+        label: "fetch_users".to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect: Effect::NetExternal,
+        capabilities: vec![],
+        dependencies: vec![],
+    };
+    rt.capabilities().allow_mask(CAP_NET_EXTERNAL);
+    let output = rt.execute_node(&node).expect("node should execute");
+
+    assert_eq!(
+        output,
+        EffectOutput::NetResponse {
+            status: 200,
+            body: vec![]
+        }
+    );
+    assert!(first.lock().unwrap().is_empty());
+    assert_eq!(*second.lock().unwrap(), vec![node.id]);
+}
+
+#[test]
+fn plan_nodes_are_dispatched_with_their_payload_and_scope_checked() {
+    let root = temp_root();
+    let handler = FsWriteHandler::new(&root).unwrap();
+    let scope = format!("fs:{}", handler.root().join("out").display());
+    let mut rt = AdrRuntime::new(NoSignal);
+    rt.capabilities()
+        .allow_scoped(CapabilityScope::parse(&scope).unwrap());
+    rt.register_effect_handler(Effect::FsWrite, Box::new(handler));
+    let (inside, outside) = (
+        fs_write_node(vec![CAP_FS_WRITE]),
+        fs_write_node(vec![CAP_FS_WRITE]),
+    );
+    let graph = Graph {
+        header: GraphHeader {
+            graph_version: "0.1".to_string(),
+            deterministic_mode: false,
+        },
+        nodes: vec![inside.clone(), outside.clone()],
+    };
+    let write = |path: &str| EffectPayload::FsWrite {
+        path: PathBuf::from(path),
+        contents: b"{}".to_vec(),
+    };
+    let plan = ExecutionPlan {
+        nodes: vec![inside.id, outside.id],
+        parallel: vec![vec![inside.id, outside.id]],
+        checkpoints: vec![],
+        human_gates: vec![],
+        capabilities: vec![CAP_FS_WRITE],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: [
+            (inside.id, write("out/emails.json")),
+            (outside.id, write("secrets/key")),
+        ]
+        .into_iter()
+        .collect(),
+    };
+
+    let err = rt.execute_plan(&plan, &graph).unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::CapabilityOutOfScope { .. }));
+    assert_eq!(std::fs::read(root.join("out/emails.json")).unwrap(), b"{}");
+    assert!(!root.join("secrets").exists());
+
+    std::fs::remove_file(root.join("out/emails.json")).unwrap();
+    let err = rt
+        .execute_plan_parallel(&plan, &graph, &Compensations::new(), 2)
+        .unwrap_err();
+    assert!(matches!(err, AdrRuntimeError::CapabilityOutOfScope { .. }));
+    assert!(root.join("out/emails.json").exists());
+    assert!(!root.join("secrets").exists());
+    std::fs::remove_dir_all(root).unwrap();
+}
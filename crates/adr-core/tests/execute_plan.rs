@@ -1,5 +1,5 @@
 use adr_core::{
-    AdrRuntime, Effect, ExecClass, ExecutionPlan, Graph, GraphHeader, Node,
+    AdrRuntime, Compensations, Effect, ExecClass, ExecutionPlan, Graph, GraphHeader, Node,
 };
 use adr_core::killswitch::{KillSwitchChannel, StopSignal};
 use uuid::Uuid;
@@ -45,6 +45,11 @@ fn execute_plan_runs_all_nodes_in_order() {
         nodes: vec![id1, id2],
         parallel: vec![],
         checkpoints: vec![],
+        human_gates: vec![],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
     };
 
     let mut rt = AdrRuntime::new(NoSignal);
@@ -80,6 +85,11 @@ fn execute_plan_fails_when_node_is_missing_from_graph() {
         nodes: vec![id1, missing_id],
         parallel: vec![],
         checkpoints: vec![],
+        human_gates: vec![],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
     };
 
     let mut rt = AdrRuntime::new(NoSignal);
@@ -145,18 +155,26 @@ fn execute_plan_stops_between_nodes_when_killswitch_triggers() {
         nodes: vec![id1, id2],
         parallel: vec![],
         checkpoints: vec![],
+        human_gates: vec![],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
     };
 
     let mut rt = AdrRuntime::new(SoftStopOnSecondPoll(Mutex::new(0)));
 
     let err = rt.execute_plan(&plan, &graph).unwrap_err();
 
+    // node_a ran before the Soft Stop; without compensations it stays committed.
     match err {
-        adr_core::AdrRuntimeError::StateBlocked(state) => {
-            assert_eq!(state, adr_core::RuntimeState::Stopping);
+        adr_core::AdrRuntimeError::SoftStopAborted(report) => {
+            assert_eq!(report.committed, vec![id1]);
+            assert!(report.rolled_back.is_empty());
         }
-        other => panic!("expected StateBlocked(Stopping), got {:?}", other),
+        other => panic!("expected SoftStopAborted, got {:?}", other),
     }
+    assert_eq!(rt.state(), adr_core::RuntimeState::Stopping);
 }
 
 
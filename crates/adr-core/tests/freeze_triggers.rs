@@ -0,0 +1,297 @@
+use adr_core::killswitch::StopLatch;
+use adr_core::{
+    verify_entries, ActionKind, AdrRuntime, AdrRuntimeError, Compensations, Effect, EffectError,
+    EffectHandler, EffectOutput, EffectPayload, ExecClass, ExecutionPlan, FreezeTrigger, Graph,
+    GraphHeader, HumanGate, MemoryApprovalStore, MemoryAuditSink, Node, NodeId, Quorum,
+    RuntimeState, CAP_FS_WRITE, CAP_NET_EXTERNAL,
+};
+use uuid::Uuid;
+
+fn node(label: &str, effect: Effect, capabilities: Vec<u64>) -> Node {
+    Node {
+        id: Uuid::new_v4(),
+        label: label.to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect,
+        capabilities,
+        dependencies: vec![],
+    }
+}
+
+fn graph(nodes: Vec<Node>, deterministic_mode: bool) -> Graph {
+    Graph {
+        header: GraphHeader {
+            graph_version: "0.1".to_string(),
+            deterministic_mode,
+        },
+        nodes,
+    }
+}
+
+fn plan(graph: &Graph, capabilities: Vec<u64>) -> ExecutionPlan {
+    ExecutionPlan {
+        nodes: graph.nodes.iter().map(|n| n.id).collect(),
+        parallel: vec![],
+        checkpoints: vec![],
+        human_gates: vec![],
+        capabilities,
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    }
+}
+
+fn runtime(trigger: FreezeTrigger) -> (AdrRuntime<StopLatch>, MemoryAuditSink) {
+    let log = MemoryAuditSink::new();
+    let rt = AdrRuntime::new(StopLatch::new())
+        .with_audit_sink(Box::new(log.clone()))
+        .with_freeze_triggers([trigger]);
+    (rt, log)
+}
+
+fn freeze_cause(log: &MemoryAuditSink) -> String {
+    let entries = log.entries();
+    assert!(verify_entries(&entries).is_ok());
+    entries
+        .into_iter()
+        .find(|e| e.kind == ActionKind::Freeze)
+        .and_then(|e| e.detail)
+        .expect("freeze is audited")
+}
+
+fn assert_frozen_by(
+    result: Result<Vec<NodeId>, AdrRuntimeError>,
+    rt: &AdrRuntime<StopLatch>,
+    trigger: FreezeTrigger,
+) {
+    match result {
+        Err(AdrRuntimeError::FreezeTriggered(t)) => assert_eq!(t, trigger),
+        other => panic!("expected FreezeTriggered, got {:?}", other),
+    }
+    assert_eq!(rt.state(), RuntimeState::Frozen);
+    assert_eq!(rt.capabilities().mask(), 0);
+}
+
+struct BrokenPostcondition;
+impl EffectHandler for BrokenPostcondition {
+    fn handle(&self, _node: &Node, _payload: &EffectPayload) -> Result<EffectOutput, EffectError> {
+        Err(EffectError::PostconditionFailed(
+            "bytes_written != len".to_string(),
+        ))
+    }
+}
+
+#[test]
+fn failed_postcondition_freezes() {
+    let (mut rt, log) = runtime(FreezeTrigger::ContractFailure);
+    rt.register_effect_handler(Effect::FsWrite, Box::new(BrokenPostcondition));
+    rt.capabilities().allow_mask(CAP_FS_WRITE);
+    let graph = graph(
+        vec![node("write", Effect::FsWrite, vec![CAP_FS_WRITE])],
+        false,
+    );
+    let id = graph.nodes[0].id;
+
+    let result = rt.execute_plan(&plan(&graph, vec![CAP_FS_WRITE]), &graph);
+
+    assert_frozen_by(result, &rt, FreezeTrigger::ContractFailure);
+    assert_eq!(
+        freeze_cause(&log),
+        format!(
+            "from=Running to=Frozen cause=freeze_trigger=contract_failure node={} bytes_written != len",
+            id
+        )
+    );
+}
+
+#[test]
+fn inactive_trigger_is_not_detected() {
+    let (mut rt, log) = runtime(FreezeTrigger::DeterministicModeViolation);
+    rt.register_effect_handler(Effect::FsWrite, Box::new(BrokenPostcondition));
+    rt.capabilities().allow_mask(CAP_FS_WRITE);
+    let graph = graph(
+        vec![node("write", Effect::FsWrite, vec![CAP_FS_WRITE])],
+        false,
+    );
+
+    let err = rt.execute_plan(&plan(&graph, vec![]), &graph).unwrap_err();
+
+    assert!(matches!(
+        err,
+        AdrRuntimeError::EffectFailed(EffectError::PostconditionFailed(_))
+    ));
+    assert_eq!(rt.state(), RuntimeState::Running);
+    assert!(log.entries().iter().all(|e| e.kind != ActionKind::Freeze));
+}
+
+#[test]
+fn capability_outside_the_plan_freezes_before_the_node_runs() {
+    let (mut rt, log) = runtime(FreezeTrigger::UnverifiedCapabilityUse);
+    rt.capabilities()
+        .allow_mask(CAP_FS_WRITE | CAP_NET_EXTERNAL);
+    let graph = graph(
+        vec![
+            node("write", Effect::FsWrite, vec![CAP_FS_WRITE]),
+            node("send", Effect::NetExternal, vec![CAP_NET_EXTERNAL]),
+        ],
+        false,
+    );
+    let send = graph.nodes[1].id;
+
+    let result = rt.execute_plan(&plan(&graph, vec![CAP_FS_WRITE]), &graph);
+
+    assert_frozen_by(result, &rt, FreezeTrigger::UnverifiedCapabilityUse);
+    assert!(freeze_cause(&log).ends_with(&format!(
+        "unverified_capability_use node={} capability={:#x}",
+        send, CAP_NET_EXTERNAL
+    )));
+    let executed: Vec<(NodeId, bool)> = log
+        .entries()
+        .into_iter()
+        .filter(|e| e.kind == ActionKind::Execute)
+        .map(|e| (e.node_id, e.success))
+        .collect();
+    assert_eq!(executed, vec![(graph.nodes[0].id, true), (send, false)]);
+}
+
+#[test]
+fn plan_weakening_an_open_gate_is_a_downgrade_attempt() {
+    let store = MemoryApprovalStore::new();
+    let graph = graph(vec![node("actuate", Effect::None, vec![])], false);
+    let id = graph.nodes[0].id;
+    let gated = ExecutionPlan {
+        human_gates: vec![HumanGate {
+            node: id,
+            quorum: Quorum {
+                count: 2,
+                roles: vec![],
+            },
+        }],
+        ..plan(&graph, vec![])
+    };
+    let mut before = AdrRuntime::new(StopLatch::new()).with_approval_store(Box::new(store.clone()));
+    before.execute_plan(&gated, &graph).unwrap_err();
+    drop(before);
+
+    // After a restart the gate is still open: the same plan waits again ...
+    let (rt, _log) = runtime(FreezeTrigger::TrustTierDowngradeAttempt);
+    let mut rt = rt.with_approval_store(Box::new(store.clone()));
+    assert!(matches!(
+        rt.execute_plan(&gated, &graph),
+        Err(AdrRuntimeError::ApprovalRequired(n)) if n == id
+    ));
+    drop(rt);
+
+    // ... one asking for a single approval freezes.
+    let (rt, log) = runtime(FreezeTrigger::TrustTierDowngradeAttempt);
+    let mut rt = rt.with_approval_store(Box::new(store));
+    let weaker = ExecutionPlan {
+        human_gates: vec![HumanGate::new(id)],
+        ..gated.clone()
+    };
+    let result = rt.execute_plan(&weaker, &graph);
+
+    assert_frozen_by(result, &rt, FreezeTrigger::TrustTierDowngradeAttempt);
+    assert!(freeze_cause(&log).ends_with(&format!(
+        "trust_tier_downgrade_attempt node={} human gate dropped or weakened",
+        id
+    )));
+}
+
+#[test]
+fn scope_change_during_a_paused_plan_freezes() {
+    let (mut rt, log) = runtime(FreezeTrigger::CapScopeHashMismatch);
+    rt.capabilities().allow_mask(CAP_FS_WRITE);
+    let graph = graph(
+        vec![
+            node("a", Effect::None, vec![]),
+            node("b", Effect::FsWrite, vec![CAP_FS_WRITE]),
+        ],
+        false,
+    );
+    let b = graph.nodes[1].id;
+    let plan = ExecutionPlan {
+        checkpoints: vec![b],
+        ..plan(&graph, vec![CAP_FS_WRITE])
+    };
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    rt.capabilities().allow_mask(CAP_NET_EXTERNAL);
+    let result = rt.acknowledge_checkpoint(b, "alice");
+
+    assert_frozen_by(result, &rt, FreezeTrigger::CapScopeHashMismatch);
+    assert!(freeze_cause(&log).ends_with(&format!(
+        "cap_scope_hash_mismatch node={} granted={:#x} at_start={:#x}",
+        b,
+        CAP_FS_WRITE | CAP_NET_EXTERNAL,
+        CAP_FS_WRITE
+    )));
+}
+
+#[test]
+fn deterministic_graph_refuses_external_input() {
+    let graph = graph(
+        vec![
+            node("a", Effect::None, vec![]),
+            node("fetch", Effect::NetExternal, vec![]),
+        ],
+        true,
+    );
+    let plan = plan(&graph, vec![]);
+
+    let (mut rt, log) = runtime(FreezeTrigger::DeterministicModeViolation);
+    let result = rt.execute_plan(&plan, &graph);
+    assert_frozen_by(result, &rt, FreezeTrigger::DeterministicModeViolation);
+    assert!(freeze_cause(&log).ends_with(&format!("node={} effect=NetExternal", graph.nodes[1].id)));
+
+    let (mut rt, _log) = runtime(FreezeTrigger::DeterministicModeViolation);
+    let result = rt.execute_plan_parallel(&plan, &graph, &Default::default(), 2);
+    assert_frozen_by(result, &rt, FreezeTrigger::DeterministicModeViolation);
+}
+
+#[test]
+fn deterministic_parallel_run_does_not_freeze() {
+    let (a, b) = (
+        node("a", Effect::None, vec![]),
+        node("b", Effect::None, vec![]),
+    );
+    let mut c = node("c", Effect::None, vec![]);
+    c.dependencies = vec![a.id, b.id];
+    let graph = graph(vec![a, b, c], true);
+    let mut plan = plan(&graph, vec![]);
+    plan.parallel = vec![vec![plan.nodes[0], plan.nodes[1]], vec![plan.nodes[2]]];
+
+    let (mut rt, log) = runtime(FreezeTrigger::DeterministicModeViolation);
+    let executed = rt
+        .execute_plan_parallel(&plan, &graph, &Default::default(), 4)
+        .unwrap();
+
+    assert_eq!(executed, plan.nodes);
+    assert_eq!(rt.state(), RuntimeState::Running);
+    assert!(log.entries().iter().all(|e| e.kind != ActionKind::Freeze));
+}
+
+#[test]
+fn deterministic_graph_refuses_dependent_nodes_in_one_parallel_layer() {
+    let a = node("a", Effect::None, vec![]);
+    let mut b = node("b", Effect::None, vec![]);
+    b.dependencies = vec![a.id];
+    let graph = graph(vec![a, b], true);
+    let mut plan = plan(&graph, vec![]);
+    plan.parallel = vec![plan.nodes.clone()];
+
+    // One worker runs the layer in order.
+    let (mut rt, _log) = runtime(FreezeTrigger::DeterministicModeViolation);
+    let executed = rt
+        .execute_plan_parallel(&plan, &graph, &Default::default(), 1)
+        .unwrap();
+    assert_eq!(executed, plan.nodes);
+
+    let (mut rt, log) = runtime(FreezeTrigger::DeterministicModeViolation);
+    let result = rt.execute_plan_parallel(&plan, &graph, &Default::default(), 2);
+    assert_frozen_by(result, &rt, FreezeTrigger::DeterministicModeViolation);
+    assert!(freeze_cause(&log).ends_with(&format!(
+        "node={} depends_on={} in_layer",
+        plan.nodes[1], plan.nodes[0]
+    )));
+}
@@ -0,0 +1,275 @@
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use adr_core::audit::SigningKey;
+use adr_core::killswitch::{StopLatch, StopSignal};
+use adr_core::{
+    verify_chain, ActionLogEntry, AdrRuntime, AdrRuntimeError, AuditSink, Compensations,
+    DiagnosticSnapshot, Effect, EffectError, EffectHandler, EffectOutput, EffectPayload, ExecClass,
+    ExecutionPlan, FrozenRuntime, Graph, GraphHeader, MemoryAuditSink, Node, OperatorKey,
+    ResumeRefusal, ResumeRequest, RuntimeState, CAP_FS_WRITE,
+};
+use uuid::Uuid;
+
+struct Counting(Arc<AtomicUsize>);
+impl EffectHandler for Counting {
+    fn handle(&self, _: &Node, _: &EffectPayload) -> Result<EffectOutput, EffectError> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(EffectOutput::None)
+    }
+}
+
+/// Sink that keeps only the head and fails `export` with `kind`.
+struct NoExport {
+    head: Option<String>,
+    kind: io::ErrorKind,
+}
+
+impl AuditSink for NoExport {
+    fn head(&self) -> Option<String> {
+        self.head.clone()
+    }
+
+    fn append(&mut self, entry: ActionLogEntry) -> io::Result<()> {
+        self.head = Some(entry.entry_hash);
+        Ok(())
+    }
+
+    fn export(&self) -> io::Result<Vec<ActionLogEntry>> {
+        Err(self.kind.into())
+    }
+}
+
+fn operator_key() -> SigningKey {
+    SigningKey::from_bytes(&[7; 32])
+}
+
+fn hand_over(rt: AdrRuntime<StopLatch>) -> FrozenRuntime<StopLatch> {
+    rt.into_frozen().ok().expect("runtime is frozen")
+}
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("adr-diag-{}", Uuid::new_v4()))
+}
+
+fn write_node() -> Node {
+    Node {
+        id: Uuid::new_v4(),
+        label: "write".to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect: Effect::FsWrite,
+        capabilities: vec![CAP_FS_WRITE],
+        dependencies: vec![],
+    }
+}
+
+/// Runs a one-node plan, then freezes.
+fn frozen_runtime(
+    calls: &Arc<AtomicUsize>,
+) -> (AdrRuntime<StopLatch>, StopLatch, Node, ExecutionPlan) {
+    let latch = StopLatch::new();
+    let mut rt = AdrRuntime::new(latch.clone())
+        .with_audit_sink(Box::new(MemoryAuditSink::new()))
+        .with_policy_version("p-1")
+        .with_operator(OperatorKey {
+            operator_id: "alice".to_string(),
+            key: operator_key().verifying_key(),
+        });
+    rt.register_effect_handler(Effect::FsWrite, Box::new(Counting(Arc::clone(calls))));
+    rt.capabilities().allow_mask(CAP_FS_WRITE);
+
+    let node = write_node();
+    let graph = Graph {
+        header: GraphHeader {
+            graph_version: "0.1".to_string(),
+            deterministic_mode: false,
+        },
+        nodes: vec![node.clone()],
+    };
+    let plan = ExecutionPlan {
+        nodes: vec![node.id],
+        parallel: vec![],
+        checkpoints: vec![],
+        human_gates: vec![],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    };
+    rt.execute_plan(&plan, &graph).unwrap();
+
+    latch.set(StopSignal::Freeze);
+    rt.execute_noop().unwrap_err();
+    (rt, latch, node, plan)
+}
+
+#[test]
+fn only_a_frozen_runtime_is_handed_over() {
+    let mut rt = match AdrRuntime::new(StopLatch::new()).into_frozen() {
+        Ok(_) => panic!("running runtime was handed over as frozen"),
+        Err(rt) => rt,
+    };
+    rt.execute_noop().unwrap();
+
+    let (rt, _latch, _node, _plan) = frozen_runtime(&Arc::new(AtomicUsize::new(0)));
+    assert!(rt.into_frozen().is_ok());
+}
+
+#[test]
+fn resume_hands_back_a_running_runtime() {
+    let (rt, latch, _node, _plan) = frozen_runtime(&Arc::new(AtomicUsize::new(0)));
+    latch.clear();
+    let frozen = hand_over(rt);
+
+    let forged = ResumeRequest::sign(
+        &frozen.resume_challenge(),
+        "alice",
+        "sensor replaced",
+        &SigningKey::from_bytes(&[9; 32]),
+    );
+    let (frozen, err) = match frozen.resume(&forged) {
+        Ok(_) => panic!("forged resume was accepted"),
+        Err(refused) => refused,
+    };
+    assert!(matches!(
+        err,
+        AdrRuntimeError::ResumeRefused(ResumeRefusal::BadCredential)
+    ));
+    assert_eq!(frozen.snapshot().state, RuntimeState::Frozen);
+
+    let request = ResumeRequest::sign(
+        &frozen.resume_challenge(),
+        "alice",
+        "sensor replaced",
+        &operator_key(),
+    );
+    let mut rt = frozen.resume(&request).ok().expect("resume accepted");
+    assert_eq!(rt.state(), RuntimeState::Running);
+    rt.execute_noop().unwrap();
+}
+
+#[test]
+fn snapshot_captures_state_capabilities_and_plan() {
+    let (rt, _latch, _node, plan) = frozen_runtime(&Arc::new(AtomicUsize::new(0)));
+    let snapshot = hand_over(rt).snapshot();
+
+    assert_eq!(snapshot.state, RuntimeState::Frozen);
+    assert_eq!(snapshot.capabilities_at_stop, Some(CAP_FS_WRITE));
+    assert_eq!(snapshot.capabilities, 0);
+    assert!(snapshot.capabilities_sealed);
+    assert_eq!(snapshot.last_plan, Some(plan));
+    assert_eq!(snapshot.graph_version, "0.1");
+    assert_eq!(snapshot.policy_version, "p-1");
+}
+
+#[test]
+fn no_effect_handler_runs_while_frozen() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let (mut rt, _latch, node, _plan) = frozen_runtime(&calls);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let err = rt
+        .execute_node_with(
+            &node,
+            &EffectPayload::FsWrite {
+                path: "x".into(),
+                contents: vec![],
+            },
+        )
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        AdrRuntimeError::StateBlocked(RuntimeState::Frozen)
+    ));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn bundle_contains_snapshot_chain_and_plan() {
+    let (rt, _latch, _node, plan) = frozen_runtime(&Arc::new(AtomicUsize::new(0)));
+    let frozen = hand_over(rt);
+    let dir = temp_dir();
+
+    frozen.export_bundle(&dir).unwrap();
+
+    let snapshot: DiagnosticSnapshot =
+        serde_json::from_slice(&std::fs::read(dir.join("snapshot.json")).unwrap()).unwrap();
+    assert_eq!(snapshot.state, RuntimeState::Frozen);
+
+    // Execute + Freeze transition.
+    assert_eq!(verify_chain(dir.join("audit.jsonl")), Ok(2));
+
+    let exported: ExecutionPlan =
+        serde_json::from_slice(&std::fs::read(dir.join("last_plan.json")).unwrap()).unwrap();
+    assert_eq!(exported, plan);
+
+    // Evidence is never overwritten.
+    assert!(frozen.export_bundle(&dir).is_err());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn freeze_writes_bundle_automatically() {
+    let root = temp_dir();
+    let latch = StopLatch::new();
+    let mut rt = AdrRuntime::new(latch.clone()).with_diagnostic_dir(&root);
+
+    latch.set(StopSignal::Freeze);
+    rt.execute_noop().unwrap_err();
+
+    let bundles: Vec<_> = std::fs::read_dir(&root).unwrap().collect();
+    assert_eq!(bundles.len(), 1);
+    let bundle = bundles[0].as_ref().unwrap().path();
+    assert!(bundle.join("snapshot.json").exists());
+    assert_eq!(verify_chain(bundle.join("audit.jsonl")), Ok(1));
+    assert!(!bundle.join("last_plan.json").exists());
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn bundle_without_exportable_chain_is_written_without_audit_log() {
+    let root = temp_dir();
+    let latch = StopLatch::new();
+    let mut rt = AdrRuntime::new(latch.clone())
+        .with_audit_sink(Box::new(NoExport {
+            head: None,
+            kind: io::ErrorKind::Unsupported,
+        }))
+        .with_diagnostic_dir(&root);
+
+    latch.set(StopSignal::Freeze);
+    let err = rt.execute_noop().unwrap_err();
+    assert!(!matches!(err, AdrRuntimeError::DiagnosticExportFailed(_)));
+
+    let bundles: Vec<_> = std::fs::read_dir(&root).unwrap().collect();
+    assert_eq!(bundles.len(), 1);
+    let bundle = bundles[0].as_ref().unwrap().path();
+    let snapshot: DiagnosticSnapshot =
+        serde_json::from_slice(&std::fs::read(bundle.join("snapshot.json")).unwrap()).unwrap();
+    assert!(snapshot.audit_head.is_some());
+    assert!(!bundle.join("audit.jsonl").exists());
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn failed_export_leaves_no_partial_bundle() {
+    let root = temp_dir();
+    let latch = StopLatch::new();
+    let mut rt = AdrRuntime::new(latch.clone()).with_audit_sink(Box::new(NoExport {
+        head: None,
+        kind: io::ErrorKind::Other,
+    }));
+    latch.set(StopSignal::Freeze);
+    rt.execute_noop().unwrap_err();
+
+    let dir = root.join("bundle");
+    assert!(hand_over(rt).export_bundle(&dir).is_err());
+    assert_eq!(std::fs::read_dir(&root).unwrap().count(), 0);
+
+    std::fs::remove_dir_all(root).unwrap();
+}
@@ -39,7 +39,7 @@ fn graph_roundtrip_json() {
     let decoded: Graph = serde_json::from_str(&json).expect("deserialize graph");
 
     assert_eq!(decoded.header.graph_version, "0.1");
-    assert_eq!(decoded.header.deterministic_mode, true);
+    assert!(decoded.header.deterministic_mode);
     assert_eq!(decoded.nodes.len(), 2);    
 	assert_eq!(decoded.nodes[0].label, "node_a");
 	assert_eq!(decoded.nodes[0].exec_class, ExecClass::Orchestrated);
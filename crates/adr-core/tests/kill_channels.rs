@@ -0,0 +1,211 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use adr_core::killswitch::{KillSwitchChannel, LocalHttpChannel, StopSignal};
+
+/// Channels latch asynchronously; wait briefly for the listener thread.
+fn wait_for(channel: &dyn KillSwitchChannel) -> Option<StopSignal> {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while Instant::now() < deadline {
+        if let Some(s) = channel.poll() {
+            return Some(s);
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    None
+}
+
+fn post(channel: &LocalHttpChannel, path: &str, token: Option<&str>) -> String {
+    let mut stream = TcpStream::connect(channel.local_addr()).unwrap();
+    let auth = token
+        .map(|t| format!("Authorization: Bearer {}\r\n", t))
+        .unwrap_or_default();
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: localhost\r\n{}Content-Length: 0\r\n\r\n",
+        path, auth
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response.lines().next().unwrap_or_default().to_string()
+}
+
+#[test]
+fn http_binds_loopback_only() {
+    let channel = LocalHttpChannel::bind(0, None).unwrap();
+    assert!(channel.local_addr().ip().is_loopback());
+}
+
+#[test]
+fn http_post_latches_stop() {
+    let channel = LocalHttpChannel::bind(0, None).unwrap();
+    assert_eq!(channel.poll(), None);
+
+    assert_eq!(post(&channel, "/hard_stop", None), "HTTP/1.1 200 OK");
+    assert_eq!(channel.poll(), Some(StopSignal::HardStop));
+
+    post(&channel, "/soft_stop", None);
+    assert_eq!(channel.poll(), Some(StopSignal::HardStop));
+
+    channel.clear();
+    assert_eq!(channel.poll(), None);
+}
+
+#[test]
+fn http_token_is_enforced() {
+    let channel = LocalHttpChannel::bind(0, Some("s3cret".to_string())).unwrap();
+
+    assert_eq!(post(&channel, "/freeze", None), "HTTP/1.1 401 Unauthorized");
+    assert_eq!(
+        post(&channel, "/freeze", Some("wrong")),
+        "HTTP/1.1 401 Unauthorized"
+    );
+    assert_eq!(channel.poll(), None);
+
+    assert_eq!(post(&channel, "/freeze", Some("s3cret")), "HTTP/1.1 200 OK");
+    assert_eq!(channel.poll(), Some(StopSignal::Freeze));
+}
+
+#[test]
+fn http_rejects_unknown_command_and_method() {
+    let channel = LocalHttpChannel::bind(0, None).unwrap();
+
+    assert_eq!(post(&channel, "/reboot", None), "HTTP/1.1 404 Not Found");
+
+    let mut stream = TcpStream::connect(channel.local_addr()).unwrap();
+    stream.write_all(b"GET /freeze HTTP/1.1\r\n\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 405"));
+
+    assert_eq!(channel.poll(), None);
+}
+
+#[test]
+fn http_stalled_connection_does_not_block_stop() {
+    let channel = LocalHttpChannel::bind(0, None).unwrap();
+
+    let mut stalled = TcpStream::connect(channel.local_addr()).unwrap();
+    stalled
+        .write_all(b"POST /freeze HTTP/1.1\r\nHost: loc")
+        .unwrap();
+
+    let started = Instant::now();
+    assert_eq!(post(&channel, "/hard_stop", None), "HTTP/1.1 200 OK");
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert_eq!(channel.poll(), Some(StopSignal::HardStop));
+
+    let mut response = String::new();
+    stalled.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 408"), "{response}");
+}
+
+#[test]
+fn http_oversized_request_head_is_rejected() {
+    let channel = LocalHttpChannel::bind(0, None).unwrap();
+
+    let mut stream = TcpStream::connect(channel.local_addr()).unwrap();
+    let padding = "a".repeat(16 * 1024);
+    write!(
+        stream,
+        "POST /freeze HTTP/1.1\r\nX-Padding: {padding}\r\n\r\n"
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 431"), "{response}");
+
+    assert_eq!(channel.poll(), None);
+}
+
+#[cfg(unix)]
+mod named_pipe {
+    use super::*;
+    use adr_core::killswitch::LocalNamedPipeChannel;
+    use std::fs::OpenOptions;
+    use std::os::unix::fs::FileTypeExt;
+
+    fn temp_fifo() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("adr-kill-{}.fifo", uuid::Uuid::new_v4()))
+    }
+
+    fn send(channel: &LocalNamedPipeChannel, data: &[u8]) {
+        let mut pipe = OpenOptions::new().write(true).open(channel.path()).unwrap();
+        pipe.write_all(data).unwrap();
+    }
+
+    #[test]
+    fn pipe_command_latches_stop() {
+        let path = temp_fifo();
+        let channel = LocalNamedPipeChannel::open(&path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().file_type().is_fifo());
+        assert_eq!(channel.poll(), None);
+
+        send(&channel, b"hello\nsoft_stop\n");
+
+        assert_eq!(wait_for(&channel), Some(StopSignal::SoftStop));
+    }
+
+    #[test]
+    fn pipe_accepts_command_without_newline() {
+        let channel = LocalNamedPipeChannel::open(temp_fifo()).unwrap();
+
+        send(&channel, b"freeze");
+
+        assert_eq!(wait_for(&channel), Some(StopSignal::Freeze));
+    }
+
+    #[test]
+    fn pipe_drops_overlong_lines() {
+        let channel = LocalNamedPipeChannel::open(temp_fifo()).unwrap();
+
+        let mut data = b"freeze".repeat(1024);
+        data.extend_from_slice(b"\nsoft_stop\n");
+        send(&channel, &data);
+
+        assert_eq!(wait_for(&channel), Some(StopSignal::SoftStop));
+    }
+
+    #[test]
+    fn pipe_created_by_channel_is_removed_on_drop() {
+        let path = temp_fifo();
+        drop(LocalNamedPipeChannel::open(&path).unwrap());
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn regular_file_is_refused() {
+        let path = temp_fifo();
+        std::fs::write(&path, b"").unwrap();
+
+        assert!(LocalNamedPipeChannel::open(&path).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+struct Fixed(Option<StopSignal>);
+impl KillSwitchChannel for Fixed {
+    fn poll(&self) -> Option<StopSignal> {
+        self.0
+    }
+}
+
+#[test]
+fn composite_reports_most_severe_signal() {
+    use adr_core::killswitch::CompositeChannel;
+
+    let quiet = CompositeChannel::new()
+        .with_channel(Box::new(Fixed(None)))
+        .with_channel(Box::new(Fixed(None)));
+    assert_eq!(quiet.poll(), None);
+
+    let composite = CompositeChannel::new()
+        .with_channel(Box::new(Fixed(Some(StopSignal::SoftStop))))
+        .with_channel(Box::new(Fixed(Some(StopSignal::Freeze))))
+        .with_channel(Box::new(Fixed(Some(StopSignal::HardStop))));
+    assert_eq!(composite.len(), 3);
+    assert_eq!(composite.poll(), Some(StopSignal::Freeze));
+}
@@ -1,5 +1,6 @@
 use adr_core::{
-    AdrRuntime, AdrRuntimeError, Effect, ExecClass, Node, RuntimeState,
+    AdrRuntime, AdrRuntimeError, Effect, ExecClass, Node, RuntimeState, CAP_FS_WRITE,
+    CAP_NET_EXTERNAL,
 };
 use adr_core::killswitch::{KillSwitchChannel, StopSignal};
 use uuid::Uuid;
@@ -36,6 +37,7 @@ fn orchestrated_node_executes() {
 	};
 
     let mut rt = AdrRuntime::new(NoSignal);
+    rt.capabilities().allow_mask(CAP_NET_EXTERNAL);
     rt.execute_node(&node).expect("orchestrated node should execute");
 }
 
@@ -115,7 +117,7 @@ fn node_with_granted_capability_executes() {
     };
 
     let mut rt = AdrRuntime::new(NoSignal);
-    rt.capabilities().allow_mask(1 << 3);
+    rt.capabilities().allow_mask((1 << 3) | CAP_FS_WRITE);
 
     rt.execute_node(&node)
         .expect("node with granted capability should execute");
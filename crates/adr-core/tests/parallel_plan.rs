@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use adr_core::killswitch::{StopLatch, StopSignal};
+use adr_core::{
+    verify_entries, ActionKind, AdrRuntime, AdrRuntimeError, Compensations, Effect, EffectError,
+    EffectHandler, EffectOutput, EffectPayload, ExecClass, ExecutionPlan, Graph, GraphHeader,
+    MemoryAuditSink, Node, NodeId, RuntimeState,
+};
+use uuid::Uuid;
+
+/// Sleeps per label, tracks peak concurrency and can raise a stop signal.
+#[derive(Default)]
+struct Probe {
+    sleep_ms: HashMap<String, u64>,
+    signal_at: Option<(String, StopLatch, StopSignal)>,
+    active: AtomicUsize,
+    peak: Arc<AtomicUsize>,
+}
+
+impl EffectHandler for Probe {
+    fn handle(&self, node: &Node, _: &EffectPayload) -> Result<EffectOutput, EffectError> {
+        let now = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(now, Ordering::SeqCst);
+
+        let ms = self.sleep_ms.get(&node.label).copied().unwrap_or(0);
+        thread::sleep(Duration::from_millis(ms));
+        if let Some((label, latch, signal)) = &self.signal_at {
+            if *label == node.label {
+                latch.set(*signal);
+            }
+        }
+        self.active.fetch_sub(1, Ordering::SeqCst);
+
+        if node.label.starts_with("fail") {
+            return Err(EffectError::Io(node.label.clone()));
+        }
+        Ok(EffectOutput::None)
+    }
+}
+
+/// Graph and plan with one parallel layer per entry of `layers`.
+fn layered(layers: &[&[&str]]) -> (Graph, ExecutionPlan) {
+    let mut nodes = Vec::new();
+    let mut parallel = Vec::new();
+    for layer in layers {
+        let mut ids = Vec::new();
+        for label in *layer {
+            let node = Node {
+                id: Uuid::new_v4(),
+                label: label.to_string(),
+                exec_class: ExecClass::Orchestrated,
+                effect: Effect::None,
+                capabilities: vec![],
+                dependencies: vec![],
+            };
+            ids.push(node.id);
+            nodes.push(node);
+        }
+        parallel.push(ids);
+    }
+    let plan = ExecutionPlan {
+        nodes: nodes.iter().map(|n| n.id).collect(),
+        parallel,
+        checkpoints: vec![],
+        human_gates: vec![],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    };
+    let graph = Graph {
+        header: GraphHeader {
+            graph_version: "0.1".to_string(),
+            deterministic_mode: false,
+        },
+        nodes,
+    };
+    (graph, plan)
+}
+
+fn id(graph: &Graph, label: &str) -> NodeId {
+    graph.nodes.iter().find(|n| n.label == label).unwrap().id
+}
+
+fn setup(probe: Probe, latch: &StopLatch) -> (AdrRuntime<StopLatch>, MemoryAuditSink) {
+    let log = MemoryAuditSink::new();
+    let mut rt = AdrRuntime::new(latch.clone()).with_audit_sink(Box::new(log.clone()));
+    rt.register_effect_handler(Effect::None, Box::new(probe));
+    (rt, log)
+}
+
+fn executed_in_log(log: &MemoryAuditSink) -> Vec<(NodeId, bool)> {
+    log.entries()
+        .into_iter()
+        .filter(|e| e.kind == ActionKind::Execute)
+        .map(|e| (e.node_id, e.success))
+        .collect()
+}
+
+#[test]
+fn layer_runs_concurrently_within_worker_bound() {
+    let latch = StopLatch::new();
+    let peak = Arc::new(AtomicUsize::new(0));
+    let probe = Probe {
+        sleep_ms: ["a", "b", "c", "d"]
+            .iter()
+            .map(|l| (l.to_string(), 50))
+            .collect(),
+        peak: Arc::clone(&peak),
+        ..Probe::default()
+    };
+    let (mut rt, _log) = setup(probe, &latch);
+    let (graph, plan) = layered(&[&["a", "b", "c", "d"]]);
+
+    let executed = rt
+        .execute_plan_parallel(&plan, &graph, &Compensations::new(), 2)
+        .unwrap();
+
+    assert_eq!(executed, plan.nodes);
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn audit_order_follows_plan_not_completion() {
+    let latch = StopLatch::new();
+    // Earlier nodes finish last.
+    let probe = Probe {
+        sleep_ms: [("a", 60), ("b", 30), ("c", 0)]
+            .iter()
+            .map(|(l, ms)| (l.to_string(), *ms))
+            .collect(),
+        ..Probe::default()
+    };
+    let (mut rt, log) = setup(probe, &latch);
+    let (graph, plan) = layered(&[&["a", "b", "c"], &["d"]]);
+
+    rt.execute_plan_parallel(&plan, &graph, &Compensations::new(), 3)
+        .unwrap();
+
+    let expected: Vec<_> = plan.nodes.iter().map(|id| (*id, true)).collect();
+    assert_eq!(executed_in_log(&log), expected);
+    assert!(verify_entries(&log.entries()).is_ok());
+}
+
+#[test]
+fn soft_stop_lets_in_flight_nodes_finish_and_blocks_next_layer() {
+    let latch = StopLatch::new();
+    let probe = Probe {
+        sleep_ms: [("a", 20), ("b", 100)]
+            .iter()
+            .map(|(l, ms)| (l.to_string(), *ms))
+            .collect(),
+        signal_at: Some(("a".to_string(), latch.clone(), StopSignal::SoftStop)),
+        ..Probe::default()
+    };
+    let (mut rt, log) = setup(probe, &latch);
+    let (graph, plan) = layered(&[&["a", "b"], &["c"]]);
+
+    let err = rt
+        .execute_plan_parallel(&plan, &graph, &Compensations::new(), 2)
+        .unwrap_err();
+
+    let AdrRuntimeError::SoftStopAborted(report) = err else {
+        panic!("expected SoftStopAborted, got {:?}", err);
+    };
+    assert_eq!(report.committed, vec![id(&graph, "b"), id(&graph, "a")]);
+    assert_eq!(rt.state(), RuntimeState::Stopping);
+    assert_eq!(
+        executed_in_log(&log),
+        vec![
+            (id(&graph, "a"), true),
+            (id(&graph, "b"), true),
+            (id(&graph, "c"), false),
+        ]
+    );
+}
+
+#[test]
+fn no_node_starts_once_the_kill_switch_fires_mid_layer() {
+    let latch = StopLatch::new();
+    let probe = Probe {
+        signal_at: Some(("a".to_string(), latch.clone(), StopSignal::HardStop)),
+        ..Probe::default()
+    };
+    let (mut rt, log) = setup(probe, &latch);
+    let (graph, plan) = layered(&[&["a", "b", "c"]]);
+
+    // One worker: b is gated only after a has finished.
+    let err = rt
+        .execute_plan_parallel(&plan, &graph, &Compensations::new(), 1)
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        AdrRuntimeError::StateBlocked(RuntimeState::Halted)
+    ));
+    assert_eq!(
+        executed_in_log(&log),
+        vec![(id(&graph, "a"), true), (id(&graph, "b"), false)]
+    );
+}
+
+#[test]
+fn failed_node_stops_the_plan_after_its_layer() {
+    let latch = StopLatch::new();
+    let (mut rt, log) = setup(Probe::default(), &latch);
+    let (graph, plan) = layered(&[&["fail_a", "b"], &["c"]]);
+
+    let err = rt
+        .execute_plan_parallel(&plan, &graph, &Compensations::new(), 2)
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        AdrRuntimeError::EffectFailed(EffectError::Io(_))
+    ));
+    assert_eq!(
+        executed_in_log(&log),
+        vec![(id(&graph, "fail_a"), false), (id(&graph, "b"), true)]
+    );
+    assert_eq!(rt.state(), RuntimeState::Running);
+}
+
+struct Panics;
+impl EffectHandler for Panics {
+    fn handle(&self, node: &Node, _: &EffectPayload) -> Result<EffectOutput, EffectError> {
+        if node.label == "boom" {
+            panic!("handler bug");
+        }
+        Ok(EffectOutput::None)
+    }
+}
+
+#[test]
+fn panicking_handler_fails_its_node_and_keeps_handlers() {
+    let latch = StopLatch::new();
+    let log = MemoryAuditSink::new();
+    let mut rt = AdrRuntime::new(latch).with_audit_sink(Box::new(log.clone()));
+    rt.register_effect_handler(Effect::None, Box::new(Panics));
+    let (graph, plan) = layered(&[&["a", "boom"], &["c"]]);
+
+    let err = rt
+        .execute_plan_parallel(&plan, &graph, &Compensations::new(), 2)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        AdrRuntimeError::EffectFailed(EffectError::HandlerPanicked(ref m)) if m == "handler bug"
+    ));
+    assert_eq!(
+        executed_in_log(&log),
+        vec![(id(&graph, "a"), true), (id(&graph, "boom"), false)]
+    );
+    let failed = log.entries().into_iter().find(|e| !e.success).unwrap();
+    assert_eq!(
+        failed.detail.as_deref(),
+        Some("effect_failed handler_panicked message=handler bug")
+    );
+    assert_eq!(verify_entries(&log.entries()), Ok(2));
+
+    // The handlers went back to the runtime: the next panic is caught too.
+    let boom = graph.nodes.iter().find(|n| n.label == "boom").unwrap();
+    assert!(matches!(
+        rt.execute_node(boom),
+        Err(AdrRuntimeError::EffectFailed(EffectError::HandlerPanicked(
+            _
+        )))
+    ));
+}
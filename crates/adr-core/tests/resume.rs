@@ -0,0 +1,159 @@
+use adr_core::audit::SigningKey;
+use adr_core::killswitch::{StopLatch, StopSignal};
+use adr_core::{
+    ActionKind, AdrRuntime, AdrRuntimeError, MemoryAuditSink, OperatorKey, ResumeRefusal,
+    ResumeRequest, RuntimeState,
+};
+
+fn operator_key() -> SigningKey {
+    SigningKey::from_bytes(&[7; 32])
+}
+
+fn setup() -> (AdrRuntime<StopLatch>, StopLatch, MemoryAuditSink) {
+    let latch = StopLatch::new();
+    let log = MemoryAuditSink::new();
+    let rt = AdrRuntime::new(latch.clone())
+        .with_audit_sink(Box::new(log.clone()))
+        .with_operator(OperatorKey {
+            operator_id: "alice".to_string(),
+            key: operator_key().verifying_key(),
+        });
+    (rt, latch, log)
+}
+
+fn signed(rt: &AdrRuntime<StopLatch>, operator: &str, key: &SigningKey) -> ResumeRequest {
+    ResumeRequest::sign(&rt.resume_challenge(), operator, "sensor replaced", key)
+}
+
+#[test]
+fn set_state_cannot_lower_state() {
+    let (mut rt, _latch, _log) = setup();
+    rt.set_state(RuntimeState::Frozen).unwrap();
+
+    assert!(matches!(
+        rt.set_state(RuntimeState::Running),
+        Err(AdrRuntimeError::StateDowngrade {
+            from: RuntimeState::Frozen,
+            to: RuntimeState::Running
+        })
+    ));
+    assert_eq!(rt.state(), RuntimeState::Frozen);
+}
+
+#[test]
+fn weaker_signal_does_not_lower_state() {
+    let (mut rt, latch, _log) = setup();
+    latch.set(StopSignal::Freeze);
+    rt.execute_noop().unwrap_err();
+
+    latch.clear();
+    latch.set(StopSignal::SoftStop);
+    rt.execute_noop().unwrap_err();
+
+    assert_eq!(rt.state(), RuntimeState::Frozen);
+}
+
+#[test]
+fn authorized_resume_is_audited() {
+    let (mut rt, latch, log) = setup();
+    latch.set(StopSignal::HardStop);
+    rt.execute_noop().unwrap_err();
+    latch.clear();
+
+    let request = signed(&rt, "alice", &operator_key());
+    rt.resume(&request).unwrap();
+
+    assert_eq!(rt.state(), RuntimeState::Running);
+    rt.execute_noop().unwrap();
+
+    let entry = log.entries().pop().unwrap();
+    assert_eq!(entry.kind, ActionKind::Resume);
+    assert!(entry.success);
+    assert_eq!(
+        entry.detail.as_deref(),
+        Some("operator=alice from=Halted reason=sensor replaced")
+    );
+}
+
+#[test]
+fn resume_refused_while_kill_switch_asserts() {
+    let (mut rt, latch, log) = setup();
+    latch.set(StopSignal::SoftStop);
+    rt.execute_noop().unwrap();
+    assert_eq!(rt.state(), RuntimeState::Stopping);
+
+    let request = signed(&rt, "alice", &operator_key());
+    assert!(matches!(
+        rt.resume(&request),
+        Err(AdrRuntimeError::ResumeRefused(
+            ResumeRefusal::KillSwitchAsserting(StopSignal::SoftStop)
+        ))
+    ));
+    assert_eq!(rt.state(), RuntimeState::Stopping);
+
+    let entry = log.entries().pop().unwrap();
+    assert_eq!(entry.kind, ActionKind::Resume);
+    assert!(!entry.success);
+    assert_eq!(
+        entry.detail.as_deref(),
+        Some(
+            "operator=alice from=Stopping reason=sensor replaced \
+             refused=kill_switch_asserting signal=soft_stop"
+        )
+    );
+}
+
+#[test]
+fn unknown_operator_and_wrong_key_are_refused() {
+    let (mut rt, _latch, _log) = setup();
+    rt.set_state(RuntimeState::Halted).unwrap();
+
+    let mallory = SigningKey::from_bytes(&[9; 32]);
+    let err = rt.resume(&signed(&rt, "mallory", &mallory)).unwrap_err();
+    assert!(matches!(
+        err,
+        AdrRuntimeError::ResumeRefused(ResumeRefusal::UnknownOperator(ref id)) if id == "mallory"
+    ));
+
+    let err = rt.resume(&signed(&rt, "alice", &mallory)).unwrap_err();
+    assert!(matches!(
+        err,
+        AdrRuntimeError::ResumeRefused(ResumeRefusal::BadCredential)
+    ));
+    assert_eq!(rt.state(), RuntimeState::Halted);
+}
+
+#[test]
+fn signed_request_cannot_be_replayed() {
+    let (mut rt, latch, _log) = setup();
+    rt.set_state(RuntimeState::Halted).unwrap();
+    let request = signed(&rt, "alice", &operator_key());
+    rt.resume(&request).unwrap();
+
+    latch.set(StopSignal::HardStop);
+    rt.execute_noop().unwrap_err();
+    latch.clear();
+
+    assert!(matches!(
+        rt.resume(&request),
+        Err(AdrRuntimeError::ResumeRefused(ResumeRefusal::BadCredential))
+    ));
+}
+
+#[test]
+fn resume_needs_reason_and_stopped_runtime() {
+    let (mut rt, _latch, _log) = setup();
+
+    let request = signed(&rt, "alice", &operator_key());
+    assert!(matches!(
+        rt.resume(&request),
+        Err(AdrRuntimeError::ResumeRefused(ResumeRefusal::NotStopped))
+    ));
+
+    rt.set_state(RuntimeState::Frozen).unwrap();
+    let request = ResumeRequest::sign(&rt.resume_challenge(), "alice", " ", &operator_key());
+    assert!(matches!(
+        rt.resume(&request),
+        Err(AdrRuntimeError::ResumeRefused(ResumeRefusal::MissingReason))
+    ));
+}
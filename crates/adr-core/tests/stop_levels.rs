@@ -0,0 +1,158 @@
+use adr_core::audit::SigningKey;
+use adr_core::killswitch::{StopLatch, StopSignal};
+use adr_core::{
+    ActionKind, AdrRuntime, Compensations, Effect, ExecClass, ExecutionPlan, Graph, GraphHeader,
+    MemoryAuditSink, Node, OperatorKey, ResumeRequest, RuntimeState,
+};
+use uuid::Uuid;
+
+const CAP: u64 = 1 << 2;
+
+fn setup() -> (AdrRuntime<StopLatch>, StopLatch, MemoryAuditSink) {
+    let latch = StopLatch::new();
+    let log = MemoryAuditSink::new();
+    let rt = AdrRuntime::new(latch.clone())
+        .with_audit_sink(Box::new(log.clone()))
+        .with_operator(OperatorKey {
+            operator_id: "alice".to_string(),
+            key: SigningKey::from_bytes(&[1; 32]).verifying_key(),
+        });
+    assert!(rt.capabilities().allow_mask(CAP));
+    (rt, latch, log)
+}
+
+fn transitions(log: &MemoryAuditSink) -> Vec<String> {
+    log.entries()
+        .into_iter()
+        .filter(|e| {
+            matches!(
+                e.kind,
+                ActionKind::SoftStop | ActionKind::Halt | ActionKind::Freeze
+            )
+        })
+        .map(|e| e.detail.unwrap())
+        .collect()
+}
+
+#[test]
+fn hard_stop_revokes_and_seals_capabilities() {
+    let (mut rt, latch, log) = setup();
+    latch.set(StopSignal::HardStop);
+
+    rt.execute_noop().unwrap_err();
+
+    assert!(!rt.capabilities().has_mask(CAP));
+    assert!(rt.capabilities().is_sealed());
+    assert!(!rt.capabilities().allow_mask(CAP));
+    assert!(!rt.capabilities().has_mask(CAP));
+    assert_eq!(
+        transitions(&log),
+        vec!["from=Running to=Halted cause=kill_switch=hard_stop"]
+    );
+}
+
+#[test]
+fn freeze_revokes_and_is_audited_as_freeze() {
+    let (mut rt, latch, log) = setup();
+    latch.set(StopSignal::Freeze);
+
+    rt.execute_noop().unwrap_err();
+
+    assert!(!rt.capabilities().has_mask(CAP));
+    assert!(rt.capabilities().is_sealed());
+    assert_eq!(log.entries()[0].kind, ActionKind::Freeze);
+}
+
+#[test]
+fn soft_stop_keeps_capabilities_and_drains_plan() {
+    let (mut rt, latch, log) = setup();
+    let a = Node {
+        id: Uuid::new_v4(),
+        label: "a".to_string(),
+        exec_class: ExecClass::Orchestrated,
+        effect: Effect::None,
+        capabilities: vec![CAP],
+        dependencies: vec![],
+    };
+    let b = Node {
+        id: Uuid::new_v4(),
+        label: "b".to_string(),
+        dependencies: vec![a.id],
+        ..a.clone()
+    };
+    let graph = Graph {
+        header: GraphHeader {
+            graph_version: "0.1".to_string(),
+            deterministic_mode: false,
+        },
+        nodes: vec![a.clone(), b.clone()],
+    };
+    let plan = ExecutionPlan {
+        nodes: vec![a.id, b.id],
+        parallel: vec![],
+        checkpoints: vec![],
+        human_gates: vec![],
+        capabilities: vec![],
+        cap_scope_hash: None,
+        compensations: Compensations::new(),
+        payloads: Default::default(),
+    };
+
+    // The in-flight node completes; the next one is not started.
+    rt.execute_node(&a).unwrap();
+    latch.set(StopSignal::SoftStop);
+    rt.execute_plan(&plan, &graph).unwrap_err();
+
+    assert_eq!(rt.state(), RuntimeState::Stopping);
+    assert!(rt.capabilities().has_mask(CAP));
+    assert!(!rt.capabilities().is_sealed());
+    assert_eq!(transitions(&log).len(), 1);
+    assert!(log
+        .entries()
+        .iter()
+        .any(|e| e.kind == ActionKind::SoftStop && e.success));
+}
+
+#[test]
+fn latched_signal_is_audited_once_and_escalation_again() {
+    let (mut rt, latch, log) = setup();
+    latch.set(StopSignal::SoftStop);
+    for _ in 0..5 {
+        rt.execute_noop().unwrap();
+    }
+    latch.set(StopSignal::HardStop);
+    for _ in 0..5 {
+        rt.execute_noop().unwrap_err();
+    }
+
+    assert_eq!(
+        transitions(&log),
+        vec![
+            "from=Running to=Stopping cause=kill_switch=soft_stop",
+            "from=Stopping to=Halted cause=kill_switch=hard_stop",
+        ]
+    );
+}
+
+#[test]
+fn capabilities_grantable_again_only_after_resume() {
+    let (mut rt, latch, _log) = setup();
+    latch.set(StopSignal::HardStop);
+    rt.execute_noop().unwrap_err();
+    latch.clear();
+
+    assert!(!rt.capabilities().allow_mask(CAP));
+
+    let request = ResumeRequest::sign(
+        &rt.resume_challenge(),
+        "alice",
+        "fault cleared",
+        &SigningKey::from_bytes(&[1; 32]),
+    );
+    rt.resume(&request).unwrap();
+
+    // Resume lifts the seal but does not restore old grants.
+    assert!(!rt.capabilities().has_mask(CAP));
+    assert!(rt.capabilities().allow_mask(CAP));
+    assert!(rt.capabilities().has_mask(CAP));
+}
@@ -0,0 +1,73 @@
+#![cfg(unix)]
+
+use std::sync::Mutex;
+
+use adr_core::killswitch::{KillSwitchChannel, StopSignal, UnixSignalChannel};
+use adr_core::{AdrRuntime, AdrRuntimeError, RuntimeState};
+use signal_hook::consts::signal::{SIGHUP, SIGKILL, SIGTERM, SIGUSR1, SIGUSR2};
+use signal_hook::low_level::raise;
+
+// Signal dispositions are process-wide; run these tests one at a time.
+static SERIAL: Mutex<()> = Mutex::new(());
+
+#[test]
+fn default_map_latches_each_signal() {
+    let _guard = SERIAL.lock().unwrap();
+
+    for (signal, expected) in [
+        (SIGTERM, StopSignal::SoftStop),
+        (SIGUSR1, StopSignal::HardStop),
+        (SIGUSR2, StopSignal::Freeze),
+    ] {
+        let channel = UnixSignalChannel::new().unwrap();
+        assert_eq!(channel.poll(), None);
+
+        raise(signal).unwrap();
+
+        assert_eq!(channel.poll(), Some(expected));
+        // Latched: reading does not consume it.
+        assert_eq!(channel.poll(), Some(expected));
+    }
+}
+
+#[test]
+fn most_severe_latch_wins_until_cleared() {
+    let _guard = SERIAL.lock().unwrap();
+    let channel = UnixSignalChannel::new().unwrap();
+
+    raise(SIGUSR2).unwrap();
+    raise(SIGTERM).unwrap();
+    assert_eq!(channel.poll(), Some(StopSignal::Freeze));
+
+    channel.clear();
+    assert_eq!(channel.poll(), None);
+}
+
+#[test]
+fn custom_map_is_honoured() {
+    let _guard = SERIAL.lock().unwrap();
+    let channel = UnixSignalChannel::with_map(&[(SIGHUP, StopSignal::HardStop)]).unwrap();
+
+    raise(SIGHUP).unwrap();
+
+    assert_eq!(channel.poll(), Some(StopSignal::HardStop));
+}
+
+#[test]
+fn uncatchable_signal_is_refused() {
+    assert!(UnixSignalChannel::with_map(&[(SIGKILL, StopSignal::HardStop)]).is_err());
+}
+
+#[test]
+fn runtime_halts_on_signal() {
+    let _guard = SERIAL.lock().unwrap();
+    let mut rt = AdrRuntime::new(UnixSignalChannel::new().unwrap());
+    rt.execute_noop().unwrap();
+
+    raise(SIGUSR1).unwrap();
+
+    assert!(matches!(
+        rt.execute_noop(),
+        Err(AdrRuntimeError::StateBlocked(RuntimeState::Halted))
+    ));
+}
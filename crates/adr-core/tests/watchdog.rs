@@ -0,0 +1,105 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use adr_core::killswitch::{KillSwitchChannel, StopSignal};
+use adr_core::{
+    ActionKind, AdrRuntime, AdrRuntimeError, ManualClock, MemoryAuditSink, RuntimeState, Watchdog,
+};
+
+struct NoSignal;
+impl KillSwitchChannel for NoSignal {
+    fn poll(&self) -> Option<StopSignal> {
+        None
+    }
+}
+
+const CAP: u64 = 1 << 3;
+
+fn setup() -> (AdrRuntime<NoSignal>, ManualClock, MemoryAuditSink) {
+    let clock = ManualClock::new();
+    let log = MemoryAuditSink::new();
+    let rt = AdrRuntime::new(NoSignal)
+        .with_audit_sink(Box::new(log.clone()))
+        .with_watchdog(Watchdog::new(
+            Duration::from_millis(500),
+            Arc::new(clock.clone()),
+        ));
+    rt.capabilities().allow_mask(CAP);
+    (rt, clock, log)
+}
+
+#[test]
+fn regular_heartbeats_keep_runtime_running() {
+    let (mut rt, clock, log) = setup();
+
+    for _ in 0..10 {
+        clock.advance(Duration::from_millis(400));
+        rt.heartbeat();
+        rt.execute_noop().unwrap();
+    }
+
+    assert_eq!(rt.state(), RuntimeState::Running);
+    assert!(rt.capabilities().has_mask(CAP));
+    assert!(log.entries().is_empty());
+}
+
+#[test]
+fn missed_deadline_halts_and_revokes_capabilities() {
+    let (mut rt, clock, log) = setup();
+
+    clock.advance(Duration::from_millis(501));
+
+    assert!(matches!(
+        rt.execute_noop(),
+        Err(AdrRuntimeError::StateBlocked(RuntimeState::Halted))
+    ));
+    assert!(!rt.capabilities().has_mask(CAP));
+
+    let entries = log.entries();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].kind, ActionKind::Halt);
+    assert!(entries[0].detail.as_deref().unwrap().contains("watchdog"));
+}
+
+#[test]
+fn supervisor_check_enforces_while_idle() {
+    let (mut rt, clock, _log) = setup();
+
+    clock.advance(Duration::from_millis(400));
+    assert_eq!(rt.check_watchdog().unwrap(), RuntimeState::Running);
+
+    clock.advance(Duration::from_millis(200));
+    assert_eq!(rt.check_watchdog().unwrap(), RuntimeState::Halted);
+    assert!(!rt.capabilities().has_mask(CAP));
+}
+
+#[test]
+fn late_heartbeat_does_not_undo_trip() {
+    let (mut rt, clock, log) = setup();
+    let supervisor = rt.watchdog().unwrap().clone();
+
+    clock.advance(Duration::from_secs(1));
+    supervisor.heartbeat();
+
+    assert!(supervisor.expired());
+    assert!(rt.execute_noop().is_err());
+    // Halt is recorded once, not on every check.
+    rt.check_watchdog().unwrap();
+    assert_eq!(log.entries().len(), 1);
+}
+
+#[test]
+fn rearm_starts_fresh_deadline() {
+    let clock = ManualClock::new();
+    let dog = Watchdog::new(Duration::from_millis(100), Arc::new(clock.clone()));
+
+    clock.advance(Duration::from_millis(150));
+    assert!(dog.expired());
+
+    dog.rearm();
+    assert!(!dog.expired());
+    clock.advance(Duration::from_millis(100));
+    assert!(!dog.expired());
+    clock.advance(Duration::from_millis(1));
+    assert!(dog.expired());
+}
@@ -0,0 +1,162 @@
+// =============================================================================
+// ADR – Agent-Oriented Declarative Runtime
+// Layer 2: Kill Switch Builder
+//
+// Turns the `kill_switch` section of a CompiledPolicy into one Layer 1
+// channel. All configured channels are polled together through
+// adr_core::killswitch::CompositeChannel, the most severe signal wins.
+//
+// A policy that requires a physical channel is refused unless one is
+// configured *and* can be instantiated. A configured channel that cannot
+// be built is an error, never silently dropped.
+//
+// Authors: ADR Runtime Contributors
+// Version: 0.1.0
+// License: MIT
+// =============================================================================
+
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use adr_core::killswitch::{self, CompositeChannel};
+use adr_core::{Clock, Watchdog};
+
+use crate::policy::{KillSwitchChannel, KillSwitchConfig};
+
+type GpioFactory = Box<dyn Fn(u8) -> io::Result<Box<dyn killswitch::KillSwitchChannel>>>;
+
+impl KillSwitchChannel {
+    /// Name as written in policy.yaml.
+    pub fn name(&self) -> &'static str {
+        match self {
+            KillSwitchChannel::UnixSignal => "unix_signal",
+            KillSwitchChannel::HardwareGpio { .. } => "hardware_gpio",
+            KillSwitchChannel::LocalNamedPipe { .. } => "local_named_pipe",
+            KillSwitchChannel::LocalHttp { .. } => "local_http",
+        }
+    }
+
+    /// Physical channels keep working when all software on the host fails.
+    pub fn is_physical(&self) -> bool {
+        matches!(self, KillSwitchChannel::HardwareGpio { .. })
+    }
+}
+
+impl KillSwitchConfig {
+    /// Watchdog for `watchdog_timer`, `None` if the policy sets no timer.
+    pub fn watchdog(&self, clock: Arc<dyn Clock>) -> Option<Watchdog> {
+        self.watchdog_timer.map(|timeout| Watchdog::new(timeout, clock))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KillSwitchBuildError {
+    /// `channels` is empty; nothing could ever stop the runtime.
+    NoChannels,
+    /// `require_physical_channel` is set but no physical channel is listed.
+    PhysicalChannelRequired,
+    /// No adapter for this channel on this platform / builder.
+    Unsupported(&'static str),
+    /// The adapter exists but failed to start.
+    ChannelFailed { channel: &'static str, message: String },
+}
+
+impl fmt::Display for KillSwitchBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KillSwitchBuildError::NoChannels => write!(f, "no kill switch channel configured"),
+            KillSwitchBuildError::PhysicalChannelRequired => write!(
+                f,
+                "policy requires a physical kill switch channel, none configured"
+            ),
+            KillSwitchBuildError::Unsupported(name) => {
+                write!(f, "kill switch channel `{}` is not available", name)
+            }
+            KillSwitchBuildError::ChannelFailed { channel, message } => {
+                write!(f, "kill switch channel `{}` failed: {}", channel, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for KillSwitchBuildError {}
+
+/// Builds a `CompositeChannel` from a policy's `KillSwitchConfig`.
+///
+/// GPIO access is board specific, so hardware channels need an adapter
+/// supplied through `gpio`.
+pub struct KillSwitchBuilder<'a> {
+    config:     &'a KillSwitchConfig,
+    gpio:       Option<GpioFactory>,
+    http_token: Option<String>,
+}
+
+impl<'a> KillSwitchBuilder<'a> {
+    pub fn new(config: &'a KillSwitchConfig) -> Self {
+        Self { config, gpio: None, http_token: None }
+    }
+
+    /// Adapter that opens a GPIO kill switch for the given pin.
+    pub fn gpio<F>(mut self, factory: F) -> Self
+    where
+        F: Fn(u8) -> io::Result<Box<dyn killswitch::KillSwitchChannel>> + 'static,
+    {
+        self.gpio = Some(Box::new(factory));
+        self
+    }
+
+    /// Shared secret required by `local_http` channels.
+    pub fn http_token(mut self, token: impl Into<String>) -> Self {
+        self.http_token = Some(token.into());
+        self
+    }
+
+    pub fn build(&self) -> Result<CompositeChannel, KillSwitchBuildError> {
+        if self.config.channels.is_empty() {
+            return Err(KillSwitchBuildError::NoChannels);
+        }
+        if self.config.require_physical_channel
+            && !self.config.channels.iter().any(KillSwitchChannel::is_physical)
+        {
+            return Err(KillSwitchBuildError::PhysicalChannelRequired);
+        }
+
+        let mut composite = CompositeChannel::new();
+        for channel in &self.config.channels {
+            composite.push(self.instantiate(channel)?);
+        }
+        Ok(composite)
+    }
+
+    fn instantiate(
+        &self,
+        channel: &KillSwitchChannel,
+    ) -> Result<Box<dyn killswitch::KillSwitchChannel>, KillSwitchBuildError> {
+        let failed = |e: io::Error| KillSwitchBuildError::ChannelFailed {
+            channel: channel.name(),
+            message: e.to_string(),
+        };
+
+        match channel {
+            #[cfg(unix)]
+            KillSwitchChannel::UnixSignal => Ok(Box::new(
+                killswitch::UnixSignalChannel::new().map_err(failed)?,
+            )),
+            #[cfg(unix)]
+            KillSwitchChannel::LocalNamedPipe { path } => Ok(Box::new(
+                killswitch::LocalNamedPipeChannel::open(path).map_err(failed)?,
+            )),
+            KillSwitchChannel::LocalHttp { port } => Ok(Box::new(
+                killswitch::LocalHttpChannel::bind(*port, self.http_token.clone())
+                    .map_err(failed)?,
+            )),
+            KillSwitchChannel::HardwareGpio { pin } => match &self.gpio {
+                Some(factory) => factory(*pin).map_err(failed),
+                None => Err(KillSwitchBuildError::Unsupported(channel.name())),
+            },
+            #[allow(unreachable_patterns)]
+            _ => Err(KillSwitchBuildError::Unsupported(channel.name())),
+        }
+    }
+}
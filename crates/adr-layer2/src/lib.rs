@@ -14,14 +14,21 @@
 // Repository: https://github.com/adr-runtime/adr-runtime
 // =============================================================================
 
+pub mod kill_switch;
 pub mod policy;
+pub mod policy_compiler;
 pub mod resolver;
 pub mod types;
 pub mod policy_engine;
 
 // Re-export the most commonly used items for convenience
+pub use kill_switch::{KillSwitchBuildError, KillSwitchBuilder};
 pub use policy::CompiledPolicy;
-pub use resolver::{AdrGraph, IntentResolver, RuleBasedResolver, RuntimeContext, RuntimeStateSnapshot};
+pub use policy_compiler::{compile_policy, compile_policy_file, PolicyCompileError};
+pub use resolver::{
+    soft_stop_compensations, verify_checkpoints, AdrGraph, IntentResolver, RuleBasedResolver,
+    RuntimeContext, RuntimeStateSnapshot,
+};
 pub use types::{
     Capability, ExecutionDecision, ExecutionPlan, ExecClass, IntentNode,
     NodeId, NodeType, RejectedPlan, RejectionReason, ResolverResult,
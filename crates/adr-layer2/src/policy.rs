@@ -12,9 +12,122 @@
 // License: MIT
 // =============================================================================
 
+use adr_core::capability_scope::split_port;
 use serde::{Deserialize, Serialize};
 use crate::types::{Capability, ExecClass, NodeType, TrustTier};
 
+/// Canonical class of a capability name, accepting the short forms used
+/// in scoped names: `fs:/data/out` is `fs_write`, `net:api.example.com`
+/// is `net_external`, `actuator:valve_3` is `actuator_control`.
+pub fn capability_class(cap: &Capability) -> Option<&'static str> {
+    match cap.0.split(':').next().unwrap_or_default() {
+        "fs" | "fs_write" => Some("fs_write"),
+        "net" | "net_external" => Some("net_external"),
+        "actuator" | "actuator_control" => Some("actuator_control"),
+        _ => None,
+    }
+}
+
+/// Whether a node using `used` falls under the policy pattern `pattern`.
+///
+/// Both must name the same class. A pattern without a scope matches the
+/// whole class. A scope is either a glob, where `*` stands for any run of
+/// characters (`fs:/data/*`, `net:*.example.com`), or a plain prefix that
+/// matches itself and everything below it as a path (`fs:/data/out`).
+/// An unscoped `used` reaches the whole class and therefore matches every
+/// scope of it.
+///
+/// `fs` scopes are compared in normalized form (`normalize_path`), so
+/// `fs:/data/../etc/shadow` is matched as `fs:/etc/shadow`. A relative
+/// path that climbs above its start cannot be placed and is treated like
+/// an unscoped capability.
+///
+/// `net` scopes are split into host and port first. Hosts compare
+/// case-insensitively, and a side without a port reaches every port, so
+/// `net:*.example.com` matches `net:API.example.com:443`.
+pub fn capability_matches(pattern: &Capability, used: &Capability) -> bool {
+    let class = match (capability_class(pattern), capability_class(used)) {
+        (Some(p), Some(u)) if p == u => p,
+        _ => return false,
+    };
+    let scope = |cap: &Capability| {
+        let (_, scope) = cap.0.split_once(':')?;
+        match class {
+            "fs_write" => normalize_path(scope),
+            _ => Some(scope.to_string()),
+        }
+    };
+    match (scope(pattern), scope(used)) {
+        (None, _) | (Some(_), None) => true,
+        (Some(pattern), Some(scope)) if class == "net_external" => {
+            host_scope_matches(&pattern, &scope)
+        }
+        (Some(glob), Some(scope)) if glob.contains('*') => glob_matches(&glob, &scope),
+        (Some(prefix), Some(scope)) => match scope.strip_prefix(prefix.as_str()) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'),
+            None => false,
+        },
+    }
+}
+
+/// `net` scope comparison for `capability_matches`.
+fn host_scope_matches(pattern: &str, scope: &str) -> bool {
+    let (pattern, pattern_port) = split_port(pattern);
+    let (host, port) = split_port(scope);
+    let (pattern, host) = (pattern.to_ascii_lowercase(), host.to_ascii_lowercase());
+    let host_matches = if pattern.contains('*') {
+        glob_matches(&pattern, &host)
+    } else {
+        pattern == host
+    };
+    host_matches && (pattern_port.is_none() || port.is_none() || pattern_port == port)
+}
+
+/// Lexically normalized path: empty and `.` components are dropped and
+/// `..` removes the component before it, so `/data/./out/../x` becomes
+/// `/data/x`. `..` at the root stays at the root. Returns `None` for a
+/// relative path that climbs above its start.
+fn normalize_path(path: &str) -> Option<String> {
+    let absolute = path.starts_with('/');
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                if parts.pop().is_none() && !absolute {
+                    return None;
+                }
+            }
+            part => parts.push(part),
+        }
+    }
+    let joined = parts.join("/");
+    Some(if absolute { format!("/{}", joined) } else { joined })
+}
+
+/// `*` matches any run of characters, including none and `/`.
+fn glob_matches(glob: &str, text: &str) -> bool {
+    let (glob, text): (Vec<char>, Vec<char>) = (glob.chars().collect(), text.chars().collect());
+    let (mut g, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if g < glob.len() && glob[g] == '*' {
+            backtrack = Some((g, t));
+            g += 1;
+        } else if g < glob.len() && glob[g] == text[t] {
+            g += 1;
+            t += 1;
+        } else if let Some((star, matched)) = backtrack {
+            g = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    glob[g..].iter().all(|c| *c == '*')
+}
+
 // -----------------------------------------------------------------------------
 // Trust Override
 // Trust tier can only be RAISED, never lowered.
@@ -29,6 +142,32 @@ pub struct TrustOverride {
     pub downgrade_forbidden: bool,
     /// If true, not even the operator can override (used for checkpoints)
     pub immutable:         bool,
+    /// Distinct approvers a matching node's human gate needs.
+    /// Implies a gate even when the tier alone would not open one.
+    pub require_approvals: Option<adr_core::Quorum>,
+}
+
+/// What an operator override may do to a tier set by policy.
+/// Ordered from weakest to strongest lock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TierLock {
+    /// Operator may raise or lower the tier
+    Open,
+    /// Operator may only raise the tier (`downgrade_forbidden`)
+    NoDowngrade,
+    /// Operator may not change the tier at all (`immutable`)
+    Immutable,
+}
+
+impl TierLock {
+    /// Whether an operator may move a node from `policy` to `requested`.
+    pub fn permits(self, policy: &TrustTier, requested: &TrustTier) -> bool {
+        match self {
+            TierLock::Open        => true,
+            TierLock::NoDowngrade => requested >= policy,
+            TierLock::Immutable   => requested == policy,
+        }
+    }
 }
 
 /// Rules for matching nodes in the graph.
@@ -37,9 +176,8 @@ pub struct MatchRule {
     pub effect_prefix: Option<String>,   // e.g. "fs_write" matches "fs_write:/data"
     pub node_type:     Option<NodeType>,
     pub exec_class:    Option<ExecClass>,
-    // TODO Phase 8: capability matching requires Node data from adr-core (Layer 1).
-    // Field is kept in schema for forward-compatibility but not yet evaluated
-    // in rule_matches(). Will be wired up once NodeStore is accessible.
+    /// Capability pattern, e.g. "fs:/data/*"; see `capability_matches`.
+    /// Matches nodes that use at least one capability under it.
     pub capability:    Option<Capability>,
 }
 
@@ -48,16 +186,8 @@ pub struct MatchRule {
 // Conditions that cause the runtime to enter emergency_freeze state.
 // -----------------------------------------------------------------------------
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum FreezeTrigger {
-    ContractFailure,
-    UnverifiedCapabilityUse,
-    TrustTierDowngradeAttempt,
-    /// Unexpected capability scope change detected
-    CapScopeHashMismatch,
-    DeterministicModeViolation,
-}
+/// Detected by Layer 1; see `adr_core::AdrRuntime::with_freeze_triggers`.
+pub use adr_core::FreezeTrigger;
 
 // -----------------------------------------------------------------------------
 // Audit Configuration
@@ -82,6 +212,34 @@ pub enum MerkleRootHolder {
     MultiParty { signers: Vec<MerkleSigner> },
 }
 
+impl MerkleRootHolder {
+    /// Role name a `Certifier` signs under.
+    pub const CERTIFIER_ROLE: &'static str = "certifier";
+
+    /// Signatures a sealed audit root needs before it counts as certified.
+    /// `None` for `Local`: the operator keeps the root, nobody co-signs.
+    /// `MultiParty` requires every listed signer.
+    pub fn signature_requirement(&self) -> Option<adr_core::audit::SignatureRequirement> {
+        use adr_core::audit::{RequiredSigner, SignatureRequirement};
+
+        match self {
+            MerkleRootHolder::Local => None,
+            MerkleRootHolder::Certifier { id } => Some(SignatureRequirement::all(vec![
+                RequiredSigner {
+                    role: Self::CERTIFIER_ROLE.to_string(),
+                    id:   Some(id.clone()),
+                },
+            ])),
+            MerkleRootHolder::MultiParty { signers } => Some(SignatureRequirement::all(
+                signers
+                    .iter()
+                    .map(|s| RequiredSigner { role: s.role.clone(), id: s.id.clone() })
+                    .collect(),
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MerkleSigner {
     pub role: String,
@@ -106,6 +264,15 @@ pub struct AuditConfig {
     pub time_source:            TimeSource,
 }
 
+impl AuditConfig {
+    /// Merkle batching settings for Layer 1, or `None` when the policy
+    /// does not ask for a tamper-evident log.
+    pub fn merkle_config(&self) -> Option<adr_core::MerkleConfig> {
+        self.tamper_evident
+            .then(|| adr_core::MerkleConfig::new(self.merkle_anchor_interval))
+    }
+}
+
 // -----------------------------------------------------------------------------
 // Kill Switch Configuration
 // -----------------------------------------------------------------------------
@@ -150,6 +317,14 @@ pub struct CompiledPolicy {
 	pub allowed_capabilities: Vec<crate::types::Capability>,
 	pub minimum_trust_tier: Option<crate::types::TrustTier>,
 	pub allowed_effects: Option<Vec<adr_core::Effect>>,
+	/// The executor pauses for an acknowledged checkpoint before every
+	/// node with one of these effects.
+	pub checkpoint_before: Vec<adr_core::Effect>,
+	/// Deadline for open human gates and what happens when it passes.
+	/// `None`: gates wait indefinitely.
+	pub approval_timeout: Option<adr_core::ApprovalTimeout>,
+	/// Nodes matching any of these rules are dropped from every plan.
+	pub deny: Vec<MatchRule>,
 }
 
 impl CompiledPolicy {
@@ -165,30 +340,102 @@ impl CompiledPolicy {
 
     /// Returns the effective trust tier for a node, after applying overrides.
     /// Trust tier can only be raised, never lowered.
+    /// Checkpoint nodes are always `human_required`.
     pub fn effective_trust_tier(
         &self,
         declared: &TrustTier,
         effect:   Option<&str>,
         node_type: Option<&NodeType>,
         exec_class: Option<&ExecClass>,
+        capabilities: &[Capability],
     ) -> TrustTier {
+        if node_type == Some(&NodeType::Checkpoint) {
+            return TrustTier::HumanRequired;
+        }
         let mut tier = declared.clone();
         for rule in &self.trust_overrides {
-            if self.rule_matches(&rule.match_rule, effect, node_type, exec_class) {
-                if rule.set_tier > tier {
-                    tier = rule.set_tier.clone();
-                }
+            if self.rule_matches(&rule.match_rule, effect, node_type, exec_class, capabilities)
+                && rule.set_tier > tier
+            {
+                tier = rule.set_tier.clone();
             }
         }
         tier
     }
 
+    /// How firmly matching overrides pin a node's tier against operator
+    /// overrides: the strongest lock among them. Checkpoint nodes are
+    /// always `Immutable`.
+    pub fn tier_lock(
+        &self,
+        effect:    Option<&str>,
+        node_type: Option<&NodeType>,
+        exec_class: Option<&ExecClass>,
+        capabilities: &[Capability],
+    ) -> TierLock {
+        if node_type == Some(&NodeType::Checkpoint) {
+            return TierLock::Immutable;
+        }
+        self.trust_overrides
+            .iter()
+            .filter(|rule| {
+                self.rule_matches(&rule.match_rule, effect, node_type, exec_class, capabilities)
+            })
+            .map(|rule| {
+                if rule.immutable {
+                    TierLock::Immutable
+                } else if rule.downgrade_forbidden {
+                    TierLock::NoDowngrade
+                } else {
+                    TierLock::Open
+                }
+            })
+            .max()
+            .unwrap_or(TierLock::Open)
+    }
+
+    /// Quorum for a node's human gate: the largest `require_approvals`
+    /// among matching overrides, `None` if no matching override sets one.
+    pub fn required_approvals(
+        &self,
+        effect:    Option<&str>,
+        node_type: Option<&NodeType>,
+        exec_class: Option<&ExecClass>,
+        capabilities: &[Capability],
+    ) -> Option<adr_core::Quorum> {
+        self.trust_overrides
+            .iter()
+            .filter(|rule| {
+                self.rule_matches(&rule.match_rule, effect, node_type, exec_class, capabilities)
+            })
+            .filter_map(|rule| rule.require_approvals.as_ref())
+            .fold(None, |best: Option<&adr_core::Quorum>, q| match best {
+                Some(b) if b.count >= q.count => Some(b),
+                _ => Some(q),
+            })
+            .cloned()
+    }
+
+    /// Returns true if a node with these properties matches a `deny` rule.
+    pub fn denies(
+        &self,
+        effect:    Option<&str>,
+        node_type: Option<&NodeType>,
+        exec_class: Option<&ExecClass>,
+        capabilities: &[Capability],
+    ) -> bool {
+        self.deny
+            .iter()
+            .any(|rule| self.rule_matches(rule, effect, node_type, exec_class, capabilities))
+    }
+
     fn rule_matches(
         &self,
         rule:      &MatchRule,
         effect:    Option<&str>,
         node_type: Option<&NodeType>,
         exec_class: Option<&ExecClass>,
+        capabilities: &[Capability],
     ) -> bool {
         if let Some(prefix) = &rule.effect_prefix {
             if let Some(eff) = effect {
@@ -209,9 +456,11 @@ impl CompiledPolicy {
                 return false;
             }
         }
-        // TODO Phase 8: add capability matching here once Node data
-        // from adr-core (Layer 1) is accessible via NodeStore.
-        // rule.capability is intentionally not evaluated yet.
+        if let Some(pattern) = &rule.capability {
+            if !capabilities.iter().any(|cap| capability_matches(pattern, cap)) {
+                return false;
+            }
+        }
         true
     }
 }
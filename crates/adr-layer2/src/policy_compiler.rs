@@ -0,0 +1,753 @@
+// =============================================================================
+// ADR – Agent-Oriented Declarative Runtime
+// Layer 2: policy.yaml Compiler
+//
+// Turns a policy.yaml document (SPEC §5) into a CompiledPolicy.
+//
+// The compiler runs in three passes:
+//   1. Parse   – YAML syntax and schema (unknown keys are rejected)
+//   2. Validate – every section is checked against the policy enums
+//   3. Seal    – policy_hash is computed over the canonical source
+//
+// Every error carries the 1-based line of the offending input where it
+// can be located, so operators can fix a policy without guessing.
+//
+// Authors: ADR Runtime Contributors
+// Version: 0.1.0
+// License: MIT
+// =============================================================================
+
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+use adr_core::{capability_name_to_mask, ApprovalTimeout, Effect, Quorum, TimeoutAction};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::policy::{
+    capability_class, AuditConfig, CompiledPolicy, FreezeTrigger, KillSwitchChannel,
+    KillSwitchConfig, LogLevel, MatchRule, MerkleRootHolder, MerkleSigner, TimeSource,
+    TrustOverride,
+};
+use crate::types::{Capability, ExecClass, NodeType, TrustTier};
+
+/// Default interval for Merkle heartbeat anchors when `audit` omits it.
+pub const DEFAULT_MERKLE_ANCHOR_INTERVAL: Duration = Duration::from_secs(300);
+
+// -----------------------------------------------------------------------------
+// Errors
+// -----------------------------------------------------------------------------
+
+/// Why a policy.yaml was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyErrorKind {
+    /// The file could not be read.
+    Io(String),
+    /// Invalid YAML, an unknown key, or a value outside the policy enums.
+    Syntax(String),
+    /// A required string field is present but empty.
+    EmptyField(&'static str),
+    /// Capability name has no Layer 1 mask (see `capability_name_to_mask`).
+    UnknownCapability(String),
+    /// Duration literal is not of the form `<n>ms`, `<n>s`, `<n>m` or `<n>h`.
+    InvalidDuration(String),
+    /// `merkle_root_holder: certifier` without `certifier_id`.
+    MissingCertifierId,
+    /// `merkle_root_holder: multi_party` without any `signers`.
+    MissingSigners,
+    /// The same entry is listed twice in a set-like section.
+    Duplicate(String),
+    /// `on_timeout: escalate` without `escalate_to`.
+    MissingEscalationRole,
+    /// `require_approvals.count` is zero.
+    InvalidQuorum,
+}
+
+/// A structured, line-numbered policy compilation error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyCompileError {
+    /// 1-based line in the source, if the error could be located.
+    pub line: Option<usize>,
+    /// 1-based column in the source, if known.
+    pub column: Option<usize>,
+    pub kind: PolicyErrorKind,
+}
+
+impl PolicyCompileError {
+    fn new(kind: PolicyErrorKind) -> Self {
+        Self {
+            line: None,
+            column: None,
+            kind,
+        }
+    }
+
+    fn at(mut self, line: Option<usize>) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl fmt::Display for PolicyErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyErrorKind::Io(msg) => write!(f, "cannot read policy: {}", msg),
+            PolicyErrorKind::Syntax(msg) => write!(f, "{}", msg),
+            PolicyErrorKind::EmptyField(field) => write!(f, "`{}` must not be empty", field),
+            PolicyErrorKind::UnknownCapability(cap) => {
+                write!(f, "unknown capability `{}`", cap)
+            }
+            PolicyErrorKind::InvalidDuration(value) => write!(
+                f,
+                "invalid duration `{}` (expected e.g. `500ms`, `30s`, `5m`, `1h`)",
+                value
+            ),
+            PolicyErrorKind::MissingCertifierId => {
+                write!(f, "`merkle_root_holder: certifier` requires `certifier_id`")
+            }
+            PolicyErrorKind::MissingSigners => {
+                write!(
+                    f,
+                    "`merkle_root_holder: multi_party` requires at least one signer"
+                )
+            }
+            PolicyErrorKind::Duplicate(entry) => write!(f, "duplicate entry `{}`", entry),
+            PolicyErrorKind::MissingEscalationRole => {
+                write!(f, "`on_timeout: escalate` requires `escalate_to`")
+            }
+            PolicyErrorKind::InvalidQuorum => {
+                write!(f, "`require_approvals.count` must be at least 1")
+            }
+        }
+    }
+}
+
+impl fmt::Display for PolicyCompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(col)) => write!(f, "policy.yaml:{}:{}: {}", line, col, self.kind),
+            (Some(line), None) => write!(f, "policy.yaml:{}: {}", line, self.kind),
+            _ => write!(f, "policy.yaml: {}", self.kind),
+        }
+    }
+}
+
+impl std::error::Error for PolicyCompileError {}
+
+// -----------------------------------------------------------------------------
+// Source schema (pass 1)
+// Mirrors policy.yaml one-to-one. Unknown keys are rejected so that a typo
+// such as `effect_prefx` can never silently widen a match rule.
+// -----------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicySource {
+    domain: String,
+    version: String,
+    #[serde(default)]
+    trust_overrides: Vec<TrustOverrideSource>,
+    #[serde(default)]
+    freeze_triggers: Vec<FreezeTrigger>,
+    kill_switch: KillSwitchSource,
+    audit: AuditSource,
+    #[serde(default)]
+    allowed_capabilities: Vec<String>,
+    #[serde(default)]
+    minimum_trust_tier: Option<TrustTier>,
+    #[serde(default)]
+    allowed_effects: Option<Vec<EffectSource>>,
+    #[serde(default)]
+    checkpoint_before: Vec<EffectSource>,
+    #[serde(default)]
+    approval_timeout: Option<ApprovalTimeoutSource>,
+    #[serde(default)]
+    deny: Vec<MatchRuleSource>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TrustOverrideSource {
+    #[serde(rename = "match")]
+    match_rule: MatchRuleSource,
+    set_tier: TrustTier,
+    #[serde(default)]
+    downgrade_forbidden: bool,
+    #[serde(default)]
+    immutable: bool,
+    #[serde(default)]
+    require_approvals: Option<QuorumSource>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QuorumSource {
+    count: usize,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MatchRuleSource {
+    effect_prefix: Option<String>,
+    node_type: Option<NodeType>,
+    exec_class: Option<ExecClass>,
+    capability: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ApprovalTimeoutSource {
+    after: String,
+    on_timeout: TimeoutActionSource,
+    #[serde(default)]
+    escalate_to: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TimeoutActionSource {
+    Reject,
+    Escalate,
+    SoftStop,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KillSwitchSource {
+    #[serde(default)]
+    require_physical_channel: bool,
+    #[serde(default)]
+    channels: Vec<ChannelSource>,
+    #[serde(default)]
+    watchdog_timer: Option<String>,
+    #[serde(default)]
+    offline_capable: bool,
+}
+
+/// Channels are written either as a bare name (`unix_signal`), in the
+/// tagged form (`!hardware_gpio { pin: 17 }`) or as a single-key map
+/// (`hardware_gpio: { pin: 17 }`), matching how SPEC §5 lists them.
+#[derive(Debug, Deserialize)]
+#[serde(
+    untagged,
+    expecting = "a kill switch channel such as `unix_signal` or `hardware_gpio: { pin: 17 }`"
+)]
+enum ChannelSource {
+    Native(KillSwitchChannel),
+    Keyed(KeyedChannelSource),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeyedChannelSource {
+    hardware_gpio: Option<GpioSource>,
+    local_named_pipe: Option<NamedPipeSource>,
+    local_http: Option<LocalHttpSource>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GpioSource {
+    pin: u8,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NamedPipeSource {
+    path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LocalHttpSource {
+    port: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RootHolderKind {
+    Local,
+    Certifier,
+    MultiParty,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AuditSource {
+    #[serde(default = "default_log_level")]
+    log_level: LogLevel,
+    #[serde(default = "default_root_holder")]
+    merkle_root_holder: RootHolderKind,
+    #[serde(default)]
+    certifier_id: Option<String>,
+    #[serde(default)]
+    signers: Vec<MerkleSigner>,
+    #[serde(default)]
+    merkle_anchor_interval: Option<String>,
+    #[serde(default = "default_tamper_evident")]
+    tamper_evident: bool,
+    #[serde(default = "default_time_source")]
+    time_source: TimeSource,
+}
+
+/// An effect as named in policy.yaml (`Effect::as_str`); `adr_core::Effect`
+/// itself uses Rust variant names on the wire.
+#[derive(Debug)]
+struct EffectSource(Effect);
+
+impl<'de> Deserialize<'de> for EffectSource {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // A visitor, so an unknown name is reported at its own line.
+        struct Name;
+        impl serde::de::Visitor<'_> for Name {
+            type Value = EffectSource;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an effect name")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<EffectSource, E> {
+                Effect::from_name(name)
+                    .map(EffectSource)
+                    .ok_or_else(|| E::custom(format!("unknown effect `{}`", name)))
+            }
+        }
+        deserializer.deserialize_str(Name)
+    }
+}
+
+fn default_log_level() -> LogLevel {
+    LogLevel::Standard
+}
+
+fn default_root_holder() -> RootHolderKind {
+    RootHolderKind::Local
+}
+
+fn default_tamper_evident() -> bool {
+    true
+}
+
+fn default_time_source() -> TimeSource {
+    TimeSource::LocalClock
+}
+
+// -----------------------------------------------------------------------------
+// Public API
+// -----------------------------------------------------------------------------
+
+/// Compiles policy.yaml source text into a `CompiledPolicy`.
+pub fn compile_policy(source: &str) -> Result<CompiledPolicy, PolicyCompileError> {
+    // Pass 1: parse
+    let parsed: PolicySource = serde_yaml::from_str(source).map_err(|e| {
+        let location = e.location();
+        PolicyCompileError {
+            line: location.as_ref().map(|l| l.line()),
+            column: location.as_ref().map(|l| l.column()),
+            kind: PolicyErrorKind::Syntax(strip_location(&e.to_string())),
+        }
+    })?;
+
+    // Pass 2: validate
+    let policy = validate(parsed, source)?;
+
+    // Pass 3: seal
+    Ok(CompiledPolicy {
+        policy_hash: policy_hash(source),
+        ..policy
+    })
+}
+
+/// Reads and compiles a policy.yaml file.
+pub fn compile_policy_file(path: impl AsRef<Path>) -> Result<CompiledPolicy, PolicyCompileError> {
+    let source = std::fs::read_to_string(path.as_ref())
+        .map_err(|e| PolicyCompileError::new(PolicyErrorKind::Io(e.to_string())))?;
+    compile_policy(&source)
+}
+
+/// SHA-256 over the canonical form of a policy source, hex-encoded.
+///
+/// Canonical form: CRLF/CR line endings become LF, trailing whitespace is
+/// removed from every line, and trailing blank lines are dropped. Editors
+/// and checkouts on different platforms therefore yield the same hash.
+pub fn policy_hash(source: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(canonical_source(source).as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn canonical_source(source: &str) -> String {
+    let normalized = source.replace("\r\n", "\n").replace('\r', "\n");
+    let mut lines: Vec<&str> = normalized.lines().map(str::trim_end).collect();
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+// -----------------------------------------------------------------------------
+// Pass 2: validation
+// -----------------------------------------------------------------------------
+
+fn validate(src: PolicySource, text: &str) -> Result<CompiledPolicy, PolicyCompileError> {
+    if src.domain.trim().is_empty() {
+        return Err(
+            PolicyCompileError::new(PolicyErrorKind::EmptyField("domain"))
+                .at(locate_key(text, "domain")),
+        );
+    }
+    if src.version.trim().is_empty() {
+        return Err(
+            PolicyCompileError::new(PolicyErrorKind::EmptyField("version"))
+                .at(locate_key(text, "version")),
+        );
+    }
+
+    let mut trust_overrides = Vec::with_capacity(src.trust_overrides.len());
+    for rule in src.trust_overrides {
+        let match_rule = match_rule(rule.match_rule, text)?;
+        let require_approvals = match rule.require_approvals {
+            Some(q) => Some(quorum(q, text)?),
+            None => None,
+        };
+        trust_overrides.push(TrustOverride {
+            match_rule,
+            set_tier: rule.set_tier,
+            downgrade_forbidden: rule.downgrade_forbidden,
+            immutable: rule.immutable,
+            require_approvals,
+        });
+    }
+
+    let mut freeze_triggers: Vec<FreezeTrigger> = Vec::new();
+    for trigger in src.freeze_triggers {
+        if freeze_triggers.contains(&trigger) {
+            let name = enum_name(&trigger);
+            return Err(
+                PolicyCompileError::new(PolicyErrorKind::Duplicate(name.clone()))
+                    .at(locate_last(text, &name)),
+            );
+        }
+        freeze_triggers.push(trigger);
+    }
+
+    let mut channels = Vec::with_capacity(src.kill_switch.channels.len());
+    for channel in src.kill_switch.channels {
+        channels.push(kill_switch_channel(channel, text)?);
+    }
+
+    let kill_switch = KillSwitchConfig {
+        require_physical_channel: src.kill_switch.require_physical_channel,
+        channels,
+        watchdog_timer: src
+            .kill_switch
+            .watchdog_timer
+            .map(|v| parse_duration(&v).map_err(|e| e.at(locate_key(text, "watchdog_timer"))))
+            .transpose()?,
+        offline_capable: src.kill_switch.offline_capable,
+    };
+
+    let merkle_root_holder = match src.audit.merkle_root_holder {
+        RootHolderKind::Local => MerkleRootHolder::Local,
+        RootHolderKind::Certifier => match src.audit.certifier_id {
+            Some(id) if !id.trim().is_empty() => MerkleRootHolder::Certifier { id },
+            _ => {
+                return Err(PolicyCompileError::new(PolicyErrorKind::MissingCertifierId)
+                    .at(locate_key(text, "merkle_root_holder")))
+            }
+        },
+        RootHolderKind::MultiParty => {
+            if src.audit.signers.is_empty() {
+                return Err(PolicyCompileError::new(PolicyErrorKind::MissingSigners)
+                    .at(locate_key(text, "merkle_root_holder")));
+            }
+            let mut roles: Vec<&str> = Vec::new();
+            for signer in &src.audit.signers {
+                if roles.contains(&signer.role.as_str()) {
+                    return Err(PolicyCompileError::new(PolicyErrorKind::Duplicate(
+                        signer.role.clone(),
+                    ))
+                    .at(locate_last(text, &signer.role)));
+                }
+                roles.push(&signer.role);
+            }
+            MerkleRootHolder::MultiParty {
+                signers: src.audit.signers,
+            }
+        }
+    };
+
+    let audit = AuditConfig {
+        log_level: src.audit.log_level,
+        merkle_root_holder,
+        merkle_anchor_interval: match src.audit.merkle_anchor_interval {
+            Some(v) => {
+                parse_duration(&v).map_err(|e| e.at(locate_key(text, "merkle_anchor_interval")))?
+            }
+            None => DEFAULT_MERKLE_ANCHOR_INTERVAL,
+        },
+        tamper_evident: src.audit.tamper_evident,
+        time_source: src.audit.time_source,
+    };
+
+    let mut allowed_capabilities: Vec<Capability> = Vec::new();
+    for cap in src.allowed_capabilities {
+        let cap = known_capability(cap, text)?;
+        if allowed_capabilities.contains(&cap) {
+            return Err(
+                PolicyCompileError::new(PolicyErrorKind::Duplicate(cap.0.clone()))
+                    .at(locate_last(text, &cap.0)),
+            );
+        }
+        allowed_capabilities.push(cap);
+    }
+
+    let mut checkpoint_before: Vec<Effect> = Vec::new();
+    for EffectSource(effect) in src.checkpoint_before {
+        if checkpoint_before.contains(&effect) {
+            let name = effect.as_str();
+            return Err(
+                PolicyCompileError::new(PolicyErrorKind::Duplicate(name.to_string()))
+                    .at(locate_last(text, name)),
+            );
+        }
+        checkpoint_before.push(effect);
+    }
+
+    let approval_timeout = src
+        .approval_timeout
+        .map(|t| approval_timeout(t, text))
+        .transpose()?;
+
+    let deny = src
+        .deny
+        .into_iter()
+        .map(|rule| match_rule(rule, text))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(CompiledPolicy {
+        domain: src.domain,
+        version: src.version,
+        policy_hash: String::new(),
+        trust_overrides,
+        freeze_triggers,
+        audit,
+        kill_switch,
+        allowed_capabilities,
+        minimum_trust_tier: src.minimum_trust_tier,
+        allowed_effects: src
+            .allowed_effects
+            .map(|effects| effects.into_iter().map(|EffectSource(e)| e).collect()),
+        checkpoint_before,
+        approval_timeout,
+        deny,
+    })
+}
+
+fn quorum(src: QuorumSource, text: &str) -> Result<Quorum, PolicyCompileError> {
+    if src.count == 0 {
+        return Err(PolicyCompileError::new(PolicyErrorKind::InvalidQuorum)
+            .at(locate_key(text, "require_approvals")));
+    }
+    let mut roles: Vec<String> = Vec::new();
+    for role in src.roles {
+        if role.trim().is_empty() {
+            return Err(PolicyCompileError::new(PolicyErrorKind::EmptyField("roles"))
+                .at(locate_key(text, "require_approvals")));
+        }
+        if roles.contains(&role) {
+            return Err(
+                PolicyCompileError::new(PolicyErrorKind::Duplicate(role.clone()))
+                    .at(locate_last(text, &role)),
+            );
+        }
+        roles.push(role);
+    }
+    Ok(Quorum {
+        count: src.count,
+        roles,
+    })
+}
+
+fn approval_timeout(
+    src: ApprovalTimeoutSource,
+    text: &str,
+) -> Result<ApprovalTimeout, PolicyCompileError> {
+    let after = parse_duration(&src.after).map_err(|e| e.at(locate_key(text, "after")))?;
+    let action = match (src.on_timeout, src.escalate_to) {
+        (TimeoutActionSource::Escalate, Some(role)) if !role.trim().is_empty() => {
+            TimeoutAction::Escalate { role }
+        }
+        (TimeoutActionSource::Escalate, Some(_)) => {
+            return Err(PolicyCompileError::new(PolicyErrorKind::EmptyField("escalate_to"))
+                .at(locate_key(text, "escalate_to")))
+        }
+        (TimeoutActionSource::Escalate, None) => {
+            return Err(PolicyCompileError::new(PolicyErrorKind::MissingEscalationRole)
+                .at(locate_key(text, "on_timeout")))
+        }
+        (_, Some(_)) => {
+            return Err(PolicyCompileError::new(PolicyErrorKind::Syntax(
+                "`escalate_to` is only valid with `on_timeout: escalate`".to_string(),
+            ))
+            .at(locate_key(text, "escalate_to")))
+        }
+        (TimeoutActionSource::Reject, None) => TimeoutAction::Reject,
+        (TimeoutActionSource::SoftStop, None) => TimeoutAction::SoftStop,
+    };
+    Ok(ApprovalTimeout { after, action })
+}
+
+fn kill_switch_channel(
+    channel: ChannelSource,
+    text: &str,
+) -> Result<KillSwitchChannel, PolicyCompileError> {
+    let keyed = match channel {
+        ChannelSource::Native(c) => return Ok(c),
+        ChannelSource::Keyed(k) => k,
+    };
+    let mut found = Vec::new();
+    if let Some(gpio) = keyed.hardware_gpio {
+        found.push(KillSwitchChannel::HardwareGpio { pin: gpio.pin });
+    }
+    if let Some(pipe) = keyed.local_named_pipe {
+        if pipe.path.trim().is_empty() {
+            return Err(PolicyCompileError::new(PolicyErrorKind::EmptyField("path"))
+                .at(locate_key(text, "local_named_pipe")));
+        }
+        found.push(KillSwitchChannel::LocalNamedPipe { path: pipe.path });
+    }
+    if let Some(http) = keyed.local_http {
+        found.push(KillSwitchChannel::LocalHttp { port: http.port });
+    }
+    if found.len() != 1 {
+        return Err(PolicyCompileError::new(PolicyErrorKind::Syntax(
+            "each kill switch channel entry must name exactly one channel".to_string(),
+        ))
+        .at(locate_key(text, "channels")));
+    }
+    Ok(found.remove(0))
+}
+
+fn match_rule(rule: MatchRuleSource, text: &str) -> Result<MatchRule, PolicyCompileError> {
+    let capability = match rule.capability {
+        Some(cap) => Some(capability_pattern(cap, text)?),
+        None => None,
+    };
+    Ok(MatchRule {
+        effect_prefix: rule.effect_prefix,
+        node_type: rule.node_type,
+        exec_class: rule.exec_class,
+        capability,
+    })
+}
+
+/// Match rules may scope a capability (`fs:/data/*`); only the class has
+/// to be known.
+fn capability_pattern(pattern: String, text: &str) -> Result<Capability, PolicyCompileError> {
+    let cap = Capability(pattern);
+    if capability_class(&cap).is_none() {
+        let line = locate_value(text, &cap.0);
+        return Err(PolicyCompileError::new(PolicyErrorKind::UnknownCapability(cap.0)).at(line));
+    }
+    if cap.0.ends_with(':') {
+        return Err(PolicyCompileError::new(PolicyErrorKind::EmptyField("capability"))
+            .at(locate_value(text, &cap.0)));
+    }
+    Ok(cap)
+}
+
+fn known_capability(name: String, text: &str) -> Result<Capability, PolicyCompileError> {
+    if capability_name_to_mask(&name).is_none() {
+        let line = locate_value(text, &name);
+        return Err(PolicyCompileError::new(PolicyErrorKind::UnknownCapability(name)).at(line));
+    }
+    Ok(Capability(name))
+}
+
+/// Parses `<n>ms`, `<n>s`, `<n>m` or `<n>h`. Zero is rejected: a zero
+/// watchdog or anchor interval is never what an operator meant.
+fn parse_duration(value: &str) -> Result<Duration, PolicyCompileError> {
+    let invalid = || PolicyCompileError::new(PolicyErrorKind::InvalidDuration(value.to_string()));
+    let v = value.trim();
+    let split = v.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (digits, unit) = v.split_at(split);
+    let n: u64 = digits.parse().map_err(|_| invalid())?;
+    if n == 0 {
+        return Err(invalid());
+    }
+    match unit {
+        "ms" => Ok(Duration::from_millis(n)),
+        "s" => Ok(Duration::from_secs(n)),
+        "m" => n
+            .checked_mul(60)
+            .map(Duration::from_secs)
+            .ok_or_else(invalid),
+        "h" => n
+            .checked_mul(3600)
+            .map(Duration::from_secs)
+            .ok_or_else(invalid),
+        _ => Err(invalid()),
+    }
+}
+
+/// snake_case name of a unit enum variant, as written in policy.yaml.
+fn enum_name<T: serde::Serialize>(value: &T) -> String {
+    serde_yaml::to_string(value)
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// serde_yaml appends " at line X column Y" to its messages; the location
+/// is already carried in the structured fields.
+fn strip_location(msg: &str) -> String {
+    match msg.find(" at line ") {
+        Some(idx) => msg[..idx].to_string(),
+        None => msg.to_string(),
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Source location helpers
+// Pass 2 works on deserialized values, so positions are recovered by
+// scanning the source text. Good enough for a flat, hand-written file.
+// -----------------------------------------------------------------------------
+
+fn locate_key(text: &str, key: &str) -> Option<usize> {
+    let pattern = format!("{}:", key);
+    text.lines()
+        .position(|l| {
+            l.trim_start()
+                .trim_start_matches("- ")
+                .starts_with(&pattern)
+        })
+        .map(|i| i + 1)
+}
+
+fn locate_value(text: &str, value: &str) -> Option<usize> {
+    text.lines()
+        .position(|l| contains_token(l, value))
+        .map(|i| i + 1)
+}
+
+fn locate_last(text: &str, value: &str) -> Option<usize> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, l)| contains_token(l, value))
+        .last()
+        .map(|(i, _)| i + 1)
+}
+
+fn contains_token(line: &str, token: &str) -> bool {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    line.match_indices(token).any(|(idx, _)| {
+        let before = line[..idx].chars().next_back();
+        let after = line[idx + token.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
@@ -18,12 +18,13 @@
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
-use adr_core::{Effect, RuntimeState};
+use adr_core::{CapabilityScope, Compensations, Effect, HumanGate, RuntimeState};
+use adr_core::capability::CapabilitySet;
 use adr_core::capability_name_to_mask;
 use crate::policy::CompiledPolicy;
 use crate::types::{
-    ExecClass, ExecutionPlan, IntentNode, NodeId, ResolverResult, SafetyRule, SafetyViolation,
-    Severity,
+    Capability, ExecClass, ExecutionPlan, IntentNode, NodeId, NodeType, RejectedPlan, RejectionReason,
+    ResolverResult, SafetyRule, SafetyViolation, Severity, StopHandlers, TrustTier,
 };
 use crate::policy_engine::PolicyEngine; 
 
@@ -44,12 +45,37 @@ pub struct RuntimeContext {
     /// Scheduler class active in the current execution context
     pub scheduler_class: ExecClass,
 	
-	/// Snapshot of runtime capability masks at resolve time.
+	/// Snapshot of runtime capability masks at resolve time: the
+	/// classes granted without a scope (`CapabilitySet::unscoped_mask`).
 	/// The executor re-enforces capability checks independently.
-	/// These masks may diverge if capabilities are revoked between
-	/// resolve and execute.
+	/// These masks may diverge if capabilities change between resolve
+	/// and execute; the plan's `cap_scope_hash` binds them so the
+	/// runtime refuses a plan resolved in a different scope.
 	pub active_capability_masks: Vec<u64>,
 
+	/// Scoped grants at resolve time (`CapabilitySet::scopes`), bound
+	/// into `cap_scope_hash` together with the masks.
+	pub active_capability_scopes: Vec<CapabilityScope>,
+
+	/// Operator trust tier overrides by node, applied on top of policy.
+	/// Refused where a `downgrade_forbidden` or `immutable` override pins
+	/// the tier.
+	pub trust_tier_overrides: HashMap<NodeId, TrustTier>,
+}
+
+impl RuntimeContext {
+	/// The grants of this snapshot as a Layer 1 `CapabilitySet`, for
+	/// computing the `cap_scope_hash` the runtime will recompute.
+	pub fn capability_set(&self) -> CapabilitySet {
+		let caps = CapabilitySet::new();
+		for mask in &self.active_capability_masks {
+			caps.allow_mask(*mask);
+		}
+		for scope in &self.active_capability_scopes {
+			caps.allow_scoped(scope.clone());
+		}
+		caps
+	}
 }
 
 /// Snapshot of the runtime state – mirrored from Layer 1.
@@ -78,6 +104,57 @@ pub struct AdrNodeMeta {
     pub id: NodeId,
     pub effect: Effect,
     pub dependencies: Vec<NodeId>,
+    pub node_type: NodeType,
+    pub exec_class: ExecClass,
+    /// Capabilities the node uses, e.g. "fs:/data/out/emails.json".
+    pub capabilities: Vec<Capability>,
+    /// The resolver carries `on_soft_stop` into the plan's compensations.
+    pub stop_handlers: StopHandlers,
+}
+
+/// Compensating actions for `ExecutionPlan::compensations`, taken from
+/// each node's `on_soft_stop` handler. Nodes without one stay
+/// committed when a Soft Stop aborts the plan.
+pub fn soft_stop_compensations<'a>(
+    handlers: impl IntoIterator<Item = (NodeId, &'a StopHandlers)>,
+) -> Compensations {
+    handlers
+        .into_iter()
+        .filter_map(|(id, h)| h.on_soft_stop.clone().map(|action| (id, action)))
+        .collect()
+}
+
+/// True if the plan must pause before `node`: it is a checkpoint node or
+/// its effect is listed in the policy's `checkpoint_before`.
+fn needs_checkpoint(node: &AdrNodeMeta, policy: &CompiledPolicy) -> bool {
+    node.node_type == NodeType::Checkpoint || policy.checkpoint_before.contains(&node.effect)
+}
+
+/// Checks that `plan` keeps every checkpoint `policy` demands: each
+/// `NodeType::Checkpoint` node and each node whose effect is listed in
+/// `checkpoint_before` must be in `plan.checkpoints`. Plans not built by
+/// the resolver must pass this before they are handed to Layer 1, which
+/// only knows the effects (`AdrRuntime::with_checkpoint_before`).
+pub fn verify_checkpoints(
+    plan: &ExecutionPlan,
+    graph: &AdrGraph,
+    policy: &CompiledPolicy,
+) -> Vec<SafetyViolation> {
+    plan.nodes
+        .iter()
+        .filter(|id| !plan.checkpoints.contains(id))
+        .filter(|id| {
+            graph
+                .nodes
+                .iter()
+                .any(|n| n.id == **id && needs_checkpoint(n, policy))
+        })
+        .map(|id| SafetyViolation {
+            node_id: *id,
+            rule: SafetyRule::CheckpointBypassed,
+            severity: Severity::Critical,
+        })
+        .collect()
 }
 
 // -----------------------------------------------------------------------------
@@ -88,6 +165,8 @@ pub struct AdrNodeMeta {
 /// Full Graph-IR types live in adr-core (Layer 1).
 /// This stub will be replaced by a proper reference in Phase 8.
 pub struct AdrGraph {
+    /// Same as the Layer 1 `GraphHeader::graph_version`.
+    pub graph_version: String,
     pub nodes: Vec<AdrNodeMeta>,
 }
 
@@ -222,7 +301,8 @@ impl IntentResolver for RuleBasedResolver {
 				};
 			};
 
-			if !context.active_capability_masks.contains(&mask) {
+			let scoped = context.active_capability_scopes.iter().any(|s| s.class() == mask);
+			if !context.active_capability_masks.contains(&mask) && !scoped {
 				return ResolverResult {
 					plan: None,
 					confidence_semantic: 0.0,
@@ -289,6 +369,19 @@ impl IntentResolver for RuleBasedResolver {
 					});
 					return None;
 				}
+				if _policy.denies(
+					Some(node.effect.as_str()),
+					Some(&node.node_type),
+					Some(&node.exec_class),
+					&node.capabilities,
+				) {
+					policy_violations.push(SafetyViolation {
+						node_id: node.id,
+						rule: SafetyRule::PolicyConstraintViolated("node_denied_by_policy".to_string()),
+						severity: Severity::Error,
+					});
+					return None;
+				}
 
 				Some(node.clone())
 			})
@@ -398,10 +491,108 @@ impl IntentResolver for RuleBasedResolver {
 			};
 		}
 
+		// Checkpoints follow plan order so the executor meets them in turn.
+		let checkpoints = allowed_ids
+			.iter()
+			.filter(|id| {
+				allowed_nodes
+					.iter()
+					.any(|n| n.id == **id && needs_checkpoint(n, _policy))
+			})
+			.copied()
+			.collect();
+
+		// Each node runs under the intent's tier raised by policy, then by
+		// the operator. Anything above ai_autonomous needs a human sign-off;
+		// a policy quorum gates the node regardless of tier.
+		let mut human_gates: Vec<HumanGate> = Vec::new();
+		let mut rejected_plans = Vec::new();
+		for id in &allowed_ids {
+			let Some(node) = allowed_nodes.iter().find(|n| n.id == *id) else {
+				continue;
+			};
+			let effect = Some(node.effect.as_str());
+			let node_type = Some(&node.node_type);
+			let exec_class = Some(&node.exec_class);
+			let capabilities = node.capabilities.as_slice();
+
+			let mut tier =
+				_policy.effective_trust_tier(&intent.trust_tier, effect, node_type, exec_class, capabilities);
+			let mut actual = intent.trust_tier.clone();
+			if let Some(requested) = context.trust_tier_overrides.get(id) {
+				if _policy
+					.tier_lock(effect, node_type, exec_class, capabilities)
+					.permits(&tier, requested)
+				{
+					tier = requested.clone();
+				} else {
+					// The policy tier stands; the attempt itself is unsafe.
+					actual = requested.clone();
+					policy_violations.push(SafetyViolation {
+						node_id: *id,
+						rule: SafetyRule::TrustTierInsufficient,
+						severity: Severity::Critical,
+					});
+				}
+			}
+			if tier > actual {
+				rejected_plans.push(RejectedPlan {
+					nodes: vec![*id],
+					reason: RejectionReason::TrustTierInsufficient {
+						node: *id,
+						required: tier.clone(),
+						actual,
+					},
+				});
+			}
+
+			let gate = match _policy.required_approvals(effect, node_type, exec_class, capabilities) {
+				Some(quorum) => Some(HumanGate { node: *id, quorum }),
+				None if tier > TrustTier::AiAutonomous => Some(HumanGate::new(*id)),
+				None => None,
+			};
+			if let Some(gate) = gate {
+				// realtime_safe nodes must never block; keep the gate so the
+				// node still waits if the plan is run regardless.
+				if node.exec_class == ExecClass::RealtimeSafe {
+					policy_violations.push(SafetyViolation {
+						node_id: *id,
+						rule: SafetyRule::RealtimeSafeBlockingForbidden,
+						severity: Severity::Error,
+					});
+				}
+				human_gates.push(gate);
+			}
+		}
+		let open_human_gates = human_gates.iter().map(|g| g.node).collect();
+		let compensations = soft_stop_compensations(
+			allowed_nodes
+				.iter()
+				.filter(|n| allowed_ids.contains(&n.id))
+				.map(|n| (n.id, &n.stop_handlers)),
+		);
+
 		let plan = ExecutionPlan {
 			nodes: allowed_ids,
 			parallel: parallel_groups,
-			checkpoints: vec![],
+			checkpoints,
+			human_gates,
+			// Every intent capability was checked against the context above.
+			capabilities: intent
+				.capabilities
+				.iter()
+				.filter_map(|cap| capability_name_to_mask(&cap.0))
+				.collect(),
+			// Binds the plan to the scope it was resolved in; Layer 1
+			// refuses it once capabilities, policy or graph change.
+			cap_scope_hash: Some(adr_core::cap_scope_hash(
+				&context.capability_set(),
+				&_policy.policy_hash,
+				&graph.graph_version,
+			)),
+			compensations,
+			// Effect input comes from the caller, not from the intent.
+			payloads: Default::default(),
 		};
 
 
@@ -412,8 +603,8 @@ impl IntentResolver for RuleBasedResolver {
 			plan: Some(plan),
 			confidence_semantic: 1.0,
 			confidence_safety: if policy_violations.is_empty() { 1.0 } else { 0.0 },
-			open_human_gates: vec![],
-			rejected_plans: vec![],
+			open_human_gates,
+			rejected_plans,
 			safety_violations: policy_violations,
 		}
 
@@ -441,6 +632,8 @@ mod tests {
             runtime_state: state,
             scheduler_class: ExecClass::Orchestrated,
 			active_capability_masks: vec![],
+			active_capability_scopes: vec![],
+			trust_tier_overrides: HashMap::new(),
         }
     }
 
@@ -482,6 +675,9 @@ mod tests {
 			allowed_capabilities: vec![],
 			minimum_trust_tier: None,
 			allowed_effects: None,
+			checkpoint_before: vec![],
+			approval_timeout: None,
+			deny: vec![],
         }
     }
 		
@@ -490,6 +686,7 @@ mod tests {
         let resolver = RuleBasedResolver;
         let intent = make_intent();
 		let graph = AdrGraph {			
+			graph_version: "0.1".to_string(),
 			nodes: vec![],
 		};
         let policy = stub_policy();
@@ -505,6 +702,7 @@ mod tests {
         let resolver = RuleBasedResolver;
         let intent = make_intent();
 		let graph = AdrGraph {			
+			graph_version: "0.1".to_string(),
 			nodes: vec![],
 		};
         let policy = stub_policy();
@@ -523,9 +721,10 @@ mod tests {
         let id1 = Uuid::new_v4();
         let id2 = Uuid::new_v4();
 		let graph = AdrGraph {			
+			graph_version: "0.1".to_string(),
 			nodes: vec![
-				AdrNodeMeta { id: id1, effect: Effect::None, dependencies: vec![],},
-				AdrNodeMeta { id: id2, effect: Effect::None, dependencies: vec![],},
+				AdrNodeMeta { id: id1, effect: Effect::None, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+				AdrNodeMeta { id: id2, effect: Effect::None, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
 			],
 		};
 
@@ -546,11 +745,16 @@ mod tests {
 
 		let id1 = Uuid::new_v4();
 		let graph = AdrGraph {			
+			graph_version: "0.1".to_string(),
 			nodes: vec![
 				AdrNodeMeta {
 					id: id1,
 					effect: Effect::FsWrite,
 					dependencies: vec![],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 			],
 		};
@@ -562,6 +766,9 @@ mod tests {
 			allowed_capabilities: vec![],
 			minimum_trust_tier: None,
 			allowed_effects: Some(vec![Effect::None]),
+			checkpoint_before: vec![],
+			approval_timeout: None,
+			deny: vec![],
 			trust_overrides: vec![],
 			freeze_triggers: vec![],
 			audit: AuditConfig {
@@ -608,16 +815,25 @@ mod tests {
 		let id2 = Uuid::new_v4();
 
 		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
 			nodes: vec![
 				AdrNodeMeta {
 					id: id1,
 					effect: Effect::None,
 					dependencies: vec![],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 				AdrNodeMeta {
 					id: id2,
 					effect: Effect::FsWrite,
 					dependencies: vec![],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 			],
 		};
@@ -629,6 +845,9 @@ mod tests {
 			allowed_capabilities: vec![],
 			minimum_trust_tier: None,
 			allowed_effects: Some(vec![Effect::None]),
+			checkpoint_before: vec![],
+			approval_timeout: None,
+			deny: vec![],
 			trust_overrides: vec![],
 			freeze_triggers: vec![],
 			audit: AuditConfig {
@@ -678,11 +897,16 @@ mod tests {
 
 		let id1 = Uuid::new_v4();
 		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
 			nodes: vec![
 				AdrNodeMeta {
 					id: id1,
 					effect: Effect::None,
 					dependencies: vec![],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 			],
 		};
@@ -716,11 +940,16 @@ mod tests {
 
 		let id1 = Uuid::new_v4();
 		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
 			nodes: vec![
 				AdrNodeMeta {
 					id: id1,
 					effect: Effect::None,
 					dependencies: vec![],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 			],
 		};
@@ -732,6 +961,8 @@ mod tests {
 			scheduler_class: ExecClass::Orchestrated,
 			active_capabilities: vec![],
 			active_capability_masks: vec![], // <-- fs_write fehlt hier
+			active_capability_scopes: vec![],
+			trust_tier_overrides: HashMap::new(),
 		};
 
 		let result = resolver.resolve(&intent, &graph, &policy, &context);
@@ -757,16 +988,25 @@ mod tests {
 
 		// Dependent node comes first, but should still be planned after its dependency.
 		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
 			nodes: vec![
 				AdrNodeMeta {
 					id: id2,
 					effect: Effect::None,
 					dependencies: vec![id1],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 				AdrNodeMeta {
 					id: id1,
 					effect: Effect::None,
 					dependencies: vec![],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 			],
 		};
@@ -792,21 +1032,34 @@ mod tests {
 		let id3 = Uuid::new_v4();
 
 		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
 			nodes: vec![
 				AdrNodeMeta {
 					id: id1,
 					effect: Effect::None,
 					dependencies: vec![],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 				AdrNodeMeta {
 					id: id2,
 					effect: Effect::None,
 					dependencies: vec![],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 				AdrNodeMeta {
 					id: id3,
 					effect: Effect::None,
 					dependencies: vec![id1, id2],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 			],
 		};
@@ -834,11 +1087,16 @@ mod tests {
 		let id1 = Uuid::new_v4();
 
 		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
 			nodes: vec![
 				AdrNodeMeta {
 					id: id1,
 					effect: Effect::None,
 					dependencies: vec![missing_id],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 			],
 		};
@@ -866,16 +1124,25 @@ mod tests {
 
 		let duplicate_id = Uuid::new_v4();
 		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
 			nodes: vec![
 				AdrNodeMeta {
 					id: duplicate_id,
 					effect: Effect::None,
 					dependencies: vec![],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 				AdrNodeMeta {
 					id: duplicate_id,
 					effect: Effect::None,
 					dependencies: vec![],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 			],
 		};
@@ -905,16 +1172,25 @@ mod tests {
 		let id2 = Uuid::new_v4();
 
 		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
 			nodes: vec![
 				AdrNodeMeta {
 					id: id1,
 					effect: Effect::None,
 					dependencies: vec![missing_id],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 				AdrNodeMeta {
 					id: id2,
 					effect: Effect::None,
 					dependencies: vec![id1],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 			],
 		};
@@ -946,16 +1222,25 @@ mod tests {
 		let id2 = Uuid::new_v4();
 
 		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
 			nodes: vec![
 				AdrNodeMeta {
 					id: id1,
 					effect: Effect::None,
 					dependencies: vec![id2],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 				AdrNodeMeta {
 					id: id2,
 					effect: Effect::None,
 					dependencies: vec![id1],
+					node_type: NodeType::Step,
+					exec_class: ExecClass::Orchestrated,
+					capabilities: vec![],
+					stop_handlers: StopHandlers::default(),
 				},
 			],
 		};
@@ -977,7 +1262,288 @@ mod tests {
 			}
 		}
 	}
-	
-	
-}
 
+	#[test]
+	fn soft_stop_compensations_use_on_soft_stop_only() {
+		let undo = StopHandlers {
+			on_soft_stop: Some("delete_output".to_string()),
+			on_hard_stop: Some("ignored".to_string()),
+			on_freeze: None,
+		};
+		let none = StopHandlers::default();
+		let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+
+		let compensations = soft_stop_compensations([(a, &undo), (b, &none)]);
+
+		assert_eq!(compensations.len(), 1);
+		assert_eq!(compensations.get(&a), Some("delete_output"));
+		assert_eq!(compensations.get(&b), None);
+	}
+
+	#[test]
+	fn resolver_carries_on_soft_stop_into_the_plan() {
+		let (id1, id2) = (Uuid::new_v4(), Uuid::new_v4());
+		let undo = StopHandlers {
+			on_soft_stop: Some("delete_output".to_string()),
+			..StopHandlers::default()
+		};
+		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
+			nodes: vec![
+				AdrNodeMeta { id: id1, effect: Effect::FsWrite, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: undo },
+				AdrNodeMeta { id: id2, effect: Effect::None, dependencies: vec![id1], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+			],
+		};
+
+		let result = RuleBasedResolver.resolve(&make_intent(), &graph, &stub_policy(), &make_context(RuntimeStateSnapshot::Running));
+		let plan = result.plan.unwrap();
+
+		assert_eq!(plan.compensations.len(), 1);
+		assert_eq!(plan.compensations.get(&id1), Some("delete_output"));
+	}
+
+	#[test]
+	fn resolver_places_checkpoints_before_policy_effects() {
+		let resolver = RuleBasedResolver;
+		let intent = make_intent();
+		let (id1, id2) = (Uuid::new_v4(), Uuid::new_v4());
+		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
+			nodes: vec![
+				AdrNodeMeta { id: id1, effect: Effect::None, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+				AdrNodeMeta { id: id2, effect: Effect::FsWrite, dependencies: vec![id1], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+			],
+		};
+		let policy = CompiledPolicy {
+			checkpoint_before: vec![Effect::FsWrite],
+			..stub_policy()
+		};
+
+		let result = resolver.resolve(&intent, &graph, &policy, &make_context(RuntimeStateSnapshot::Running));
+		let plan = result.plan.unwrap();
+
+		assert_eq!(plan.checkpoints, vec![id2]);
+		assert!(verify_checkpoints(&plan, &graph, &policy).is_empty());
+	}
+
+	#[test]
+	fn checkpoint_nodes_are_checkpoints_without_policy() {
+		let (id1, id2) = (Uuid::new_v4(), Uuid::new_v4());
+		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
+			nodes: vec![
+				AdrNodeMeta { id: id1, effect: Effect::None, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+				AdrNodeMeta { id: id2, effect: Effect::None, dependencies: vec![id1], node_type: NodeType::Checkpoint, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+			],
+		};
+		let policy = stub_policy();
+
+		let result = RuleBasedResolver.resolve(&make_intent(), &graph, &policy, &make_context(RuntimeStateSnapshot::Running));
+		let mut plan = result.plan.unwrap();
+		assert_eq!(plan.checkpoints, vec![id2]);
+
+		plan.checkpoints.clear();
+		let violations = verify_checkpoints(&plan, &graph, &policy);
+		assert_eq!(violations.len(), 1);
+		assert_eq!(violations[0].node_id, id2);
+	}
+
+	#[test]
+	fn plan_without_required_checkpoint_is_flagged() {
+		let id1 = Uuid::new_v4();
+		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
+			nodes: vec![AdrNodeMeta { id: id1, effect: Effect::FsWrite, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() }],
+		};
+		let policy = CompiledPolicy {
+			checkpoint_before: vec![Effect::FsWrite],
+			..stub_policy()
+		};
+		let plan = ExecutionPlan { nodes: vec![id1], parallel: vec![vec![id1]], checkpoints: vec![], human_gates: vec![], capabilities: vec![], cap_scope_hash: None, compensations: Compensations::new(), payloads: Default::default() };
+
+		let violations = verify_checkpoints(&plan, &graph, &policy);
+
+		assert_eq!(violations.len(), 1);
+		assert_eq!(violations[0].node_id, id1);
+		assert!(matches!(violations[0].rule, SafetyRule::CheckpointBypassed));
+		assert!(matches!(violations[0].severity, Severity::Critical));
+	}
+
+	#[test]
+	fn resolver_opens_human_gates_for_ai_proposed_intent() {
+		let resolver = RuleBasedResolver;
+		let (id1, id2) = (Uuid::new_v4(), Uuid::new_v4());
+		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
+			nodes: vec![
+				AdrNodeMeta { id: id1, effect: Effect::None, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+				AdrNodeMeta { id: id2, effect: Effect::None, dependencies: vec![id1], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+			],
+		};
+		let context = make_context(RuntimeStateSnapshot::Running);
+
+		let autonomous = resolver.resolve(&make_intent(), &graph, &stub_policy(), &context);
+		assert!(autonomous.open_human_gates.is_empty());
+		assert!(autonomous.plan.unwrap().human_gates.is_empty());
+
+		let intent = IntentNode { trust_tier: TrustTier::AiProposed, ..make_intent() };
+		let result = resolver.resolve(&intent, &graph, &stub_policy(), &context);
+		assert_eq!(result.open_human_gates, vec![id1, id2]);
+		assert_eq!(result.plan.unwrap().human_gates, vec![HumanGate::new(id1), HumanGate::new(id2)]);
+	}
+
+	#[test]
+	fn resolver_gates_nodes_with_policy_quorum() {
+		let resolver = RuleBasedResolver;
+		let (id1, id2) = (Uuid::new_v4(), Uuid::new_v4());
+		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
+			nodes: vec![
+				AdrNodeMeta { id: id1, effect: Effect::None, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+				AdrNodeMeta { id: id2, effect: Effect::NetExternal, dependencies: vec![id1], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+			],
+		};
+		let quorum = adr_core::Quorum { count: 2, roles: vec!["operator".to_string(), "safety_officer".to_string()] };
+		let policy = CompiledPolicy {
+			trust_overrides: vec![crate::policy::TrustOverride {
+				match_rule: crate::policy::MatchRule {
+					effect_prefix: Some("net_external".to_string()),
+					node_type: None,
+					exec_class: None,
+					capability: None,
+				},
+				set_tier: TrustTier::HumanRequired,
+				downgrade_forbidden: false,
+				immutable: false,
+				require_approvals: Some(quorum.clone()),
+			}],
+			..stub_policy()
+		};
+
+		let result = resolver.resolve(&make_intent(), &graph, &policy, &make_context(RuntimeStateSnapshot::Running));
+
+		assert_eq!(result.open_human_gates, vec![id2]);
+		assert_eq!(result.plan.unwrap().human_gates, vec![HumanGate { node: id2, quorum }]);
+	}
+
+	fn fs_write_override(downgrade_forbidden: bool) -> CompiledPolicy {
+		CompiledPolicy {
+			trust_overrides: vec![crate::policy::TrustOverride {
+				match_rule: crate::policy::MatchRule {
+					effect_prefix: Some("fs_write".to_string()),
+					node_type: None,
+					exec_class: None,
+					capability: None,
+				},
+				set_tier: TrustTier::HumanRequired,
+				downgrade_forbidden,
+				immutable: false,
+				require_approvals: None,
+			}],
+			..stub_policy()
+		}
+	}
+
+	#[test]
+	fn resolver_raises_tier_per_node_from_trust_overrides() {
+		let resolver = RuleBasedResolver;
+		let (id1, id2) = (Uuid::new_v4(), Uuid::new_v4());
+		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
+			nodes: vec![
+				AdrNodeMeta { id: id1, effect: Effect::None, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+				AdrNodeMeta { id: id2, effect: Effect::FsWrite, dependencies: vec![id1], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+			],
+		};
+
+		let result = resolver.resolve(&make_intent(), &graph, &fs_write_override(false), &make_context(RuntimeStateSnapshot::Running));
+
+		assert_eq!(result.confidence_safety, 1.0);
+		assert_eq!(result.open_human_gates, vec![id2]);
+		assert_eq!(result.rejected_plans.len(), 1);
+		assert!(matches!(
+			&result.rejected_plans[0].reason,
+			RejectionReason::TrustTierInsufficient { node, required: TrustTier::HumanRequired, actual: TrustTier::AiAutonomous }
+				if *node == id2
+		));
+	}
+
+	#[test]
+	fn resolver_applies_operator_override_unless_downgrade_forbidden() {
+		let resolver = RuleBasedResolver;
+		let id1 = Uuid::new_v4();
+		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
+			nodes: vec![
+				AdrNodeMeta { id: id1, effect: Effect::FsWrite, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+			],
+		};
+		let mut context = make_context(RuntimeStateSnapshot::Running);
+		context.trust_tier_overrides.insert(id1, TrustTier::AiAutonomous);
+
+		let lowered = resolver.resolve(&make_intent(), &graph, &fs_write_override(false), &context);
+		assert_eq!(lowered.confidence_safety, 1.0);
+		assert!(lowered.open_human_gates.is_empty());
+
+		let refused = resolver.resolve(&make_intent(), &graph, &fs_write_override(true), &context);
+		assert_eq!(refused.confidence_safety, 0.0);
+		assert_eq!(refused.open_human_gates, vec![id1]);
+		assert!(matches!(
+			refused.safety_violations[0].rule,
+			SafetyRule::TrustTierInsufficient
+		));
+	}
+
+	#[test]
+	fn resolver_pins_checkpoint_nodes_and_forbids_gating_realtime_safe() {
+		let resolver = RuleBasedResolver;
+		let (id1, id2) = (Uuid::new_v4(), Uuid::new_v4());
+		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
+			nodes: vec![
+				AdrNodeMeta { id: id1, effect: Effect::None, dependencies: vec![], node_type: NodeType::Checkpoint, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+				AdrNodeMeta { id: id2, effect: Effect::None, dependencies: vec![], node_type: NodeType::Checkpoint, exec_class: ExecClass::RealtimeSafe, capabilities: vec![], stop_handlers: StopHandlers::default() },
+			],
+		};
+		let mut context = make_context(RuntimeStateSnapshot::Running);
+		context.trust_tier_overrides.insert(id1, TrustTier::AiProposed);
+
+		let result = resolver.resolve(&make_intent(), &graph, &stub_policy(), &context);
+
+		assert_eq!(result.confidence_safety, 0.0);
+		assert_eq!(result.open_human_gates, vec![id1, id2]);
+		let rules: Vec<_> = result.safety_violations.iter().map(|v| (v.node_id, format!("{:?}", v.rule))).collect();
+		assert_eq!(rules, vec![
+			(id1, "TrustTierInsufficient".to_string()),
+			(id2, "RealtimeSafeBlockingForbidden".to_string()),
+		]);
+	}
+
+	#[test]
+	fn resolver_drops_nodes_denied_by_capability_scope() {
+		let resolver = RuleBasedResolver;
+		let (id1, id2) = (Uuid::new_v4(), Uuid::new_v4());
+		let graph = AdrGraph {
+			graph_version: "0.1".to_string(),
+			nodes: vec![
+				AdrNodeMeta { id: id1, effect: Effect::FsWrite, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![Capability::new("fs:/data/out/a.json")], stop_handlers: StopHandlers::default() },
+				AdrNodeMeta { id: id2, effect: Effect::FsWrite, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![Capability::new("fs:/etc/passwd")], stop_handlers: StopHandlers::default() },
+			],
+		};
+		let policy = CompiledPolicy {
+			deny: vec![crate::policy::MatchRule {
+				effect_prefix: None,
+				node_type: None,
+				exec_class: None,
+				capability: Some(Capability::new("fs:/etc/*")),
+			}],
+			..stub_policy()
+		};
+
+		let result = resolver.resolve(&make_intent(), &graph, &policy, &make_context(RuntimeStateSnapshot::Running));
+
+		assert_eq!(result.confidence_safety, 0.0);
+		assert_eq!(result.plan.unwrap().nodes, vec![id1]);
+		assert_eq!(result.safety_violations[0].node_id, id2);
+	}
+}
@@ -1,4 +1,6 @@
-use adr_core::{AdrRuntime, RuntimeState};
+use std::collections::HashMap;
+
+use adr_core::{AdrRuntime, CapabilityScope, RuntimeState};
 use adr_core::killswitch::{KillSwitchChannel, StopSignal};
 
 use adr_layer2::IntentResolver;
@@ -6,7 +8,7 @@ use adr_layer2::resolver::{AdrGraph, RuleBasedResolver, RuntimeContext, RuntimeS
 use adr_layer2::policy::{
     AuditConfig, CompiledPolicy, KillSwitchConfig, LogLevel, MerkleRootHolder, TimeSource,
 };
-use adr_layer2::types::{ExecClass, IntentNode, TrustTier};
+use adr_layer2::types::{ExecClass, IntentNode, NodeType, StopHandlers, TrustTier};
 
 use uuid::Uuid;
 
@@ -23,6 +25,8 @@ fn make_context(state: RuntimeState) -> RuntimeContext {
         runtime_state: RuntimeStateSnapshot::from(state),
         scheduler_class: ExecClass::Orchestrated,
 		active_capability_masks: vec![],
+		active_capability_scopes: vec![],
+		trust_tier_overrides: HashMap::new(),
     }
 }
 
@@ -59,6 +63,9 @@ fn stub_policy() -> CompiledPolicy {
 		allowed_capabilities: vec![],
 		minimum_trust_tier: None,
 		allowed_effects: None,
+		checkpoint_before: vec![],
+		approval_timeout: None,
+		deny: vec![],
     }
 }
 
@@ -74,9 +81,10 @@ fn e2e_resolve_then_execute_noop() {
     let id1 = Uuid::new_v4();
     let id2 = Uuid::new_v4();
 	let graph = AdrGraph {		
+		graph_version: "0.1".to_string(),
 		nodes: vec![
-			adr_layer2::resolver::AdrNodeMeta { id: id1, effect: adr_core::Effect::None, dependencies: vec![], },
-			adr_layer2::resolver::AdrNodeMeta { id: id2, effect: adr_core::Effect::None, dependencies: vec![], },
+			adr_layer2::resolver::AdrNodeMeta { id: id1, effect: adr_core::Effect::None, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
+			adr_layer2::resolver::AdrNodeMeta { id: id2, effect: adr_core::Effect::None, dependencies: vec![], node_type: NodeType::Step, exec_class: ExecClass::Orchestrated, capabilities: vec![], stop_handlers: StopHandlers::default() },
 		],
 	};
 
@@ -92,4 +100,96 @@ fn e2e_resolve_then_execute_noop() {
 	
     // Execute noop (Layer 1)
     rt.execute_noop().expect("runtime execute ok");
-}
\ No newline at end of file
+}
+/// One unprivileged step, as resolver graph and as Layer 1 graph.
+fn step_graphs(id: Uuid) -> (AdrGraph, adr_core::Graph) {
+    let graph = AdrGraph {
+        graph_version: "0.1".to_string(),
+        nodes: vec![adr_layer2::resolver::AdrNodeMeta {
+            id,
+            effect: adr_core::Effect::None,
+            dependencies: vec![],
+            node_type: NodeType::Step,
+            exec_class: ExecClass::Orchestrated,
+            capabilities: vec![],
+            stop_handlers: StopHandlers::default(),
+        }],
+    };
+    let core_graph = adr_core::Graph {
+        header: adr_core::GraphHeader {
+            graph_version: graph.graph_version.clone(),
+            deterministic_mode: false,
+        },
+        nodes: vec![adr_core::Node {
+            id,
+            label: "step".to_string(),
+            exec_class: adr_core::ExecClass::Orchestrated,
+            effect: adr_core::Effect::None,
+            capabilities: vec![],
+            dependencies: vec![],
+        }],
+    };
+    (graph, core_graph)
+}
+
+#[test]
+fn e2e_plan_is_refused_when_capabilities_change_after_resolve() {
+    let policy = stub_policy();
+    let mut rt = AdrRuntime::new(NoSignal).with_policy_hash(policy.policy_hash.clone());
+    rt.capabilities().allow_mask(adr_core::CAP_FS_WRITE);
+
+    let mut context = make_context(RuntimeState::Running);
+    context.active_capability_masks = vec![rt.capabilities().mask()];
+    let id1 = Uuid::new_v4();
+    let (graph, core_graph) = step_graphs(id1);
+    let plan = RuleBasedResolver
+        .resolve(&make_intent(), &graph, &policy, &context)
+        .plan
+        .unwrap();
+
+    // Granting more between resolve and execute unbinds the plan.
+    rt.capabilities().allow_mask(adr_core::CAP_NET_EXTERNAL);
+    let err = rt.execute_plan(&plan, &core_graph).unwrap_err();
+    assert!(matches!(err, adr_core::AdrRuntimeError::CapScopeMismatch { .. }));
+    assert_eq!(rt.state(), RuntimeState::Running);
+
+    context.active_capability_masks = vec![rt.capabilities().mask()];
+    let plan = RuleBasedResolver
+        .resolve(&make_intent(), &graph, &policy, &context)
+        .plan
+        .unwrap();
+    assert_eq!(rt.execute_plan(&plan, &core_graph).unwrap(), vec![id1]);
+}
+
+#[test]
+fn e2e_plan_is_refused_when_a_scope_widens_after_resolve() {
+    let policy = stub_policy();
+    let mut rt = AdrRuntime::new(NoSignal).with_policy_hash(policy.policy_hash.clone());
+    rt.capabilities()
+        .allow_scoped(CapabilityScope::parse("fs:/data/out").unwrap());
+
+    let mut context = make_context(RuntimeState::Running);
+    context.active_capability_masks = vec![rt.capabilities().unscoped_mask()];
+    context.active_capability_scopes = rt.capabilities().scopes();
+    let id1 = Uuid::new_v4();
+    let (graph, core_graph) = step_graphs(id1);
+    let plan = RuleBasedResolver
+        .resolve(&make_intent(), &graph, &policy, &context)
+        .plan
+        .unwrap();
+
+    // `fs:/data/out` to `fs:/` leaves the granted mask unchanged.
+    let mask = rt.capabilities().mask();
+    rt.capabilities()
+        .allow_scoped(CapabilityScope::parse("fs:/").unwrap());
+    assert_eq!(rt.capabilities().mask(), mask);
+    let err = rt.execute_plan(&plan, &core_graph).unwrap_err();
+    assert!(matches!(err, adr_core::AdrRuntimeError::CapScopeMismatch { .. }));
+
+    context.active_capability_scopes = rt.capabilities().scopes();
+    let plan = RuleBasedResolver
+        .resolve(&make_intent(), &graph, &policy, &context)
+        .plan
+        .unwrap();
+    assert_eq!(rt.execute_plan(&plan, &core_graph).unwrap(), vec![id1]);
+}
@@ -0,0 +1,107 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use adr_core::killswitch::{KillSwitchChannel as _, StopSignal};
+use adr_layer2::policy::{KillSwitchChannel, KillSwitchConfig};
+use adr_layer2::{KillSwitchBuildError, KillSwitchBuilder};
+
+struct Pressed;
+impl adr_core::killswitch::KillSwitchChannel for Pressed {
+    fn poll(&self) -> Option<StopSignal> {
+        Some(StopSignal::HardStop)
+    }
+}
+
+fn config(require_physical_channel: bool, channels: Vec<KillSwitchChannel>) -> KillSwitchConfig {
+    KillSwitchConfig {
+        require_physical_channel,
+        channels,
+        watchdog_timer: None,
+        offline_capable: false,
+    }
+}
+
+#[test]
+fn physical_requirement_without_physical_channel_is_refused() {
+    let cfg = config(true, vec![KillSwitchChannel::LocalHttp { port: 0 }]);
+
+    let err = KillSwitchBuilder::new(&cfg).build().err().unwrap();
+    assert_eq!(err, KillSwitchBuildError::PhysicalChannelRequired);
+}
+
+#[test]
+fn empty_channel_list_is_refused() {
+    let cfg = config(false, vec![]);
+    assert_eq!(
+        KillSwitchBuilder::new(&cfg).build().err(),
+        Some(KillSwitchBuildError::NoChannels)
+    );
+}
+
+#[test]
+fn gpio_without_adapter_is_unsupported() {
+    let cfg = config(true, vec![KillSwitchChannel::HardwareGpio { pin: 17 }]);
+    assert_eq!(
+        KillSwitchBuilder::new(&cfg).build().err(),
+        Some(KillSwitchBuildError::Unsupported("hardware_gpio"))
+    );
+}
+
+#[test]
+fn composite_is_built_from_all_channels() {
+    let cfg = config(
+        true,
+        vec![
+            KillSwitchChannel::LocalHttp { port: 0 },
+            KillSwitchChannel::HardwareGpio { pin: 17 },
+        ],
+    );
+    let seen_pin = Rc::new(Cell::new(0));
+    let pin = Rc::clone(&seen_pin);
+
+    let composite = KillSwitchBuilder::new(&cfg)
+        .gpio(move |p| {
+            pin.set(p);
+            Ok(Box::new(Pressed))
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(composite.len(), 2);
+    assert_eq!(seen_pin.get(), 17);
+    assert_eq!(composite.poll(), Some(StopSignal::HardStop));
+}
+
+#[test]
+fn failing_adapter_is_reported() {
+    let cfg = config(true, vec![KillSwitchChannel::HardwareGpio { pin: 4 }]);
+
+    let err = KillSwitchBuilder::new(&cfg)
+        .gpio(|_| Err(std::io::Error::other("no such pin")))
+        .build()
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        err,
+        KillSwitchBuildError::ChannelFailed {
+            channel: "hardware_gpio",
+            message: "no such pin".to_string()
+        }
+    );
+}
+
+#[test]
+fn watchdog_follows_policy_timer() {
+    use adr_core::ManualClock;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let clock = Arc::new(ManualClock::new());
+    let mut cfg = config(false, vec![]);
+    assert!(cfg.watchdog(clock.clone()).is_none());
+
+    cfg.watchdog_timer = Some(Duration::from_millis(500));
+    let dog = cfg.watchdog(clock).unwrap();
+    assert_eq!(dog.timeout(), Duration::from_millis(500));
+}
@@ -0,0 +1,209 @@
+use adr_layer2::policy::{capability_matches, CompiledPolicy};
+use adr_layer2::policy_compiler::{compile_policy, PolicyErrorKind};
+use adr_layer2::types::{Capability, ExecClass, NodeType, TrustTier};
+
+const BASE: &str = "domain: test\nversion: \"1\"\nkill_switch: {}\naudit: {}\n";
+
+fn policy(rules: &str) -> CompiledPolicy {
+    compile_policy(&format!("{}{}", BASE, rules)).unwrap()
+}
+
+fn caps(names: &[&str]) -> Vec<Capability> {
+    names.iter().map(|n| Capability::new(*n)).collect()
+}
+
+fn tier(policy: &CompiledPolicy, effect: &str, exec_class: ExecClass, used: &[&str]) -> TrustTier {
+    policy.effective_trust_tier(
+        &TrustTier::AiAutonomous,
+        Some(effect),
+        Some(&NodeType::Step),
+        Some(&exec_class),
+        &caps(used),
+    )
+}
+
+#[test]
+fn capability_globs_and_prefixes_match_scopes() {
+    let m = |pattern: &str, used: &str| {
+        capability_matches(&Capability::new(pattern), &Capability::new(used))
+    };
+
+    assert!(m("fs:/data/*", "fs:/data/out/emails.json"));
+    assert!(!m("fs:/data/*", "fs:/etc/passwd"));
+    assert!(m("net:*.example.com", "net:api.example.com"));
+    assert!(!m("net:*.example.com", "net:example.com.evil"));
+    assert!(m("fs:/data/out", "fs:/data/out"));
+    assert!(m("fs:/data/out", "fs:/data/out/x.json"));
+    assert!(!m("fs:/data/out", "fs:/data/output"));
+
+    // Class names and short forms are interchangeable.
+    assert!(m("fs_write", "fs:/anything"));
+    assert!(m("net", "net_external"));
+    assert!(!m("fs:/data/*", "net:data"));
+
+    // An unscoped capability reaches every scope of its class.
+    assert!(m("fs:/data/*", "fs_write"));
+}
+
+#[test]
+fn fs_scopes_are_normalized_before_matching() {
+    let m = |pattern: &str, used: &str| {
+        capability_matches(&Capability::new(pattern), &Capability::new(used))
+    };
+
+    assert!(!m("fs:/data/*", "fs:/data/../etc/shadow"));
+    assert!(m("fs:/etc", "fs:/data/../etc/shadow"));
+    assert!(m("fs:/etc", "fs://etc/./shadow"));
+    assert!(m("fs:/etc", "fs:/../../etc/shadow"));
+    assert!(!m("fs:/data/out", "fs:/data/out/../output"));
+    assert!(m("fs:/data/out", "fs:/data/tmp/../out/x.json"));
+    assert!(m("fs:/data/out/", "fs:/data/out"));
+
+    // A relative path above its start could be anywhere.
+    assert!(m("fs:/etc", "fs:../etc/shadow"));
+    assert!(m("fs:data/out", "fs:data/../../x"));
+    assert!(!m("fs:data/out", "fs:data/tmp/../output"));
+}
+
+#[test]
+fn deny_rules_cannot_be_bypassed_with_dot_segments() {
+    let policy = policy("deny:\n  - { capability: \"fs:/etc\" }\n");
+    let step = Some(&NodeType::Step);
+    let orchestrated = Some(&ExecClass::Orchestrated);
+    let denied = |used: &str| policy.denies(Some("fs_write"), step, orchestrated, &caps(&[used]));
+
+    assert!(denied("fs:/etc/shadow"));
+    assert!(denied("fs:/data/../etc/shadow"));
+    assert!(denied("fs:/data/./../../etc/shadow"));
+    assert!(denied("fs:/etc/"));
+    assert!(denied("fs:../etc/shadow"));
+    assert!(!denied("fs:/data/etc/shadow"));
+    assert!(!denied("fs:/etc/../data/out"));
+}
+
+#[test]
+fn net_scopes_match_hosts_case_insensitively_on_any_port() {
+    let m = |pattern: &str, used: &str| {
+        capability_matches(&Capability::new(pattern), &Capability::new(used))
+    };
+
+    assert!(m("net:*.example.com", "net:api.example.com:443"));
+    assert!(m("net:*.example.com", "net:API.Example.com"));
+    assert!(m("net:api.example.com", "net:api.example.com:8443"));
+    assert!(m("net:API.example.com:443", "net:api.example.com:443"));
+    assert!(!m("net:api.example.com:443", "net:api.example.com:80"));
+    assert!(!m("net:api.example.com", "net:api.example.com.evil:443"));
+    assert!(!m("net:*.example.com", "net:example.com:443"));
+
+    // A node without a port may use the pattern's port too.
+    assert!(m("net:api.example.com:443", "net:api.example.com"));
+}
+
+#[test]
+fn net_deny_rules_cannot_be_bypassed_with_port_or_case() {
+    let policy = policy(
+        "deny:\n  - { capability: \"net:*.example.com\" }\n  - { capability: \"net:db.internal\" }\n",
+    );
+    let step = Some(&NodeType::Step);
+    let orchestrated = Some(&ExecClass::Orchestrated);
+    let denied =
+        |used: &str| policy.denies(Some("net_external"), step, orchestrated, &caps(&[used]));
+
+    assert!(denied("net:api.example.com"));
+    assert!(denied("net:api.example.com:443"));
+    assert!(denied("net:API.example.com"));
+    assert!(denied("net:API.EXAMPLE.COM:8080"));
+    assert!(denied("net:DB.Internal:5432"));
+    assert!(!denied("net:api.example.org:443"));
+}
+
+#[test]
+fn combined_effect_capability_and_exec_class_rule_raises_tier() {
+    let policy = policy(
+        "trust_overrides:\n  - match: { effect_prefix: fs_write, capability: \"fs:/data/*\", exec_class: orchestrated }\n    set_tier: human_required\n",
+    );
+
+    assert_eq!(
+        tier(
+            &policy,
+            "fs_write",
+            ExecClass::Orchestrated,
+            &["fs:/data/out/a.json"]
+        ),
+        TrustTier::HumanRequired
+    );
+    // Every condition must hold.
+    assert_eq!(
+        tier(
+            &policy,
+            "fs_write",
+            ExecClass::Orchestrated,
+            &["fs:/tmp/a.json"]
+        ),
+        TrustTier::AiAutonomous
+    );
+    assert_eq!(
+        tier(
+            &policy,
+            "fs_write",
+            ExecClass::RealtimeSafe,
+            &["fs:/data/out/a.json"]
+        ),
+        TrustTier::AiAutonomous
+    );
+    assert_eq!(
+        tier(
+            &policy,
+            "net_external",
+            ExecClass::Orchestrated,
+            &["fs:/data/out/a.json"]
+        ),
+        TrustTier::AiAutonomous
+    );
+    assert_eq!(
+        tier(&policy, "fs_write", ExecClass::Orchestrated, &[]),
+        TrustTier::AiAutonomous
+    );
+}
+
+#[test]
+fn deny_rules_match_on_capability_and_exec_class() {
+    let policy = policy(
+        "deny:\n  - { capability: \"net:*.untrusted.example\" }\n  - { effect_prefix: fs_write, exec_class: realtime_safe }\n",
+    );
+    let step = Some(&NodeType::Step);
+    let orchestrated = Some(&ExecClass::Orchestrated);
+
+    assert!(policy.denies(
+        Some("net_external"),
+        step,
+        orchestrated,
+        &caps(&["net:api.example.com", "net:x.untrusted.example"]),
+    ));
+    assert!(!policy.denies(
+        Some("net_external"),
+        step,
+        orchestrated,
+        &caps(&["net:api.example.com"]),
+    ));
+    assert!(policy.denies(Some("fs_write"), step, Some(&ExecClass::RealtimeSafe), &[]));
+    assert!(!policy.denies(Some("fs_write"), step, orchestrated, &[]));
+}
+
+#[test]
+fn capability_pattern_needs_a_known_class() {
+    let err = compile_policy(&format!(
+        "{}deny:\n  - {{ capability: \"teleport:*\" }}\n",
+        BASE
+    ))
+    .unwrap_err();
+    assert_eq!(
+        err.kind,
+        PolicyErrorKind::UnknownCapability("teleport:*".to_string())
+    );
+    assert_eq!(err.line, Some(6));
+
+    let err =
+        compile_policy(&format!("{}deny:\n  - {{ capability: \"fs:\" }}\n", BASE)).unwrap_err();
+    assert_eq!(err.kind, PolicyErrorKind::EmptyField("capability"));
+}
@@ -0,0 +1,380 @@
+use adr_core::Effect;
+use adr_layer2::policy::{
+    FreezeTrigger, KillSwitchChannel, LogLevel, MerkleRootHolder, TimeSource,
+};
+use adr_layer2::policy_compiler::{compile_policy, policy_hash, PolicyErrorKind};
+use adr_layer2::types::{Capability, TrustTier};
+use std::time::Duration;
+
+const MEDICAL: &str = r#"
+domain: medical
+version: "1.0"
+trust_overrides:
+  - match: { effect_prefix: "fs_write" }
+    set_tier: human_required
+    downgrade_forbidden: true
+freeze_triggers:
+  - contract_failure
+  - cap_scope_hash_mismatch
+kill_switch:
+  require_physical_channel: true
+  channels:
+    - unix_signal
+    - hardware_gpio: { pin: 17 }
+  watchdog_timer: 500ms
+audit:
+  log_level: full
+  merkle_root_holder: multi_party
+  signers:
+    - role: operator
+    - role: regulator
+      id: bfarm
+    - role: auditor
+  merkle_anchor_interval: 5m
+  time_source: hardware_rtc
+allowed_capabilities: [fs_write]
+minimum_trust_tier: ai_proposed
+allowed_effects: [none, fs_write]
+"#;
+
+#[test]
+fn compiles_full_medical_policy() {
+    let policy = compile_policy(MEDICAL).expect("policy should compile");
+
+    assert_eq!(policy.domain, "medical");
+    assert_eq!(policy.version, "1.0");
+    assert_eq!(policy.trust_overrides.len(), 1);
+    assert_eq!(policy.trust_overrides[0].set_tier, TrustTier::HumanRequired);
+    assert!(policy.trust_overrides[0].downgrade_forbidden);
+    assert!(!policy.trust_overrides[0].immutable);
+    assert_eq!(
+        policy.freeze_triggers,
+        vec![
+            FreezeTrigger::ContractFailure,
+            FreezeTrigger::CapScopeHashMismatch
+        ]
+    );
+
+    assert!(policy.requires_physical_kill_switch());
+    assert!(matches!(
+        policy.kill_switch.channels[0],
+        KillSwitchChannel::UnixSignal
+    ));
+    assert!(matches!(
+        policy.kill_switch.channels[1],
+        KillSwitchChannel::HardwareGpio { pin: 17 }
+    ));
+    assert_eq!(
+        policy.kill_switch.watchdog_timer,
+        Some(Duration::from_millis(500))
+    );
+
+    assert!(matches!(policy.audit.log_level, LogLevel::Full));
+    assert!(matches!(policy.audit.time_source, TimeSource::HardwareRtc));
+    assert_eq!(
+        policy.audit.merkle_anchor_interval,
+        Duration::from_secs(300)
+    );
+    match &policy.audit.merkle_root_holder {
+        MerkleRootHolder::MultiParty { signers } => {
+            let roles: Vec<&str> = signers.iter().map(|s| s.role.as_str()).collect();
+            assert_eq!(roles, vec!["operator", "regulator", "auditor"]);
+            assert_eq!(signers[1].id.as_deref(), Some("bfarm"));
+        }
+        other => panic!("expected MultiParty, got {:?}", other),
+    }
+
+    assert_eq!(
+        policy.allowed_capabilities,
+        vec![Capability::new("fs_write")]
+    );
+    assert_eq!(policy.minimum_trust_tier, Some(TrustTier::AiProposed));
+    assert_eq!(
+        policy.allowed_effects,
+        Some(vec![Effect::None, Effect::FsWrite])
+    );
+    assert_eq!(policy.policy_hash, policy_hash(MEDICAL));
+    assert_eq!(policy.policy_hash.len(), 64);
+}
+
+#[test]
+fn minimal_policy_uses_defaults() {
+    let src = "domain: test\nversion: \"0.1\"\nkill_switch: {}\naudit: {}\n";
+    let policy = compile_policy(src).expect("policy should compile");
+
+    assert!(policy.trust_overrides.is_empty());
+    assert!(policy.freeze_triggers.is_empty());
+    assert!(matches!(
+        policy.audit.merkle_root_holder,
+        MerkleRootHolder::Local
+    ));
+    assert!(policy.audit.tamper_evident);
+    assert_eq!(policy.kill_switch.watchdog_timer, None);
+    assert_eq!(policy.allowed_effects, None);
+}
+
+#[test]
+fn policy_hash_ignores_line_endings_and_trailing_whitespace() {
+    let unix = "domain: test\nversion: \"1\"\n";
+    let windows = "domain: test  \r\nversion: \"1\"\r\n\r\n";
+    let changed = "domain: test\nversion: \"2\"\n";
+
+    assert_eq!(policy_hash(unix), policy_hash(windows));
+    assert_ne!(policy_hash(unix), policy_hash(changed));
+}
+
+#[test]
+fn unknown_freeze_trigger_reports_line() {
+    let src = "domain: test\nversion: \"1\"\nfreeze_triggers:\n  - contract_failure\n  - solar_flare\nkill_switch: {}\naudit: {}\n";
+    let err = compile_policy(src).unwrap_err();
+
+    assert_eq!(err.line, Some(5));
+    assert!(matches!(err.kind, PolicyErrorKind::Syntax(_)));
+    assert!(err.to_string().starts_with("policy.yaml:5:"));
+}
+
+#[test]
+fn unknown_effect_reports_line() {
+    let src = "domain: test\nversion: \"1\"\nkill_switch: {}\naudit: {}\nallowed_effects:\n  - fs_write\n  - teleport\n";
+    let err = compile_policy(src).unwrap_err();
+
+    assert_eq!(err.line, Some(7));
+    assert_eq!(
+        err.kind,
+        PolicyErrorKind::Syntax("allowed_effects[1]: unknown effect `teleport`".to_string())
+    );
+}
+
+#[test]
+fn misspelled_match_key_is_rejected() {
+    let src = "domain: test\nversion: \"1\"\ntrust_overrides:\n  - match: { effect_prefx: fs_write }\n    set_tier: human_required\nkill_switch: {}\naudit: {}\n";
+    let err = compile_policy(src).unwrap_err();
+
+    assert_eq!(err.line, Some(4));
+    assert!(matches!(err.kind, PolicyErrorKind::Syntax(_)));
+}
+
+#[test]
+fn unknown_capability_is_rejected_with_line() {
+    let src = "domain: test\nversion: \"1\"\nkill_switch: {}\naudit: {}\nallowed_capabilities:\n  - fs_write\n  - teleport\n";
+    let err = compile_policy(src).unwrap_err();
+
+    assert_eq!(err.line, Some(7));
+    assert_eq!(
+        err.kind,
+        PolicyErrorKind::UnknownCapability("teleport".to_string())
+    );
+}
+
+#[test]
+fn invalid_duration_is_rejected() {
+    let src = "domain: test\nversion: \"1\"\nkill_switch:\n  watchdog_timer: soon\naudit: {}\n";
+    let err = compile_policy(src).unwrap_err();
+
+    assert_eq!(err.line, Some(4));
+    assert_eq!(
+        err.kind,
+        PolicyErrorKind::InvalidDuration("soon".to_string())
+    );
+}
+
+#[test]
+fn multi_party_without_signers_is_rejected() {
+    let src = "domain: test\nversion: \"1\"\nkill_switch: {}\naudit:\n  merkle_root_holder: multi_party\n";
+    let err = compile_policy(src).unwrap_err();
+
+    assert_eq!(err.line, Some(5));
+    assert_eq!(err.kind, PolicyErrorKind::MissingSigners);
+}
+
+#[test]
+fn certifier_requires_id() {
+    let src =
+        "domain: test\nversion: \"1\"\nkill_switch: {}\naudit:\n  merkle_root_holder: certifier\n";
+    let err = compile_policy(src).unwrap_err();
+
+    assert_eq!(err.kind, PolicyErrorKind::MissingCertifierId);
+
+    let ok = "domain: test\nversion: \"1\"\nkill_switch: {}\naudit:\n  merkle_root_holder: certifier\n  certifier_id: tuv\n";
+    let policy = compile_policy(ok).expect("policy should compile");
+    assert!(matches!(
+        policy.audit.merkle_root_holder,
+        MerkleRootHolder::Certifier { ref id } if id == "tuv"
+    ));
+}
+
+#[test]
+fn duplicate_freeze_trigger_is_rejected() {
+    let src = "domain: test\nversion: \"1\"\nfreeze_triggers:\n  - contract_failure\n  - contract_failure\nkill_switch: {}\naudit: {}\n";
+    let err = compile_policy(src).unwrap_err();
+
+    assert_eq!(err.line, Some(5));
+    assert_eq!(
+        err.kind,
+        PolicyErrorKind::Duplicate("contract_failure".to_string())
+    );
+}
+
+#[test]
+fn empty_domain_is_rejected() {
+    let src = "domain: \"\"\nversion: \"1\"\nkill_switch: {}\naudit: {}\n";
+    let err = compile_policy(src).unwrap_err();
+
+    assert_eq!(err.line, Some(1));
+    assert_eq!(err.kind, PolicyErrorKind::EmptyField("domain"));
+}
+
+#[test]
+fn missing_section_is_a_syntax_error() {
+    let src = "domain: test\nversion: \"1\"\naudit: {}\n";
+    let err = compile_policy(src).unwrap_err();
+
+    match err.kind {
+        PolicyErrorKind::Syntax(msg) => assert!(msg.contains("kill_switch")),
+        other => panic!("expected Syntax, got {:?}", other),
+    }
+}
+
+#[test]
+fn multi_party_policy_requires_every_signer() {
+    use adr_core::audit::{
+        merkle_root, verify_root_signatures, RootSigner, SealedBatch, SigningKey,
+    };
+
+    let policy = compile_policy(MEDICAL).unwrap();
+    let requirement = policy.audit.merkle_root_holder.signature_requirement().unwrap();
+    assert_eq!(requirement.threshold, 3);
+
+    let signers: Vec<RootSigner> = requirement
+        .signers
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            RootSigner::new(r.role.clone(), r.id.clone(), SigningKey::from_bytes(&[i as u8; 32]))
+        })
+        .collect();
+    let trusted: Vec<_> = signers.iter().map(RootSigner::trusted).collect();
+    let batch = SealedBatch {
+        index: 0,
+        entry_hashes: vec![],
+        root: merkle_root(&[]),
+        anchor_hash: "00".repeat(32),
+    };
+
+    let all: Vec<_> = signers.iter().map(|s| s.sign(&batch)).collect();
+    assert!(verify_root_signatures(&batch, &all, &trusted, &requirement).satisfied);
+
+    let report = verify_root_signatures(&batch, &all[..2], &trusted, &requirement);
+    assert!(!report.satisfied);
+    assert_eq!(report.missing_roles, vec!["auditor"]);
+}
+
+#[test]
+fn local_root_holder_needs_no_signatures() {
+    let src = "domain: test\nversion: \"1\"\nkill_switch: {}\naudit: {}\n";
+    let policy = compile_policy(src).unwrap();
+
+    assert!(policy.audit.merkle_root_holder.signature_requirement().is_none());
+}
+
+#[test]
+fn checkpoint_before_compiles_and_rejects_duplicates() {
+    let base = "domain: test\nversion: \"1\"\nkill_switch: {}\naudit: {}\n";
+
+    let policy = compile_policy(&format!("{}checkpoint_before: [fs_write]\n", base)).unwrap();
+    assert_eq!(policy.checkpoint_before, vec![adr_core::Effect::FsWrite]);
+    assert!(compile_policy(base).unwrap().checkpoint_before.is_empty());
+
+    let err = compile_policy(&format!("{}checkpoint_before: [fs_write, fs_write]\n", base))
+        .unwrap_err();
+    assert_eq!(err.kind, PolicyErrorKind::Duplicate("fs_write".to_string()));
+}
+
+#[test]
+fn approval_timeout_compiles_with_each_action() {
+    let base = "domain: test\nversion: \"1\"\nkill_switch: {}\naudit: {}\n";
+    assert!(compile_policy(base).unwrap().approval_timeout.is_none());
+
+    let policy = compile_policy(&format!(
+        "{}approval_timeout:\n  after: 15m\n  on_timeout: escalate\n  escalate_to: safety_officer\n",
+        base
+    ))
+    .unwrap();
+    assert_eq!(
+        policy.approval_timeout,
+        Some(adr_core::ApprovalTimeout {
+            after: std::time::Duration::from_secs(900),
+            action: adr_core::TimeoutAction::Escalate {
+                role: "safety_officer".to_string()
+            },
+        })
+    );
+
+    let policy = compile_policy(&format!(
+        "{}approval_timeout: {{ after: 30s, on_timeout: soft_stop }}\n",
+        base
+    ))
+    .unwrap();
+    assert_eq!(
+        policy.approval_timeout.unwrap().action,
+        adr_core::TimeoutAction::SoftStop
+    );
+}
+
+#[test]
+fn approval_timeout_escalation_needs_a_role() {
+    let base = "domain: test\nversion: \"1\"\nkill_switch: {}\naudit: {}\n";
+
+    let err = compile_policy(&format!(
+        "{}approval_timeout:\n  after: 15m\n  on_timeout: escalate\n",
+        base
+    ))
+    .unwrap_err();
+    assert_eq!(err.kind, PolicyErrorKind::MissingEscalationRole);
+    assert_eq!(err.line, Some(7));
+
+    let err = compile_policy(&format!(
+        "{}approval_timeout:\n  after: 15m\n  on_timeout: reject\n  escalate_to: safety_officer\n",
+        base
+    ))
+    .unwrap_err();
+    assert!(matches!(err.kind, PolicyErrorKind::Syntax(_)));
+}
+
+#[test]
+fn require_approvals_compiles_into_quorum() {
+    let src = "domain: test\nversion: \"1\"\nkill_switch: {}\naudit: {}\ntrust_overrides:\n  - match: { effect_prefix: \"net_external\" }\n    set_tier: human_required\n    require_approvals: { count: 2, roles: [operator, safety_officer] }\n";
+
+    let policy = compile_policy(src).unwrap();
+    assert_eq!(
+        policy.trust_overrides[0].require_approvals,
+        Some(adr_core::Quorum {
+            count: 2,
+            roles: vec!["operator".to_string(), "safety_officer".to_string()],
+        })
+    );
+    assert_eq!(
+        policy
+            .required_approvals(Some("net_external"), None, None, &[])
+            .map(|q| q.count),
+        Some(2)
+    );
+    assert_eq!(policy.required_approvals(Some("fs_write"), None, None, &[]), None);
+}
+
+#[test]
+fn require_approvals_rejects_zero_count_and_duplicate_roles() {
+    let base = "domain: test\nversion: \"1\"\nkill_switch: {}\naudit: {}\ntrust_overrides:\n  - match: {}\n    set_tier: human_required\n";
+
+    let err = compile_policy(&format!("{}    require_approvals: {{ count: 0 }}\n", base))
+        .unwrap_err();
+    assert_eq!(err.kind, PolicyErrorKind::InvalidQuorum);
+    assert_eq!(err.line, Some(8));
+
+    let err = compile_policy(&format!(
+        "{}    require_approvals: {{ count: 2, roles: [operator, operator] }}\n",
+        base
+    ))
+    .unwrap_err();
+    assert_eq!(err.kind, PolicyErrorKind::Duplicate("operator".to_string()));
+}